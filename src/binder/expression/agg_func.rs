@@ -1,7 +1,17 @@
-use arrow::datatypes::DataType;
-use sqlparser::ast::{Function, FunctionArg, FunctionArgExpr, FunctionArguments};
+use std::sync::Arc;
 
-use crate::binder::{expression::BoundExpr, BindError, Binder};
+use arrow::datatypes::{DataType, Field};
+use sqlparser::ast::{
+    Function, FunctionArg, FunctionArgExpr, FunctionArgumentClause, FunctionArguments,
+};
+
+use crate::{
+    binder::{
+        expression::{BoundExpr, BoundOrderBy},
+        BindError, Binder,
+    },
+    types::ScalarValue,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AggFunc {
@@ -9,6 +19,17 @@ pub enum AggFunc {
     Sum,
     Min,
     Max,
+    /// `GROUPING(args)` returns a bit mask telling which of its arguments are aggregated
+    /// away (not part of the grouping set) for the current row.
+    Grouping,
+    /// `string_agg(value, delimiter [ORDER BY ...])`
+    StringAgg,
+    /// `array_agg(value [ORDER BY ...])`
+    ArrayAgg,
+    /// `percentile_cont(fraction) WITHIN GROUP (ORDER BY value)`
+    PercentileCont,
+    /// `percentile_disc(fraction) WITHIN GROUP (ORDER BY value)`
+    PercentileDisc,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -16,6 +37,11 @@ pub struct BoundAggFunc {
     pub func: AggFunc,
     pub exprs: Vec<BoundExpr>,
     pub return_type: DataType,
+    /// `FILTER (WHERE ...)`, only the rows it evaluates to true are aggregated.
+    pub filter: Option<Box<BoundExpr>>,
+    /// The order in which rows are fed into the aggregation, from either the
+    /// ORDER BY in the argument list or `WITHIN GROUP (ORDER BY ...)`.
+    pub order_by: Vec<BoundOrderBy>,
 }
 
 impl Binder {
@@ -38,7 +64,7 @@ impl Binder {
             };
             match arg {
                 FunctionArgExpr::Expr(expr) => {
-                    let expr = self.bind_expr(&expr)?;
+                    let expr = self.bind_expr(expr)?;
                     args.push(expr);
                 }
                 FunctionArgExpr::QualifiedWildcard(_) => todo!(),
@@ -46,29 +72,126 @@ impl Binder {
             }
         }
 
-        let expr = match func.name.to_string().to_lowercase().as_str() {
-            "count" => BoundAggFunc {
-                func: AggFunc::Count,
-                exprs: args.clone(),
-                return_type: DataType::Int64,
-            },
-            "sum" => BoundAggFunc {
-                func: AggFunc::Sum,
-                exprs: args.clone(),
-                return_type: args[0].return_type().unwrap(),
-            },
-            "min" => BoundAggFunc {
-                func: AggFunc::Min,
-                exprs: args.clone(),
-                return_type: args[0].return_type().unwrap(),
-            },
-            "max" => BoundAggFunc {
-                func: AggFunc::Max,
-                exprs: args.clone(),
-                return_type: args[0].return_type().unwrap(),
-            },
+        let mut order_by = vec![];
+        for clause in &list.clauses {
+            match clause {
+                FunctionArgumentClause::OrderBy(exprs) => {
+                    for expr in exprs {
+                        order_by.push(self.bind_order_by(expr)?);
+                    }
+                }
+                _ => return Err(BindError::InvalidAggFunc(func.to_string())),
+            }
+        }
+        let within_group = func
+            .within_group
+            .iter()
+            .map(|expr| self.bind_order_by(expr))
+            .collect::<Result<Vec<_>, _>>()?;
+        let filter = match &func.filter {
+            Some(expr) => {
+                let filter = self.bind_expr(expr)?;
+                match filter.return_type() {
+                    // a null filter passes no rows, like a false one
+                    Some(DataType::Boolean | DataType::Null) => {
+                        Some(Box::new(filter.cast_to(&DataType::Boolean)))
+                    }
+                    data_type => {
+                        let data_type = data_type.map(|t| t.to_string()).unwrap_or_default();
+                        return Err(BindError::InvalidFilterType(data_type));
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let name = func.name.to_string().to_lowercase();
+        let is_ordered_set = matches!(name.as_str(), "percentile_cont" | "percentile_disc");
+        if is_ordered_set != !within_group.is_empty() || (is_ordered_set && !order_by.is_empty()) {
+            return Err(BindError::InvalidAggFunc(func.to_string()));
+        }
+
+        if args.is_empty() {
+            return Err(BindError::InvalidAggFunc(func.to_string()));
+        }
+        // the argument types the accumulators of sum, min and max can add up and compare
+        let arg_type = args[0].return_type().unwrap();
+        let supported = match name.as_str() {
+            "sum" => matches!(
+                arg_type,
+                DataType::Int32 | DataType::Int64 | DataType::Float64
+            ),
+            "min" | "max" => matches!(
+                arg_type,
+                DataType::Int32 | DataType::Int64 | DataType::Float64 | DataType::Utf8
+            ),
+            _ => true,
+        };
+        if !supported {
+            return Err(BindError::UnsupportedAggArgument(
+                name,
+                arg_type.to_string(),
+            ));
+        }
+
+        let (func, return_type) = match name.as_str() {
+            "count" => (AggFunc::Count, DataType::Int64),
+            "sum" => (AggFunc::Sum, arg_type),
+            "min" => (AggFunc::Min, arg_type),
+            "max" => (AggFunc::Max, arg_type),
+            "grouping" => (AggFunc::Grouping, DataType::Int32),
+            "string_agg" => {
+                if args.len() != 2 {
+                    return Err(BindError::InvalidAggFunc(func.to_string()));
+                }
+                (AggFunc::StringAgg, DataType::Utf8)
+            }
+            "array_agg" => {
+                let field = Field::new("item", arg_type, true);
+                (AggFunc::ArrayAgg, DataType::List(Arc::new(field)))
+            }
+            "percentile_cont" | "percentile_disc" => {
+                if args.len() != 1 || within_group.len() != 1 {
+                    return Err(BindError::InvalidAggFunc(func.to_string()));
+                }
+                // the fraction is the same for all rows, so it is checked here once
+                let fraction = match &args[0] {
+                    BoundExpr::Constant(ScalarValue::Int32(Some(v))) => Some(*v as f64),
+                    BoundExpr::Constant(ScalarValue::Int64(Some(v))) => Some(*v as f64),
+                    BoundExpr::Constant(ScalarValue::Float64(Some(v))) => Some(*v),
+                    _ => None,
+                };
+                if !fraction.is_some_and(|f| (0.0..=1.0).contains(&f)) {
+                    return Err(BindError::InvalidPercentile(func.to_string()));
+                }
+                let order_type = within_group[0].expr.return_type().unwrap();
+                if name == "percentile_cont"
+                    && !matches!(
+                        order_type,
+                        DataType::Int32 | DataType::Int64 | DataType::Float64
+                    )
+                {
+                    return Err(BindError::UnsupportedAggArgument(
+                        name,
+                        order_type.to_string(),
+                    ));
+                }
+                order_by = within_group;
+                if name == "percentile_cont" {
+                    (AggFunc::PercentileCont, DataType::Float64)
+                } else {
+                    let return_type = order_by[0].expr.return_type().unwrap();
+                    (AggFunc::PercentileDisc, return_type)
+                }
+            }
             _ => unimplemented!("not implemented agg fun {}", func.name),
         };
-        Ok(BoundExpr::AggFunc(expr))
+        Ok(BoundExpr::AggFunc(BoundAggFunc {
+            func,
+            exprs: args,
+            return_type,
+            filter,
+            order_by,
+        }))
     }
 }
@@ -1,13 +1,16 @@
 use arrow::datatypes::DataType;
 use itertools::Itertools;
-use sqlparser::ast::{Expr, Ident};
+use sqlparser::ast::{Expr, Ident, OrderByExpr};
 
 use crate::{
     binder::{
-        expression::{agg_func::BoundAggFunc, binary_op::BoundBinaryOp},
+        expression::{
+            agg_func::{AggFunc, BoundAggFunc},
+            binary_op::BoundBinaryOp,
+        },
         BindError, Binder,
     },
-    catalog::ColumnCatalog,
+    catalog::{ColumnCatalog, ColumnDesc},
     types::ScalarValue,
 };
 pub mod agg_func;
@@ -22,6 +25,7 @@ pub enum BoundExpr {
     BinaryOp(BoundBinaryOp),
     TypeCast(BoundTypeCast),
     AggFunc(BoundAggFunc),
+    IsNull(BoundIsNull),
 }
 
 impl BoundExpr {
@@ -35,8 +39,70 @@ impl BoundExpr {
             BoundExpr::BinaryOp(binary_op) => binary_op.return_type.clone(),
             BoundExpr::TypeCast(tc) => Some(tc.cast_type.clone()),
             BoundExpr::AggFunc(agg) => Some(agg.return_type.clone()),
+            BoundExpr::IsNull(_) => Some(DataType::Boolean),
         }
     }
+
+    /// Whether the expr may evaluate to null over the input columns it is evaluated on.
+    pub fn nullable(&self, input: &[ColumnCatalog]) -> bool {
+        match self {
+            BoundExpr::Constant(value) => value.is_null(),
+            BoundExpr::ColumnRef(column_ref) => column_ref.column_catalog.desc.nullable,
+            BoundExpr::InputRef(input_ref) => input
+                .get(input_ref.index)
+                .is_none_or(|column| column.desc.nullable),
+            BoundExpr::BinaryOp(binary_op) => {
+                binary_op.left.nullable(input) || binary_op.right.nullable(input)
+            }
+            // a cast of a value which the type cannot hold is null, unlike a widening cast
+            BoundExpr::TypeCast(tc) => {
+                tc.expr.nullable(input)
+                    || !matches!(
+                        (tc.expr.return_type(), &tc.cast_type),
+                        (Some(DataType::Int32), DataType::Int64 | DataType::Float64)
+                            | (Some(DataType::Int64), DataType::Float64)
+                            | (Some(_), DataType::Utf8)
+                    )
+            }
+            // the other aggregations of no rows are null
+            BoundExpr::AggFunc(agg) => !matches!(agg.func, AggFunc::Count | AggFunc::Grouping),
+            BoundExpr::IsNull(_) => false,
+        }
+    }
+
+    /// The column a plan node outputs for the expr over its input columns, which is the
+    /// column the expr refers to, one named after the aggregation, or one named `?column?`
+    /// like PostgreSQL names it.
+    pub fn output_column(&self, input: &[ColumnCatalog]) -> ColumnCatalog {
+        let name = match self {
+            BoundExpr::InputRef(input_ref) if input_ref.index < input.len() => {
+                return input[input_ref.index].clone();
+            }
+            BoundExpr::ColumnRef(column_ref) => return column_ref.column_catalog.clone(),
+            BoundExpr::AggFunc(agg) => format!("{:?}", agg.func).to_lowercase(),
+            _ => "?column?".to_string(),
+        };
+        ColumnCatalog {
+            id: name.clone(),
+            desc: ColumnDesc {
+                name,
+                data_type: self.return_type().unwrap_or(DataType::Null),
+                nullable: self.nullable(input),
+                default: None,
+            },
+        }
+    }
+
+    /// Cast the expr to `data_type`, unless it already returns the type.
+    pub fn cast_to(self, data_type: &DataType) -> BoundExpr {
+        if self.return_type().as_ref() == Some(data_type) {
+            return self;
+        }
+        BoundExpr::TypeCast(BoundTypeCast {
+            expr: Box::new(self),
+            cast_type: data_type.clone(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -58,22 +124,54 @@ pub struct BoundTypeCast {
     pub cast_type: DataType,
 }
 
+/// `expr IS NULL`, or `expr IS NOT NULL` when negated
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundIsNull {
+    pub expr: Box<BoundExpr>,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundOrderBy {
+    pub expr: BoundExpr,
+    pub asc: bool,
+    pub nulls_first: bool,
+}
+
 impl Binder {
     /// bind sqlparser Expr into BoundExpr
     pub fn bind_expr(&mut self, expr: &Expr) -> Result<BoundExpr, BindError> {
         match expr {
-            Expr::Identifier(ident) => self.bind_column_ref_from_identifiers(&[ident.clone()]),
+            Expr::Identifier(ident) => {
+                self.bind_column_ref_from_identifiers(std::slice::from_ref(ident))
+            }
             Expr::CompoundIdentifier(idents) => self.bind_column_ref_from_identifiers(idents),
             Expr::BinaryOp { left, op, right } => self.bind_binary_op(left, op, right),
+            Expr::Nested(expr) => self.bind_expr(expr),
+            Expr::IsNull(e) | Expr::IsNotNull(e) => Ok(BoundExpr::IsNull(BoundIsNull {
+                expr: Box::new(self.bind_expr(e)?),
+                negated: matches!(expr, Expr::IsNotNull(_)),
+            })),
             Expr::UnaryOp { op: _, expr: _ } => todo!(),
-            Expr::Value(v) => Ok(BoundExpr::Constant(
-                v.value.clone().into_string().unwrap().into(),
-            )),
+            Expr::Value(v) => Ok(BoundExpr::Constant((&v.value).into())),
             Expr::Function(func) => self.bind_agg_func(func),
             _ => todo!("unsupported expr: {expr:?}"),
         }
     }
 
+    /// bind sqlparser OrderByExpr, nulls are sorted as if larger than any value by default
+    /// like PostgreSQL, i.e. NULLS LAST for ASC and NULLS FIRST for DESC.
+    pub fn bind_order_by(&mut self, order_by: &OrderByExpr) -> Result<BoundOrderBy, BindError> {
+        let expr = self.bind_expr(&order_by.expr)?;
+        let asc = order_by.options.asc.unwrap_or(true);
+        let nulls_first = order_by.options.nulls_first.unwrap_or(!asc);
+        Ok(BoundOrderBy {
+            expr,
+            asc,
+            nulls_first,
+        })
+    }
+
     /// bind sqlparser Identifier into BoundExpr
     ///
     /// Identifier types:
@@ -98,7 +196,7 @@ impl Binder {
 
         if let Some(table) = table_name {
             let table_catalog = self.context.tables.get(table).unwrap();
-            let column_catalog = table_catalog.get_column_by_name(&column_name).unwrap();
+            let column_catalog = table_catalog.get_column_by_name(column_name).unwrap();
             Ok(BoundExpr::ColumnRef(BoundColumnRef { column_catalog }))
         } else {
             let mut got_column = None;
@@ -3,15 +3,18 @@ pub mod expression;
 pub mod statement;
 pub mod table;
 
-use sqlparser::ast::{Ident, Statement};
+use sqlparser::ast::{Ident, ObjectType, Statement};
 
 use crate::{
     binder::statement::BoundStatement,
     catalog::{RootCatalogRef, TableCatalog},
+    function::TableFunctions,
+    storage::StorageError,
 };
 
 pub struct Binder {
     catalog: RootCatalogRef,
+    table_functions: TableFunctions,
     context: BinderContext,
 }
 
@@ -26,16 +29,69 @@ impl Binder {
     pub fn new(catalog: RootCatalogRef) -> Self {
         Self {
             catalog,
+            table_functions: TableFunctions::default(),
             context: BinderContext::default(),
         }
     }
 
+    /// call the table functions in FROM instead of the builtin ones
+    pub fn with_table_functions(mut self, table_functions: TableFunctions) -> Self {
+        self.table_functions = table_functions;
+        self
+    }
+
     pub fn bind(&mut self, stmt: &Statement) -> Result<BoundStatement, BindError> {
         match stmt {
             Statement::Query(query) => {
                 let bound_select = self.bind_select(query)?;
                 Ok(BoundStatement::Select(bound_select))
             }
+            Statement::CreateTable(create_table) => Ok(BoundStatement::CreateTable(
+                self.bind_create_table(create_table)?,
+            )),
+            Statement::Insert(insert) => Ok(BoundStatement::Insert(self.bind_insert(insert)?)),
+            Statement::Update {
+                table,
+                assignments,
+                from: None,
+                selection,
+                returning: None,
+                or: None,
+                limit: None,
+            } => Ok(BoundStatement::Update(self.bind_update(
+                table,
+                assignments,
+                selection.as_ref(),
+            )?)),
+            Statement::Delete(delete) => Ok(BoundStatement::Delete(self.bind_delete(delete)?)),
+            Statement::Drop {
+                object_type: ObjectType::Table,
+                if_exists,
+                names,
+                ..
+            } => Ok(BoundStatement::DropTable(
+                self.bind_drop_table(names, *if_exists)?,
+            )),
+            Statement::Copy {
+                source,
+                to,
+                target,
+                options,
+                legacy_options,
+                values,
+            } if legacy_options.is_empty() && values.is_empty() => Ok(BoundStatement::Copy(
+                self.bind_copy(source, *to, target, options)?,
+            )),
+            Statement::Analyze {
+                table_name,
+                partitions: None,
+                columns,
+                cache_metadata: false,
+                noscan: false,
+                ..
+            } => Ok(BoundStatement::Analyze(
+                self.bind_analyze(table_name, columns)?,
+            )),
             _ => Err(BindError::UnsupportedStmt(format!("{stmt:?}"))),
         }
     }
@@ -47,22 +103,60 @@ pub enum BindError {
     UnsupportedStmt(String),
     #[error("invalid table {0}")]
     InvalidTable(String),
+    #[error("unsupported table in FROM: {0}")]
+    UnsupportedTableRef(String),
+    #[error("table {0} already exists")]
+    TableExists(String),
     #[error("invalid table name: {0:?}")]
     InvalidTableName(Vec<Ident>),
     #[error("invalid column {0}")]
     InvalidColumn(String),
+    #[error("column {0} specified more than once")]
+    DuplicateColumn(String),
+    #[error("unsupported data type {0}")]
+    UnsupportedDataType(String),
+    #[error("INSERT has {0} target columns but {1} expressions")]
+    InsertColumnsMismatch(usize, usize),
     #[error("binary operator types mismatch: {0} != {1}")]
     BinaryOpTypeMismatch(String, String),
+    #[error("unsupported group by clause: {0}")]
+    UnsupportedGroupBy(String),
+    #[error("column {0} must appear in the GROUP BY clause or be used in an aggregate function")]
+    UngroupedColumn(String),
+    #[error("arguments to GROUPING must be grouping expressions: {0}")]
+    InvalidGroupingArgument(String),
+    #[error("invalid aggregate function call: {0}")]
+    InvalidAggFunc(String),
+    #[error("percentile fraction must be a constant between 0 and 1: {0}")]
+    InvalidPercentile(String),
+    #[error("argument of FILTER must be boolean, not {0}")]
+    InvalidFilterType(String),
+    #[error("function {0} does not support argument type {1}")]
+    UnsupportedAggArgument(String, String),
+    #[error("VALUES lists must all be the same length")]
+    ValuesLengthMismatch,
+    #[error("VALUES types {0} and {1} cannot be matched")]
+    ValuesTypeMismatch(String, String),
+    #[error("invalid table option: {0}")]
+    InvalidTableOption(String),
+    #[error("table function {0} does not exist")]
+    InvalidTableFunction(String),
+    #[error("invalid arguments of table function {0}: {1}")]
+    InvalidFunctionArgs(String, String),
+    #[error("invalid default of column {0}: {1}")]
+    InvalidDefault(String, String),
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
 }
 
 #[cfg(test)]
 mod binder_test {
-    use std::{assert_matches::assert_matches, collections::BTreeMap, sync::Arc};
+    use std::{collections::BTreeMap, sync::Arc};
 
     use arrow::datatypes::DataType;
 
     use crate::{
-        binder::{expression::BoundExpr, statement::BoundStatement, Binder},
+        binder::{expression::BoundExpr, statement::BoundStatement, BindError, Binder},
         catalog::{ColumnCatalog, ColumnDesc, RootCatalog, TableCatalog},
         parser::parse,
     };
@@ -78,6 +172,8 @@ mod binder_test {
                 desc: ColumnDesc {
                     name: "c1".to_string(),
                     data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
                 },
             },
         );
@@ -88,6 +184,8 @@ mod binder_test {
                 desc: ColumnDesc {
                     name: "c2".to_string(),
                     data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
                 },
             },
         );
@@ -97,6 +195,7 @@ mod binder_test {
             name: table_id.clone(),
             columns,
             column_ids,
+            statistics: None,
         };
         catalog.tables.insert(table_id, table_catalog);
         catalog
@@ -111,9 +210,10 @@ mod binder_test {
         match bound_stmt {
             BoundStatement::Select(select) => {
                 assert_eq!(select.select_list.len(), 2);
-                assert_eq!(select.from_table.is_some(), true);
+                assert!(select.from_table.is_some());
                 assert_eq!(select.from_table.unwrap().table_catalog.id, "t1");
             }
+            _ => unreachable!(),
         }
     }
 
@@ -127,8 +227,100 @@ mod binder_test {
         match bound_stmt {
             BoundStatement::Select(select) => {
                 assert_eq!(select.select_list.len(), 1);
-                assert_eq!(select.from_table.is_some(), false);
+                assert!(select.from_table.is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_bind_values_works() {
+        let catalog = build_test_catalog();
+        let mut binder = Binder::new(Arc::new(catalog));
+        let stats = parse("select b from (values (1, NULL), (2.5, 'x')) as t(a, b)").unwrap();
+        let bound_stmt = binder.bind(&stats[0]).unwrap();
+        match bound_stmt {
+            BoundStatement::Select(select) => {
+                let table = select.from_table.unwrap();
+                assert_eq!(table.table_catalog.id, "t");
+                let columns = table.table_catalog.get_all_columns();
+                assert_eq!(columns[0].desc.data_type, DataType::Float64);
+                assert_eq!(columns[1].desc.data_type, DataType::Utf8);
+                assert_eq!(table.values.unwrap().len(), 2);
+                assert_eq!(select.select_list[0].return_type(), Some(DataType::Utf8));
+            }
+            _ => unreachable!(),
+        }
+
+        let stats = parse("values (1, 2), (3)").unwrap();
+        assert!(matches!(
+            binder.bind(&stats[0]),
+            Err(BindError::ValuesLengthMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_bind_create_table_and_insert_works() {
+        let catalog = build_test_catalog();
+        let mut binder = Binder::new(Arc::new(catalog));
+        let stats = parse("create table t2 (a int, b varchar)").unwrap();
+        match binder.bind(&stats[0]).unwrap() {
+            BoundStatement::CreateTable(create_table) => {
+                assert_eq!(create_table.table_name, "t2");
+                assert_eq!(create_table.columns[0].desc.data_type, DataType::Int32);
+                assert_eq!(create_table.columns[1].desc.data_type, DataType::Utf8);
+                assert!(create_table.query.is_none());
+            }
+            _ => unreachable!(),
+        }
+
+        let stats = parse("create table t1 (a int)").unwrap();
+        assert!(matches!(
+            binder.bind(&stats[0]),
+            Err(BindError::TableExists(_))
+        ));
+
+        let stats = parse("insert into t1 (c2) values (1)").unwrap();
+        match binder.bind(&stats[0]).unwrap() {
+            BoundStatement::Insert(insert) => {
+                assert_eq!(insert.table_catalog.id, "t1");
+                // the absent c1 is filled with null
+                assert!(matches!(
+                    insert.source.select_list[0],
+                    BoundExpr::Constant(ref v) if v.is_null()
+                ));
+                assert!(matches!(
+                    insert.source.select_list[1],
+                    BoundExpr::ColumnRef(_)
+                ));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_bind_update_and_delete_works() {
+        let catalog = build_test_catalog();
+        let mut binder = Binder::new(Arc::new(catalog));
+        let stats = parse("update t1 set c2 = c1 + 1 where c1 > 1").unwrap();
+        match binder.bind(&stats[0]).unwrap() {
+            BoundStatement::Update(update) => {
+                assert_eq!(update.table_ref.table_catalog.id, "t1");
+                // the unassigned c1 keeps its value
+                assert!(matches!(update.assignments[0], BoundExpr::ColumnRef(_)));
+                assert!(matches!(update.assignments[1], BoundExpr::BinaryOp(_)));
+                assert!(update.where_clause.is_some());
             }
+            _ => unreachable!(),
+        }
+
+        let stats = parse("delete from t1").unwrap();
+        match binder.bind(&stats[0]).unwrap() {
+            BoundStatement::Delete(delete) => {
+                assert_eq!(delete.table_ref.table_catalog.id, "t1");
+                assert!(delete.where_clause.is_none());
+            }
+            _ => unreachable!(),
         }
     }
 
@@ -140,9 +332,103 @@ mod binder_test {
         let bound_stmt = binder.bind(&stats[0]).unwrap();
         match bound_stmt {
             BoundStatement::Select(select) => {
-                assert_matches!(select.select_list[0], BoundExpr::AggFunc(..));
-                assert_matches!(select.select_list[0], BoundExpr::AggFunc(..));
+                assert!(matches!(select.select_list[0], BoundExpr::AggFunc(..)));
+                assert!(matches!(select.select_list[0], BoundExpr::AggFunc(..)));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_bind_select_rollup_and_cube_works() {
+        let catalog = build_test_catalog();
+        let mut binder = Binder::new(Arc::new(catalog));
+        let stats = parse("select c1, c2, sum(c1) from t1 group by rollup(c1, c2)").unwrap();
+        let bound_stmt = binder.bind(&stats[0]).unwrap();
+        match bound_stmt {
+            BoundStatement::Select(select) => {
+                assert_eq!(select.group_by.len(), 2);
+                assert_eq!(select.grouping_sets, vec![vec![0, 1], vec![0], vec![]]);
+            }
+            _ => unreachable!(),
+        }
+
+        let stats = parse("select c1, c2, sum(c1) from t1 group by c1, cube(c2, c1)").unwrap();
+        let bound_stmt = binder.bind(&stats[0]).unwrap();
+        match bound_stmt {
+            BoundStatement::Select(select) => {
+                assert_eq!(select.group_by.len(), 2);
+                assert_eq!(select.grouping_sets, vec![vec![0, 1], vec![0]]);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_bind_grouping_requires_group_by_argument() {
+        let catalog = build_test_catalog();
+        let mut binder = Binder::new(Arc::new(catalog));
+        let stats = parse("select grouping(c2) from t1 group by rollup(c1)").unwrap();
+        assert!(matches!(
+            binder.bind(&stats[0]),
+            Err(BindError::InvalidGroupingArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_bind_select_requires_grouped_columns() {
+        let catalog = build_test_catalog();
+        let mut binder = Binder::new(Arc::new(catalog));
+        for sql in [
+            "select c2 from t1 group by c1",
+            "select c2, count(c1) from t1",
+            "select c1 + c2 from t1 group by c1",
+        ] {
+            let stats = parse(sql).unwrap();
+            assert!(
+                matches!(binder.bind(&stats[0]), Err(BindError::UngroupedColumn(c)) if c == "c2"),
+                "{sql}"
+            );
+        }
+        let stats = parse("select c1 + 1, sum(c2) + c1 from t1 group by c1").unwrap();
+        assert!(binder.bind(&stats[0]).is_ok());
+    }
+
+    #[test]
+    fn test_bind_agg_func_with_filter_and_order_by_works() {
+        let catalog = build_test_catalog();
+        let mut binder = Binder::new(Arc::new(catalog));
+        let stats = parse(
+            "select sum(c1) filter (where c2 > 1), array_agg(c1 order by c2 desc), \
+             percentile_disc(0.5) within group (order by c2) from t1",
+        )
+        .unwrap();
+        let bound_stmt = binder.bind(&stats[0]).unwrap();
+        match bound_stmt {
+            BoundStatement::Select(select) => {
+                let BoundExpr::AggFunc(sum) = &select.select_list[0] else {
+                    panic!("expected agg func");
+                };
+                assert!(sum.filter.is_some());
+                let BoundExpr::AggFunc(array_agg) = &select.select_list[1] else {
+                    panic!("expected agg func");
+                };
+                assert_eq!(array_agg.order_by.len(), 1);
+                assert!(!array_agg.order_by[0].asc);
+                assert!(array_agg.order_by[0].nulls_first);
+                let BoundExpr::AggFunc(percentile) = &select.select_list[2] else {
+                    panic!("expected agg func");
+                };
+                assert_eq!(percentile.return_type, DataType::Int32);
+                assert_eq!(percentile.order_by.len(), 1);
             }
+            _ => unreachable!(),
         }
+
+        let stats = parse("select percentile_disc(0.5) from t1").unwrap();
+        assert!(matches!(
+            binder.bind(&stats[0]),
+            Err(BindError::InvalidAggFunc(_))
+        ));
     }
 }
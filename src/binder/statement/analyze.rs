@@ -0,0 +1,45 @@
+use sqlparser::ast::{Ident, ObjectName};
+
+use crate::{
+    binder::{BindError, Binder},
+    catalog::{ColumnCatalog, TableCatalog},
+};
+
+#[derive(Debug)]
+pub struct BoundAnalyze {
+    pub table_catalog: TableCatalog,
+    /// the columns to collect the statistics of, all columns of the table by default
+    pub columns: Vec<ColumnCatalog>,
+}
+
+impl Binder {
+    pub fn bind_analyze(
+        &mut self,
+        table_name: &ObjectName,
+        columns: &[Ident],
+    ) -> Result<BoundAnalyze, BindError> {
+        let table_name = self.bind_table_name(table_name)?;
+        let table_catalog = self
+            .catalog
+            .get_table_by_name(&table_name)
+            .ok_or_else(|| BindError::InvalidTable(table_name.clone()))?;
+        let mut column_catalogs: Vec<ColumnCatalog> = vec![];
+        for column in columns {
+            let id = column.value.to_lowercase();
+            let column = table_catalog
+                .get_column_by_name(&id)
+                .ok_or_else(|| BindError::InvalidColumn(id.clone()))?;
+            if column_catalogs.iter().any(|c| c.id == id) {
+                return Err(BindError::DuplicateColumn(id));
+            }
+            column_catalogs.push(column);
+        }
+        if column_catalogs.is_empty() {
+            column_catalogs = table_catalog.get_all_columns();
+        }
+        Ok(BoundAnalyze {
+            table_catalog,
+            columns: column_catalogs,
+        })
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use sqlparser::ast::{CopyOption, CopySource, CopyTarget, Ident, ObjectName};
+
+use crate::{
+    binder::{
+        expression::{BoundColumnRef, BoundExpr},
+        statement::{
+            create_table::{output_name, ExternalFormat, ExternalTable},
+            BoundSelect,
+        },
+        table::BoundTableRef,
+        BindError, Binder,
+    },
+    catalog::{ColumnCatalog, ColumnDesc, TableCatalog},
+    storage::{CsvConfig, IpcConfig, JsonConfig},
+};
+
+#[derive(Debug)]
+pub enum BoundCopy {
+    /// `COPY table TO` or `COPY (SELECT ...) TO`, which writes the rows of the query into
+    /// the file as they are produced.
+    To {
+        query: Box<BoundSelect>,
+        /// the output columns of the query
+        columns: Vec<ColumnCatalog>,
+        file: ExternalTable,
+    },
+    /// `COPY table FROM`, which appends the rows of the file to the table.
+    From {
+        table_catalog: TableCatalog,
+        /// the columns of the file, in the order of the file, while the other columns of
+        /// the table are null
+        columns: Vec<ColumnCatalog>,
+        file: ExternalTable,
+    },
+}
+
+impl Binder {
+    pub fn bind_copy(
+        &mut self,
+        source: &CopySource,
+        to: bool,
+        target: &CopyTarget,
+        options: &[CopyOption],
+    ) -> Result<BoundCopy, BindError> {
+        let CopyTarget::File { filename } = target else {
+            return Err(BindError::UnsupportedStmt(format!("COPY {target}")));
+        };
+        match (source, to) {
+            (CopySource::Query(query), true) => {
+                let query = self.bind_select(query)?;
+                let columns = query
+                    .select_list
+                    .iter()
+                    .map(|expr| {
+                        let name = output_name(expr);
+                        ColumnCatalog {
+                            id: name.clone(),
+                            desc: ColumnDesc {
+                                name,
+                                data_type: expr.return_type().unwrap(),
+                                nullable: true,
+                                default: None,
+                            },
+                        }
+                    })
+                    .collect();
+                let format = bind_copy_options(options, None)?;
+                Ok(BoundCopy::To {
+                    query: Box::new(query),
+                    columns,
+                    file: ExternalTable {
+                        location: filename.clone(),
+                        format,
+                    },
+                })
+            }
+            (
+                CopySource::Table {
+                    table_name,
+                    columns,
+                },
+                to,
+            ) => {
+                let table_catalog = self.bind_copy_table(table_name)?;
+                let columns = bind_copy_columns(&table_catalog, columns)?;
+                if to {
+                    let query = BoundSelect {
+                        select_list: columns
+                            .iter()
+                            .map(|column| {
+                                BoundExpr::ColumnRef(BoundColumnRef {
+                                    column_catalog: column.clone(),
+                                })
+                            })
+                            .collect(),
+                        from_table: Some(BoundTableRef {
+                            table_catalog,
+                            values: None,
+                            function: None,
+                        }),
+                        where_clause: None,
+                        group_by: vec![],
+                        grouping_sets: vec![],
+                    };
+                    let format = bind_copy_options(options, None)?;
+                    return Ok(BoundCopy::To {
+                        query: Box::new(query),
+                        columns,
+                        file: ExternalTable {
+                            location: filename.clone(),
+                            format,
+                        },
+                    });
+                }
+                let fields = columns
+                    .iter()
+                    .map(|c| Field::new(c.desc.name.clone(), c.desc.data_type.clone(), true))
+                    .collect::<Vec<_>>();
+                let format = bind_copy_options(options, Some(Arc::new(Schema::new(fields))))?;
+                Ok(BoundCopy::From {
+                    table_catalog,
+                    columns,
+                    file: ExternalTable {
+                        location: filename.clone(),
+                        format,
+                    },
+                })
+            }
+            (CopySource::Query(query), false) => {
+                Err(BindError::UnsupportedStmt(format!("COPY ({query}) FROM")))
+            }
+        }
+    }
+
+    fn bind_copy_table(&mut self, name: &ObjectName) -> Result<TableCatalog, BindError> {
+        let table_name = self.bind_table_name(name)?;
+        let table_catalog = self
+            .catalog
+            .get_table_by_name(&table_name)
+            .ok_or_else(|| BindError::InvalidTable(table_name.clone()))?;
+        self.context
+            .tables
+            .insert(table_name, table_catalog.clone());
+        Ok(table_catalog)
+    }
+}
+
+/// the columns of the column list, or all columns of the table without one
+fn bind_copy_columns(
+    table_catalog: &TableCatalog,
+    columns: &[Ident],
+) -> Result<Vec<ColumnCatalog>, BindError> {
+    if columns.is_empty() {
+        return Ok(table_catalog.get_all_columns());
+    }
+    let mut column_catalogs: Vec<ColumnCatalog> = vec![];
+    for column in columns {
+        let id = column.value.to_lowercase();
+        let column = table_catalog
+            .get_column_by_name(&id)
+            .ok_or_else(|| BindError::InvalidColumn(id.clone()))?;
+        if column_catalogs.iter().any(|c| c.id == id) {
+            return Err(BindError::DuplicateColumn(id));
+        }
+        column_catalogs.push(column);
+    }
+    Ok(column_catalogs)
+}
+
+/// Bind the `FORMAT` of the file, csv by default, with the options of the format. The
+/// schema, when given, is what csv and json files are read as, while Arrow IPC files
+/// embed their own. Parquet files are not supported yet.
+fn bind_copy_options(
+    options: &[CopyOption],
+    schema: Option<SchemaRef>,
+) -> Result<ExternalFormat, BindError> {
+    let format = options.iter().find_map(|option| match option {
+        CopyOption::Format(format) => Some(format.value.to_lowercase()),
+        _ => None,
+    });
+    let mut format = match format.as_deref() {
+        None | Some("csv") => ExternalFormat::Csv(CsvConfig::new()),
+        Some("json" | "ndjson") => ExternalFormat::Json(JsonConfig::new()),
+        Some("arrow" | "ipc") => ExternalFormat::Ipc(IpcConfig::new()),
+        Some(format) => {
+            return Err(BindError::InvalidTableOption(format!(
+                "unsupported format {format}"
+            )))
+        }
+    };
+    for option in options {
+        let invalid = || BindError::InvalidTableOption(option.to_string());
+        format = match (format, option) {
+            (format, CopyOption::Format(_)) => format,
+            (ExternalFormat::Csv(config), CopyOption::Header(header)) => {
+                ExternalFormat::Csv(config.with_header(*header))
+            }
+            (ExternalFormat::Csv(config), CopyOption::Delimiter(delimiter)) => {
+                let delimiter = u8::try_from(*delimiter).map_err(|_| invalid())?;
+                ExternalFormat::Csv(config.with_delimiter(delimiter))
+            }
+            (ExternalFormat::Csv(config), CopyOption::Null(null_marker)) => {
+                ExternalFormat::Csv(config.with_null_marker(null_marker))
+            }
+            _ => return Err(invalid()),
+        };
+    }
+    Ok(match (format, schema) {
+        (ExternalFormat::Csv(config), Some(schema)) => {
+            ExternalFormat::Csv(config.with_schema(schema))
+        }
+        (ExternalFormat::Json(config), Some(schema)) => {
+            ExternalFormat::Json(config.with_schema(schema))
+        }
+        (format, _) => format,
+    })
+}
@@ -0,0 +1,326 @@
+use std::sync::Arc;
+
+use arrow::{
+    compute::cast,
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+};
+use sqlparser::ast::{self, ColumnOption, CreateTable, CreateTableOptions, Expr, SqlOption, Value};
+
+use crate::{
+    binder::{expression::BoundExpr, statement::BoundSelect, BindError, Binder},
+    catalog::{ColumnCatalog, ColumnDesc},
+    storage::{CsvConfig, FileCompression, IpcConfig, JsonConfig},
+    types::build_scalar_value_array,
+};
+
+#[derive(Debug)]
+pub struct BoundCreateTable {
+    pub table_name: String,
+    pub columns: Vec<ColumnCatalog>,
+    /// The query of `CREATE TABLE ... AS SELECT`, whose rows fill the new table.
+    pub query: Option<BoundSelect>,
+    /// The file of `CREATE EXTERNAL TABLE`, whose rows fill the new table.
+    pub external: Option<ExternalTable>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExternalTable {
+    pub location: String,
+    pub format: ExternalFormat,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExternalFormat {
+    Csv(CsvConfig),
+    Json(JsonConfig),
+    Ipc(IpcConfig),
+}
+
+impl Binder {
+    pub fn bind_create_table(&mut self, stmt: &CreateTable) -> Result<BoundCreateTable, BindError> {
+        let table_name = self.bind_table_name(&stmt.name)?;
+        // names which read as paths are rejected, although the storage never uses the name
+        // of a table as a file name
+        let path_like = stmt
+            .name
+            .0
+            .iter()
+            .filter_map(|part| part.as_ident())
+            .any(|ident| {
+                ident.value.contains(['/', '\\']) || ident.value == "." || ident.value == ".."
+            });
+        if path_like {
+            return Err(BindError::InvalidTable(table_name));
+        }
+        if self.catalog.get_table_by_name(&table_name).is_some() {
+            return Err(BindError::TableExists(table_name));
+        }
+
+        let (columns, query) = match &stmt.query {
+            Some(query) => {
+                if !stmt.columns.is_empty() {
+                    return Err(BindError::UnsupportedStmt(stmt.to_string()));
+                }
+                let query = self.bind_select(query)?;
+                let columns = query
+                    .select_list
+                    .iter()
+                    .map(|expr| ColumnDesc {
+                        name: output_name(expr),
+                        data_type: expr.return_type().unwrap(),
+                        nullable: true,
+                        default: None,
+                    })
+                    .collect();
+                (columns, Some(query))
+            }
+            None => {
+                let columns = stmt
+                    .columns
+                    .iter()
+                    .map(|column| self.bind_column_def(column))
+                    .collect::<Result<Vec<_>, BindError>>()?;
+                (columns, None)
+            }
+        };
+
+        let mut column_catalogs: Vec<ColumnCatalog> = vec![];
+        for desc in columns {
+            if column_catalogs.iter().any(|c| c.id == desc.name) {
+                return Err(BindError::DuplicateColumn(desc.name));
+            }
+            column_catalogs.push(ColumnCatalog {
+                id: desc.name.clone(),
+                desc,
+            });
+        }
+
+        let external = if stmt.external {
+            Some(bind_external_table(stmt, &column_catalogs)?)
+        } else {
+            None
+        };
+
+        Ok(BoundCreateTable {
+            table_name,
+            columns: column_catalogs,
+            query,
+            external,
+        })
+    }
+
+    /// Bind the name, type, `NULL` / `NOT NULL` and `DEFAULT` of a column. A primary key
+    /// is not null, while the other constraints are not enforced and so are ignored.
+    fn bind_column_def(&mut self, column: &ast::ColumnDef) -> Result<ColumnDesc, BindError> {
+        let mut desc = ColumnDesc {
+            name: column.name.value.to_lowercase(),
+            data_type: bind_data_type(&column.data_type)?,
+            nullable: true,
+            default: None,
+        };
+        for option in &column.options {
+            match &option.option {
+                ColumnOption::Null => desc.nullable = true,
+                ColumnOption::NotNull
+                | ColumnOption::Unique {
+                    is_primary: true, ..
+                } => desc.nullable = false,
+                ColumnOption::Default(expr) => {
+                    let invalid = || BindError::InvalidDefault(desc.name.clone(), expr.to_string());
+                    let BoundExpr::Constant(value) = self.bind_expr(expr)? else {
+                        return Err(invalid());
+                    };
+                    // a value the column type cannot hold would turn into a null on insert
+                    let array = build_scalar_value_array(&value, 1);
+                    let castable = cast(&array, &desc.data_type).is_ok_and(|a| !a.is_null(0));
+                    if !value.is_null() && !castable {
+                        return Err(invalid());
+                    }
+                    desc.default = Some(value);
+                }
+                _ => {}
+            }
+        }
+        Ok(desc)
+    }
+}
+
+/// Bind the location and `WITH` options of `CREATE EXTERNAL TABLE`. The columns, when
+/// given, are read from the file instead of being inferred.
+fn bind_external_table(
+    stmt: &CreateTable,
+    columns: &[ColumnCatalog],
+) -> Result<ExternalTable, BindError> {
+    let location = stmt
+        .location
+        .clone()
+        .ok_or_else(|| BindError::InvalidTableOption("LOCATION is required".to_string()))?;
+    let options = match &stmt.table_options {
+        CreateTableOptions::With(options) => options.as_slice(),
+        CreateTableOptions::None => &[],
+        options => return Err(BindError::InvalidTableOption(options.to_string())),
+    };
+
+    // the format is the first option, set by STORED AS
+    let format = match options.first() {
+        Some(SqlOption::KeyValue { key, value }) if key.value == "format" => option_string(value),
+        _ => None,
+    };
+    let schema = (!columns.is_empty()).then(|| {
+        let fields = columns
+            .iter()
+            .map(|c| Field::new(c.desc.name.clone(), c.desc.data_type.clone(), true))
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    });
+    let format = match format.as_deref() {
+        Some("csv") => ExternalFormat::Csv(bind_csv_options(&options[1..], schema)?),
+        Some("json" | "ndjson") => ExternalFormat::Json(bind_json_options(&options[1..], schema)?),
+        Some("arrow" | "ipc") => ExternalFormat::Ipc(bind_ipc_options(&options[1..], schema)?),
+        Some(format) => {
+            return Err(BindError::InvalidTableOption(format!(
+                "unsupported format {format}"
+            )))
+        }
+        None => {
+            return Err(BindError::InvalidTableOption(
+                "STORED AS is required".to_string(),
+            ))
+        }
+    };
+    Ok(ExternalTable { location, format })
+}
+
+fn bind_csv_options(
+    options: &[SqlOption],
+    schema: Option<SchemaRef>,
+) -> Result<CsvConfig, BindError> {
+    let mut config = CsvConfig::new();
+    for option in options {
+        let SqlOption::KeyValue { key, value } = option else {
+            return Err(BindError::InvalidTableOption(option.to_string()));
+        };
+        let invalid = || BindError::InvalidTableOption(option.to_string());
+        config = match key.value.to_lowercase().as_str() {
+            "delimiter" => match option_string(value).ok_or_else(invalid)?.as_bytes() {
+                [delimiter] => config.with_delimiter(*delimiter),
+                _ => return Err(invalid()),
+            },
+            "header" => config.with_header(option_bool(value).ok_or_else(invalid)?),
+            "newlines_in_values" => {
+                config.with_newlines_in_values(option_bool(value).ok_or_else(invalid)?)
+            }
+            "null" => config.with_null_marker(option_string(value).ok_or_else(invalid)?),
+            "datetime_format" => {
+                config.with_datetime_format(option_string(value).ok_or_else(invalid)?)
+            }
+            "compression" => {
+                config.with_compression(option_compression(value).ok_or_else(invalid)?)
+            }
+            _ => return Err(invalid()),
+        };
+    }
+    Ok(match schema {
+        Some(schema) => config.with_schema(schema),
+        None => config,
+    })
+}
+
+fn bind_json_options(
+    options: &[SqlOption],
+    schema: Option<SchemaRef>,
+) -> Result<JsonConfig, BindError> {
+    let mut config = JsonConfig::new();
+    for option in options {
+        let SqlOption::KeyValue { key, value } = option else {
+            return Err(BindError::InvalidTableOption(option.to_string()));
+        };
+        let invalid = || BindError::InvalidTableOption(option.to_string());
+        config = match key.value.to_lowercase().as_str() {
+            "compression" => {
+                config.with_compression(option_compression(value).ok_or_else(invalid)?)
+            }
+            _ => return Err(invalid()),
+        };
+    }
+    Ok(match schema {
+        Some(schema) => config.with_schema(schema),
+        None => config,
+    })
+}
+
+/// the columns of Arrow IPC files are read from the schema the files embed
+fn bind_ipc_options(
+    options: &[SqlOption],
+    schema: Option<SchemaRef>,
+) -> Result<IpcConfig, BindError> {
+    if let Some(option) = options.first() {
+        return Err(BindError::InvalidTableOption(option.to_string()));
+    }
+    if schema.is_some() {
+        return Err(BindError::InvalidTableOption(
+            "arrow tables take the columns of the files".to_string(),
+        ));
+    }
+    Ok(IpcConfig::new())
+}
+
+fn option_compression(value: &Expr) -> Option<FileCompression> {
+    option_string(value)?.parse().ok()
+}
+
+fn option_string(value: &Expr) -> Option<String> {
+    match value {
+        Expr::Value(value) => match &value.value {
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(s.clone()),
+            _ => None,
+        },
+        Expr::Identifier(ident) => Some(ident.value.clone()),
+        _ => None,
+    }
+}
+
+fn option_bool(value: &Expr) -> Option<bool> {
+    match value {
+        Expr::Value(value) => match &value.value {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The column name of a select list expr in the output, same as the executors use.
+pub(super) fn output_name(expr: &BoundExpr) -> String {
+    match expr {
+        BoundExpr::ColumnRef(column_ref) => column_ref.column_catalog.desc.name.clone(),
+        BoundExpr::AggFunc(agg) => format!("{:?}", agg.func).to_lowercase(),
+        _ => "?column?".to_string(),
+    }
+}
+
+/// bind sqlparser DataType into arrow DataType
+pub fn bind_data_type(data_type: &ast::DataType) -> Result<DataType, BindError> {
+    use ast::DataType as T;
+
+    match data_type {
+        T::Boolean | T::Bool => Ok(DataType::Boolean),
+        T::SmallInt(_) | T::Int2(_) | T::Int(_) | T::Int4(_) | T::Integer(_) => Ok(DataType::Int32),
+        T::BigInt(_) | T::Int8(_) => Ok(DataType::Int64),
+        T::Real | T::Float4 | T::Float(_) | T::Float8 | T::Double(_) | T::DoublePrecision => {
+            Ok(DataType::Float64)
+        }
+        T::Char(_)
+        | T::Character(_)
+        | T::Varchar(_)
+        | T::CharVarying(_)
+        | T::CharacterVarying(_)
+        | T::Text
+        | T::String(_) => Ok(DataType::Utf8),
+        T::Date => Ok(DataType::Date32),
+        T::Timestamp(_, ast::TimezoneInfo::None | ast::TimezoneInfo::WithoutTimeZone) => {
+            Ok(DataType::Timestamp(TimeUnit::Microsecond, None))
+        }
+        _ => Err(BindError::UnsupportedDataType(data_type.to_string())),
+    }
+}
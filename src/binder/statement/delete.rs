@@ -0,0 +1,42 @@
+use sqlparser::ast::{Delete, FromTable};
+
+use crate::binder::{expression::BoundExpr, table::BoundTableRef, BindError, Binder};
+
+#[derive(Debug)]
+pub struct BoundDelete {
+    pub table_ref: BoundTableRef,
+    /// The rows it evaluates to true are deleted, all rows without WHERE clause.
+    pub where_clause: Option<BoundExpr>,
+}
+
+impl Binder {
+    pub fn bind_delete(&mut self, stmt: &Delete) -> Result<BoundDelete, BindError> {
+        let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) = &stmt.from;
+        // currently, only support deleting from one table without any extension
+        let [table] = tables.as_slice() else {
+            return Err(BindError::UnsupportedStmt(stmt.to_string()));
+        };
+        if !stmt.tables.is_empty()
+            || stmt.using.is_some()
+            || stmt.returning.is_some()
+            || !stmt.order_by.is_empty()
+            || stmt.limit.is_some()
+        {
+            return Err(BindError::UnsupportedStmt(stmt.to_string()));
+        }
+        let table_ref = self.bind_table_with_joins(table)?;
+        if table_ref.values.is_some() {
+            return Err(BindError::UnsupportedStmt(stmt.to_string()));
+        }
+
+        let where_clause = stmt
+            .selection
+            .as_ref()
+            .map(|expr| self.bind_expr(expr))
+            .transpose()?;
+        Ok(BoundDelete {
+            table_ref,
+            where_clause,
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use sqlparser::ast::ObjectName;
+
+use crate::binder::{BindError, Binder};
+
+#[derive(Debug)]
+pub struct BoundDropTable {
+    pub table_name: String,
+    pub if_exists: bool,
+}
+
+impl Binder {
+    pub fn bind_drop_table(
+        &mut self,
+        names: &[ObjectName],
+        if_exists: bool,
+    ) -> Result<BoundDropTable, BindError> {
+        // currently, only support dropping one table
+        let [name] = names else {
+            return Err(BindError::UnsupportedStmt(format!("drop tables {names:?}")));
+        };
+        let table_name = self.bind_table_name(name)?;
+        if !if_exists && self.catalog.get_table_by_name(&table_name).is_none() {
+            return Err(BindError::InvalidTable(table_name));
+        }
+        Ok(BoundDropTable {
+            table_name,
+            if_exists,
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use sqlparser::ast::{Insert, TableObject};
+
+use crate::{
+    binder::{expression::BoundExpr, statement::BoundSelect, BindError, Binder},
+    catalog::TableCatalog,
+    types::ScalarValue,
+};
+
+#[derive(Debug)]
+pub struct BoundInsert {
+    pub table_catalog: TableCatalog,
+    /// The rows to insert, whose select list is casted into the columns of the table in
+    /// order, with the defaults, or nulls, of the columns absent from the insert column list.
+    pub source: BoundSelect,
+}
+
+impl Binder {
+    pub fn bind_insert(&mut self, stmt: &Insert) -> Result<BoundInsert, BindError> {
+        let TableObject::TableName(name) = &stmt.table else {
+            return Err(BindError::UnsupportedStmt(stmt.to_string()));
+        };
+        let Some(source) = &stmt.source else {
+            return Err(BindError::UnsupportedStmt(stmt.to_string()));
+        };
+        let table_name = self.bind_table_name(name)?;
+        let table_catalog = self
+            .catalog
+            .get_table_by_name(&table_name)
+            .ok_or_else(|| BindError::InvalidTable(table_name.clone()))?;
+
+        let target_ids = if stmt.columns.is_empty() {
+            table_catalog.column_ids.clone()
+        } else {
+            let mut target_ids: Vec<String> = vec![];
+            for column in &stmt.columns {
+                let id = column.value.to_lowercase();
+                if table_catalog.get_column_by_name(&id).is_none() {
+                    return Err(BindError::InvalidColumn(id));
+                }
+                if target_ids.contains(&id) {
+                    return Err(BindError::DuplicateColumn(id));
+                }
+                target_ids.push(id);
+            }
+            target_ids
+        };
+
+        let mut source = self.bind_select(source)?;
+        if source.select_list.len() != target_ids.len() {
+            return Err(BindError::InsertColumnsMismatch(
+                target_ids.len(),
+                source.select_list.len(),
+            ));
+        }
+        source.select_list = table_catalog
+            .get_all_columns()
+            .iter()
+            .map(|column| {
+                let data_type = &column.desc.data_type;
+                match target_ids.iter().position(|id| *id == column.id) {
+                    Some(i) => source.select_list[i].clone().cast_to(data_type),
+                    None => {
+                        let default = column.desc.default.clone();
+                        let value = default.unwrap_or_else(|| ScalarValue::new_null(data_type));
+                        BoundExpr::Constant(value).cast_to(data_type)
+                    }
+                }
+            })
+            .collect();
+
+        Ok(BoundInsert {
+            table_catalog,
+            source,
+        })
+    }
+}
@@ -1,10 +1,30 @@
+use crate::binder::expression::agg_func::AggFunc;
+use crate::binder::expression::BoundColumnRef;
+use crate::binder::statement::{
+    analyze::BoundAnalyze, copy::BoundCopy, create_table::BoundCreateTable, delete::BoundDelete,
+    drop_table::BoundDropTable, insert::BoundInsert, update::BoundUpdate,
+};
 use crate::binder::{expression::BoundExpr, table::BoundTableRef, BindError, Binder};
-use sqlparser::ast::SetExpr::Select;
-use sqlparser::ast::{Query, SelectItem};
+use crate::planner::util::find_aggregate_exprs;
+use sqlparser::ast::{Expr, GroupByExpr, Query, SelectItem, SetExpr};
+pub mod analyze;
+pub mod copy;
+pub mod create_table;
+pub mod delete;
+pub mod drop_table;
+pub mod insert;
+pub mod update;
 
 #[derive(Debug)]
 pub enum BoundStatement {
     Select(BoundSelect),
+    CreateTable(BoundCreateTable),
+    Insert(BoundInsert),
+    DropTable(BoundDropTable),
+    Update(BoundUpdate),
+    Delete(BoundDelete),
+    Copy(BoundCopy),
+    Analyze(BoundAnalyze),
 }
 
 #[derive(Debug)]
@@ -12,12 +32,27 @@ pub struct BoundSelect {
     pub select_list: Vec<BoundExpr>,
     pub from_table: Option<BoundTableRef>,
     pub where_clause: Option<BoundExpr>,
+    pub group_by: Vec<BoundExpr>,
+    /// Grouping sets expanded from ROLLUP, CUBE and GROUPING SETS, each one holding indices
+    /// into `group_by`. Empty for a plain GROUP BY, which groups by all `group_by` exprs.
+    pub grouping_sets: Vec<Vec<usize>>,
 }
 
 impl Binder {
     pub fn bind_select(&mut self, query: &Query) -> Result<BoundSelect, BindError> {
         let select = match query.body.as_ref() {
-            Select(select) => select,
+            SetExpr::Select(select) => select,
+            // a bare `VALUES` list selects all of its columns
+            SetExpr::Values(values) => {
+                let from_table = self.bind_values(values, None)?;
+                return Ok(BoundSelect {
+                    select_list: all_column_refs(&from_table),
+                    from_table: Some(from_table),
+                    where_clause: None,
+                    group_by: vec![],
+                    grouping_sets: vec![],
+                });
+            }
             _ => todo!(),
         };
 
@@ -38,7 +73,11 @@ impl Binder {
                 }
                 SelectItem::ExprWithAlias { expr: _, alias: _ } => todo!(),
                 SelectItem::QualifiedWildcard(_, _) => todo!(),
-                SelectItem::Wildcard(_) => todo!(),
+                SelectItem::Wildcard(_) => {
+                    if let Some(table) = &from_table {
+                        select_list.extend(all_column_refs(table));
+                    }
+                }
             }
         }
 
@@ -49,10 +88,171 @@ impl Binder {
             .map(|expr| self.bind_expr(expr))
             .transpose()?;
 
+        // bind group by clause
+        let (group_by, grouping_sets) = self.bind_group_by(&select.group_by)?;
+        let aggs = find_aggregate_exprs(&select_list);
+        if !group_by.is_empty() || !aggs.is_empty() {
+            for expr in &select_list {
+                check_grouped(expr, &group_by)?;
+            }
+        }
+        for agg in aggs {
+            if let BoundExpr::AggFunc(agg) = &agg {
+                if agg.func == AggFunc::Grouping
+                    && (agg.exprs.is_empty() || agg.exprs.iter().any(|e| !group_by.contains(e)))
+                {
+                    return Err(BindError::InvalidGroupingArgument(format!(
+                        "{:?}",
+                        agg.exprs
+                    )));
+                }
+            }
+        }
+
         Ok(BoundSelect {
             select_list,
             from_table,
             where_clause,
+            group_by,
+            grouping_sets,
         })
     }
+
+    /// bind GROUP BY clause into the distinct grouping exprs and the grouping sets over them.
+    ///
+    /// Every grouping element expands into a list of sets:
+    ///     * `a` => `(a)`
+    ///     * `ROLLUP(a, b)` => `(a, b), (a), ()`
+    ///     * `CUBE(a, b)` => `(a, b), (a), (b), ()`
+    ///     * `GROUPING SETS ((a), (b))` => `(a), (b)`
+    ///
+    /// and the sets of multiple elements are combined by cross product, so
+    /// `GROUP BY a, ROLLUP(b)` yields `(a, b), (a)`.
+    fn bind_group_by(
+        &mut self,
+        group_by: &GroupByExpr,
+    ) -> Result<(Vec<BoundExpr>, Vec<Vec<usize>>), BindError> {
+        let exprs = match group_by {
+            GroupByExpr::Expressions(exprs, modifiers) if modifiers.is_empty() => exprs,
+            _ => return Err(BindError::UnsupportedGroupBy(group_by.to_string())),
+        };
+
+        let mut group_exprs = vec![];
+        let mut grouping_sets: Vec<Vec<usize>> = vec![vec![]];
+        let mut has_grouping_sets = false;
+        for expr in exprs {
+            let element_sets = match expr {
+                Expr::Rollup(items) => {
+                    has_grouping_sets = true;
+                    let items = self.bind_grouping_items(items, &mut group_exprs)?;
+                    (0..=items.len())
+                        .rev()
+                        .map(|n| items[..n].concat())
+                        .collect()
+                }
+                Expr::Cube(items) => {
+                    has_grouping_sets = true;
+                    let items = self.bind_grouping_items(items, &mut group_exprs)?;
+                    let n = items.len();
+                    (0..1_usize << n)
+                        .rev()
+                        .map(|mask| {
+                            (0..n)
+                                .filter(|i| mask & (1 << (n - 1 - i)) != 0)
+                                .flat_map(|i| items[i].clone())
+                                .collect()
+                        })
+                        .collect()
+                }
+                Expr::GroupingSets(items) => {
+                    has_grouping_sets = true;
+                    self.bind_grouping_items(items, &mut group_exprs)?
+                }
+                // `GROUP BY ()` and `GROUP BY (a, b)`
+                Expr::Tuple(items) => {
+                    self.bind_grouping_items(std::slice::from_ref(items), &mut group_exprs)?
+                }
+                expr => self.bind_grouping_items(&[vec![expr.clone()]], &mut group_exprs)?,
+            };
+
+            grouping_sets = grouping_sets
+                .iter()
+                .flat_map(|set| {
+                    element_sets
+                        .iter()
+                        .map(move |element| [set.as_slice(), element.as_slice()].concat())
+                })
+                .collect();
+        }
+
+        if !has_grouping_sets {
+            return Ok((group_exprs, vec![]));
+        }
+
+        // identical sets would share the same grouping id and be aggregated twice
+        let mut deduped: Vec<Vec<usize>> = vec![];
+        for mut set in grouping_sets {
+            set.sort_unstable();
+            set.dedup();
+            if !deduped.contains(&set) {
+                deduped.push(set);
+            }
+        }
+        Ok((group_exprs, deduped))
+    }
+
+    /// bind each item of a grouping element and resolve it into indices of `group_exprs`,
+    /// appending the exprs not seen before.
+    fn bind_grouping_items(
+        &mut self,
+        items: &[Vec<Expr>],
+        group_exprs: &mut Vec<BoundExpr>,
+    ) -> Result<Vec<Vec<usize>>, BindError> {
+        let mut bound_items = vec![];
+        for item in items {
+            let mut indices = vec![];
+            for expr in item {
+                let expr = self.bind_expr(expr)?;
+                let index = match group_exprs.iter().position(|e| *e == expr) {
+                    Some(index) => index,
+                    None => {
+                        group_exprs.push(expr);
+                        group_exprs.len() - 1
+                    }
+                };
+                indices.push(index);
+            }
+            bound_items.push(indices);
+        }
+        Ok(bound_items)
+    }
+}
+
+/// check that the expr of an aggregating select reads columns only through grouping exprs
+/// or aggregate functions
+fn check_grouped(expr: &BoundExpr, group_by: &[BoundExpr]) -> Result<(), BindError> {
+    if group_by.contains(expr) {
+        return Ok(());
+    }
+    match expr {
+        BoundExpr::ColumnRef(column_ref) => Err(BindError::UngroupedColumn(
+            column_ref.column_catalog.desc.name.clone(),
+        )),
+        BoundExpr::BinaryOp(e) => {
+            check_grouped(&e.left, group_by)?;
+            check_grouped(&e.right, group_by)
+        }
+        BoundExpr::TypeCast(e) => check_grouped(&e.expr, group_by),
+        BoundExpr::IsNull(e) => check_grouped(&e.expr, group_by),
+        BoundExpr::Constant(_) | BoundExpr::InputRef(_) | BoundExpr::AggFunc(_) => Ok(()),
+    }
+}
+
+fn all_column_refs(table: &BoundTableRef) -> Vec<BoundExpr> {
+    table
+        .table_catalog
+        .get_all_columns()
+        .into_iter()
+        .map(|column_catalog| BoundExpr::ColumnRef(BoundColumnRef { column_catalog }))
+        .collect()
 }
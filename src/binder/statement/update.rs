@@ -0,0 +1,74 @@
+use sqlparser::ast::{Assignment, AssignmentTarget, Expr, TableWithJoins};
+
+use crate::binder::{
+    expression::{BoundColumnRef, BoundExpr},
+    table::BoundTableRef,
+    BindError, Binder,
+};
+
+#[derive(Debug)]
+pub struct BoundUpdate {
+    pub table_ref: BoundTableRef,
+    /// The new value of every table column in order, the column itself if not assigned.
+    pub assignments: Vec<BoundExpr>,
+    /// Only the rows it evaluates to true are updated, all rows without WHERE clause.
+    pub where_clause: Option<BoundExpr>,
+}
+
+impl Binder {
+    pub fn bind_update(
+        &mut self,
+        table: &TableWithJoins,
+        assignments: &[Assignment],
+        selection: Option<&Expr>,
+    ) -> Result<BoundUpdate, BindError> {
+        let table_ref = self.bind_table_with_joins(table)?;
+        if table_ref.values.is_some() {
+            return Err(BindError::UnsupportedStmt(format!("update {table}")));
+        }
+        let table_catalog = &table_ref.table_catalog;
+
+        let mut new_values: Vec<(String, BoundExpr)> = vec![];
+        for assignment in assignments {
+            let AssignmentTarget::ColumnName(name) = &assignment.target else {
+                return Err(BindError::UnsupportedStmt(assignment.to_string()));
+            };
+            let column_name = name
+                .0
+                .last()
+                .and_then(|part| part.as_ident())
+                .map(|ident| ident.value.to_lowercase())
+                .ok_or_else(|| BindError::InvalidColumn(name.to_string()))?;
+            let column = table_catalog
+                .get_column_by_name(&column_name)
+                .ok_or_else(|| BindError::InvalidColumn(column_name.clone()))?;
+            if new_values.iter().any(|(id, _)| *id == column.id) {
+                return Err(BindError::DuplicateColumn(column_name));
+            }
+            let value = self
+                .bind_expr(&assignment.value)?
+                .cast_to(&column.desc.data_type);
+            new_values.push((column.id, value));
+        }
+
+        let assignments = table_catalog
+            .get_all_columns()
+            .into_iter()
+            .map(
+                |column| match new_values.iter().find(|(id, _)| *id == column.id) {
+                    Some((_, value)) => value.clone(),
+                    None => BoundExpr::ColumnRef(BoundColumnRef {
+                        column_catalog: column,
+                    }),
+                },
+            )
+            .collect();
+        let where_clause = selection.map(|expr| self.bind_expr(expr)).transpose()?;
+
+        Ok(BoundUpdate {
+            table_ref,
+            assignments,
+            where_clause,
+        })
+    }
+}
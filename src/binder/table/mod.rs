@@ -1,16 +1,32 @@
-use sqlparser::ast::{TableFactor, TableWithJoins};
+use std::collections::BTreeMap;
+
+use arrow::datatypes::{DataType, Schema};
+use sqlparser::ast::{
+    Expr, FunctionArg, FunctionArgExpr, ObjectName, SetExpr, TableAlias, TableFactor,
+    TableFunctionArgs as FunctionArgs, TableWithJoins, UnaryOperator, Value, Values,
+};
 
 use crate::{
-    binder::{BindError, Binder},
-    catalog::TableCatalog,
+    binder::{expression::BoundExpr, BindError, Binder},
+    catalog::{ColumnCatalog, ColumnDesc, TableCatalog},
+    function::{FunctionTable, TableFunctionArgs},
+    types::ScalarValue,
 };
 
 pub static DEFAULT_DATABASE_NAME: &str = "postgres";
 pub static DEFAULT_SCHEMA_NAME: &str = "postgres";
+/// The table name of a `VALUES` list without alias, same as PostgreSQL.
+pub static VALUES_TABLE_NAME: &str = "*VALUES*";
 
 #[derive(Debug)]
 pub struct BoundTableRef {
     pub table_catalog: TableCatalog,
+    /// The constant rows of a `VALUES` list, whose columns are described by
+    /// `table_catalog`. None for a table in the catalog.
+    pub values: Option<Vec<Vec<BoundExpr>>>,
+    /// The table returned by a table function in FROM, whose columns are described by
+    /// `table_catalog`. None for a table in the catalog.
+    pub function: Option<FunctionTable>,
 }
 
 impl Binder {
@@ -23,28 +39,14 @@ impl Binder {
 
     pub fn bind_table_ref(&mut self, table: &TableFactor) -> Result<BoundTableRef, BindError> {
         match table {
-            TableFactor::Table { name, alias: _, .. } => {
-                // ObjectName internal items: db.schema.table
-                let (_database, _schema, table) = match name.0.as_slice() {
-                    [table] => (
-                        DEFAULT_DATABASE_NAME.to_string(),
-                        DEFAULT_SCHEMA_NAME.to_string(),
-                        table.as_ident().unwrap().to_string(),
-                    ),
-                    [schema, table] => (
-                        DEFAULT_DATABASE_NAME.to_string(),
-                        schema.as_ident().unwrap().to_string(),
-                        table.as_ident().unwrap().to_string(),
-                    ),
-                    [db, schema, table] => (
-                        db.as_ident().unwrap().to_string(),
-                        schema.as_ident().unwrap().to_string(),
-                        table.as_ident().unwrap().to_string(),
-                    ),
-                    _ => return Err(BindError::InvalidTable(name.to_string())),
-                };
-
-                let table_name = table.to_string();
+            TableFactor::Table {
+                name,
+                alias,
+                args: Some(args),
+                ..
+            } => self.bind_table_function(name, args, alias.as_ref()),
+            TableFactor::Table { name, .. } => {
+                let table_name = self.bind_table_name(name)?;
                 let table_catalog = self
                     .catalog
                     .get_table_by_name(&table_name)
@@ -52,9 +54,258 @@ impl Binder {
                 self.context
                     .tables
                     .insert(table_name, table_catalog.clone());
-                Ok(BoundTableRef { table_catalog })
+                Ok(BoundTableRef {
+                    table_catalog,
+                    values: None,
+                    function: None,
+                })
             }
-            _ => panic!("unsupported table factor"),
+            TableFactor::Derived {
+                subquery, alias, ..
+            } => match subquery.body.as_ref() {
+                SetExpr::Values(values) => self.bind_values(values, alias.as_ref()),
+                _ => Err(BindError::UnsupportedTableRef(table.to_string())),
+            },
+            _ => Err(BindError::UnsupportedTableRef(table.to_string())),
         }
     }
+
+    /// bind the table name of an ObjectName, whose internal items are `db.schema.table`
+    pub fn bind_table_name(&self, name: &ObjectName) -> Result<String, BindError> {
+        let (_database, _schema, table) = match name.0.as_slice() {
+            [table] => (
+                DEFAULT_DATABASE_NAME.to_string(),
+                DEFAULT_SCHEMA_NAME.to_string(),
+                table.as_ident().unwrap().to_string(),
+            ),
+            [schema, table] => (
+                DEFAULT_DATABASE_NAME.to_string(),
+                schema.as_ident().unwrap().to_string(),
+                table.as_ident().unwrap().to_string(),
+            ),
+            [db, schema, table] => (
+                db.as_ident().unwrap().to_string(),
+                schema.as_ident().unwrap().to_string(),
+                table.as_ident().unwrap().to_string(),
+            ),
+            _ => return Err(BindError::InvalidTable(name.to_string())),
+        };
+        Ok(table)
+    }
+
+    /// bind a `VALUES` list into a table ref, whose columns are named `column1`, `column2`,
+    /// ... unless renamed by the alias. The type of each column is the common type of its
+    /// values, to which the other values are casted.
+    pub fn bind_values(
+        &mut self,
+        values: &Values,
+        alias: Option<&TableAlias>,
+    ) -> Result<BoundTableRef, BindError> {
+        let num_columns = values.rows.first().map(|row| row.len()).unwrap_or(0);
+        let mut rows = vec![];
+        for row in &values.rows {
+            if row.len() != num_columns {
+                return Err(BindError::ValuesLengthMismatch);
+            }
+            let row = row
+                .iter()
+                .map(|expr| self.bind_expr(expr))
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+
+        let table_name = alias
+            .map(|alias| alias.name.value.to_lowercase())
+            .unwrap_or_else(|| VALUES_TABLE_NAME.to_string());
+        let mut column_ids = vec![];
+        let mut columns = BTreeMap::new();
+        for i in 0..num_columns {
+            let mut data_type = None;
+            for row in &rows {
+                data_type = common_values_type(data_type, row[i].return_type().unwrap())?;
+            }
+            // like PostgreSQL, a column of only nulls is resolved as text
+            let data_type = data_type.unwrap_or(DataType::Utf8);
+            for row in rows.iter_mut() {
+                row[i] = row[i].clone().cast_to(&data_type);
+            }
+
+            let name = match alias.and_then(|alias| alias.columns.get(i)) {
+                Some(column) => column.name.value.to_lowercase(),
+                None => format!("column{}", i + 1),
+            };
+            column_ids.push(name.clone());
+            columns.insert(
+                name.clone(),
+                ColumnCatalog {
+                    id: name.clone(),
+                    desc: ColumnDesc {
+                        name,
+                        data_type,
+                        nullable: true,
+                        default: None,
+                    },
+                },
+            );
+        }
+
+        let table_catalog = TableCatalog {
+            id: table_name.clone(),
+            name: table_name.clone(),
+            column_ids,
+            columns,
+            statistics: None,
+        };
+        self.context
+            .tables
+            .insert(table_name, table_catalog.clone());
+        Ok(BoundTableRef {
+            table_catalog,
+            values: Some(rows),
+            function: None,
+        })
+    }
+
+    /// Bind a table function call like `generate_series(1, 10)` into a table ref, named
+    /// after the function unless aliased. The arguments must be constants, so the function
+    /// is called right away for the columns of its table.
+    pub fn bind_table_function(
+        &mut self,
+        name: &ObjectName,
+        args: &FunctionArgs,
+        alias: Option<&TableAlias>,
+    ) -> Result<BoundTableRef, BindError> {
+        let function_name = self.bind_table_name(name)?.to_lowercase();
+        let function = self
+            .table_functions
+            .get(&function_name)
+            .ok_or_else(|| BindError::InvalidTableFunction(function_name.clone()))?;
+        let mut call_args = TableFunctionArgs {
+            function: function_name.clone(),
+            ..Default::default()
+        };
+        for arg in &args.args {
+            let invalid = || BindError::InvalidFunctionArgs(function_name.clone(), arg.to_string());
+            match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => {
+                    if !call_args.named.is_empty() {
+                        return Err(invalid());
+                    }
+                    call_args
+                        .positional
+                        .push(bind_constant(expr).ok_or_else(invalid)?);
+                }
+                FunctionArg::Named {
+                    name,
+                    arg: FunctionArgExpr::Expr(expr),
+                    ..
+                }
+                | FunctionArg::ExprNamed {
+                    name: Expr::Identifier(name),
+                    arg: FunctionArgExpr::Expr(expr),
+                    ..
+                } => call_args.named.push((
+                    name.value.to_lowercase(),
+                    bind_constant(expr).ok_or_else(invalid)?,
+                )),
+                _ => return Err(invalid()),
+            }
+        }
+        let function = function.call(&call_args)?;
+
+        let table_name = alias
+            .map(|alias| alias.name.value.to_lowercase())
+            .unwrap_or(function_name);
+        let table_catalog = function_table_catalog(&table_name, &function.schema, alias)?;
+        self.context
+            .tables
+            .insert(table_name, table_catalog.clone());
+        Ok(BoundTableRef {
+            table_catalog,
+            values: None,
+            function: Some(function),
+        })
+    }
+}
+
+/// a literal, or a negated number
+fn bind_constant(expr: &Expr) -> Option<ScalarValue> {
+    match expr {
+        Expr::Value(value) => Some((&value.value).into()),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => match expr.as_ref() {
+            Expr::Value(value) => match &value.value {
+                Value::Number(n, long) => Some((&Value::Number(format!("-{n}"), *long)).into()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// the catalog of the table of a table function, whose columns may be renamed by the alias
+fn function_table_catalog(
+    table_name: &str,
+    schema: &Schema,
+    alias: Option<&TableAlias>,
+) -> Result<TableCatalog, BindError> {
+    let mut column_ids = vec![];
+    let mut columns = BTreeMap::new();
+    for (i, field) in schema.fields().iter().enumerate() {
+        let name = match alias.and_then(|alias| alias.columns.get(i)) {
+            Some(column) => column.name.value.to_lowercase(),
+            None => field.name().to_lowercase(),
+        };
+        if columns.contains_key(&name) {
+            return Err(BindError::DuplicateColumn(name));
+        }
+        column_ids.push(name.clone());
+        columns.insert(
+            name.clone(),
+            ColumnCatalog {
+                id: name.clone(),
+                desc: ColumnDesc {
+                    name,
+                    data_type: field.data_type().clone(),
+                    nullable: true,
+                    default: None,
+                },
+            },
+        );
+    }
+    Ok(TableCatalog {
+        id: table_name.to_string(),
+        name: table_name.to_string(),
+        column_ids,
+        columns,
+        statistics: None,
+    })
+}
+
+/// The common type of a `VALUES` column so far and the type of its next value. Nulls
+/// match any type and numbers are widened, other types must be the same.
+fn common_values_type(
+    current: Option<DataType>,
+    next: DataType,
+) -> Result<Option<DataType>, BindError> {
+    let Some(current) = current else {
+        return Ok((next != DataType::Null).then_some(next));
+    };
+    let common = match (&current, &next) {
+        (_, DataType::Null) => current,
+        (left, right) if left == right => current,
+        (DataType::Int32, DataType::Int64) | (DataType::Int64, DataType::Int32) => DataType::Int64,
+        (DataType::Int32 | DataType::Int64, DataType::Float64)
+        | (DataType::Float64, DataType::Int32 | DataType::Int64) => DataType::Float64,
+        _ => {
+            return Err(BindError::ValuesTypeMismatch(
+                format!("{current:?}"),
+                format!("{next:?}"),
+            ))
+        }
+    };
+    Ok(Some(common))
 }
@@ -6,6 +6,11 @@ use std::{
 
 use arrow::datatypes::DataType;
 
+use crate::types::ScalarValue;
+
+mod statistics;
+pub use statistics::*;
+
 /// Use column name as id for simplicity
 pub type ColumnId = String;
 
@@ -19,6 +24,10 @@ pub struct ColumnCatalog {
 pub struct ColumnDesc {
     pub name: String,
     pub data_type: DataType,
+    /// whether the column may hold nulls, false for `NOT NULL` columns
+    pub nullable: bool,
+    /// the value inserted into the column when an insert leaves it out
+    pub default: Option<ScalarValue>,
 }
 
 /// use table name as id for simplicity
@@ -31,6 +40,8 @@ pub struct TableCatalog {
     /// column ids to keep the order of inferred columns
     pub column_ids: Vec<ColumnId>,
     pub columns: BTreeMap<ColumnId, ColumnCatalog>,
+    /// the statistics collected by the last `ANALYZE` of the table, if any
+    pub statistics: Option<Arc<TableStatistics>>,
 }
 
 impl TableCatalog {
@@ -0,0 +1,471 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+};
+
+use arrow::{
+    array::RecordBatch,
+    error::ArrowError,
+    row::{OwnedRow, RowConverter, SortField},
+};
+use sqlparser::ast::BinaryOperator;
+
+use crate::{
+    binder::expression::BoundExpr,
+    catalog::{ColumnCatalog, ColumnId},
+    types::ScalarValue,
+};
+
+/// the number of buckets of the histogram of a column
+const HISTOGRAM_BUCKETS: usize = 10;
+/// the number of values of a column sampled to build its histogram
+const HISTOGRAM_SAMPLE_SIZE: usize = 10_000;
+/// log2 of the number of registers of a HyperLogLog sketch
+const HLL_PRECISION: u32 = 12;
+/// the fraction of rows assumed to pass a predicate the statistics tell nothing about
+const DEFAULT_SELECTIVITY: f64 = 0.1;
+
+/// The statistics of a table collected by `ANALYZE`, which describe the rows of the table
+/// as they were then and are not updated by later writes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableStatistics {
+    pub row_count: usize,
+    /// the statistics of the analyzed columns
+    pub columns: BTreeMap<ColumnId, ColumnStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStatistics {
+    pub null_count: usize,
+    /// the smallest value, null if all values are null
+    pub min: ScalarValue,
+    /// the largest value, null if all values are null
+    pub max: ScalarValue,
+    /// the number of distinct values other than null, estimated by HyperLogLog
+    pub distinct_count: usize,
+    /// The sorted bounds of an equi-depth histogram built from a sample of the values, so
+    /// that about the same number of values falls between each pair of adjacent bounds.
+    pub histogram: Vec<ScalarValue>,
+}
+
+impl TableStatistics {
+    /// the estimated number of rows passing the filter
+    pub fn estimated_rows(&self, filter: Option<&BoundExpr>) -> usize {
+        let selectivity = filter.map_or(1.0, |filter| self.selectivity(filter));
+        (self.row_count as f64 * selectivity).round() as usize
+    }
+
+    /// The estimated fraction of rows passing the predicate. Comparisons of a column with
+    /// a constant are estimated from the statistics of the column, assuming that the
+    /// conditions of AND and OR are independent.
+    pub fn selectivity(&self, predicate: &BoundExpr) -> f64 {
+        let BoundExpr::BinaryOp(binary_op) = predicate else {
+            return DEFAULT_SELECTIVITY;
+        };
+        match binary_op.op {
+            BinaryOperator::And => {
+                self.selectivity(&binary_op.left) * self.selectivity(&binary_op.right)
+            }
+            BinaryOperator::Or => {
+                let left = self.selectivity(&binary_op.left);
+                let right = self.selectivity(&binary_op.right);
+                left + right - left * right
+            }
+            ref op => match (
+                column_id(&binary_op.left),
+                constant(&binary_op.right),
+                constant(&binary_op.left),
+                column_id(&binary_op.right),
+            ) {
+                (Some(column), Some(value), _, _) => self.compare_selectivity(column, op, value),
+                (_, _, Some(value), Some(column)) => match flip(op) {
+                    Some(op) => self.compare_selectivity(column, &op, value),
+                    None => DEFAULT_SELECTIVITY,
+                },
+                _ => DEFAULT_SELECTIVITY,
+            },
+        }
+    }
+
+    fn compare_selectivity(&self, column: &str, op: &BinaryOperator, value: &ScalarValue) -> f64 {
+        let Some(stats) = self.columns.get(column) else {
+            return DEFAULT_SELECTIVITY;
+        };
+        if self.row_count == 0 {
+            return 0.0;
+        }
+        if value.is_null() {
+            // comparisons with null are never true
+            return 0.0;
+        }
+        let non_null = (self.row_count - stats.null_count) as f64 / self.row_count as f64;
+        let equal = if compare(value, &stats.min) == Some(Ordering::Less)
+            || compare(value, &stats.max) == Some(Ordering::Greater)
+        {
+            0.0
+        } else {
+            non_null / stats.distinct_count.max(1) as f64
+        };
+        let below = || stats.fraction_below(value).unwrap_or(DEFAULT_SELECTIVITY);
+        let selectivity = match op {
+            BinaryOperator::Eq => equal,
+            BinaryOperator::NotEq => non_null - equal,
+            BinaryOperator::Lt => non_null * below(),
+            BinaryOperator::LtEq => non_null * below() + equal,
+            BinaryOperator::Gt => non_null * (1.0 - below()) - equal,
+            BinaryOperator::GtEq => non_null * (1.0 - below()),
+            _ => DEFAULT_SELECTIVITY,
+        };
+        selectivity.clamp(0.0, 1.0)
+    }
+}
+
+impl ColumnStatistics {
+    /// The estimated fraction of the values smaller than the value, interpolated within
+    /// the bucket of the histogram having the value. None if the value is not comparable
+    /// with the values of the column.
+    pub fn fraction_below(&self, value: &ScalarValue) -> Option<f64> {
+        let (first, last) = (self.histogram.first()?, self.histogram.last()?);
+        if compare(value, first)? != Ordering::Greater {
+            return Some(0.0);
+        }
+        if compare(value, last)? == Ordering::Greater {
+            return Some(1.0);
+        }
+        let buckets = (self.histogram.len() - 1) as f64;
+        let bucket = self
+            .histogram
+            .windows(2)
+            .position(|bounds| compare(value, &bounds[1]) != Some(Ordering::Greater))?;
+        let (low, high) = (&self.histogram[bucket], &self.histogram[bucket + 1]);
+        let within = match (as_f64(value), as_f64(low), as_f64(high)) {
+            (Some(v), Some(low), Some(high)) if high > low => (v - low) / (high - low),
+            _ => 0.5,
+        };
+        Some((bucket as f64 + within) / buckets)
+    }
+}
+
+/// Collects the statistics of a table from its batches, whose columns are the columns
+/// given on creation in the same order.
+pub struct StatisticsBuilder {
+    row_count: usize,
+    columns: Vec<(ColumnId, ColumnStatisticsBuilder)>,
+}
+
+impl StatisticsBuilder {
+    pub fn new(columns: &[ColumnCatalog]) -> Result<Self, ArrowError> {
+        let columns = columns
+            .iter()
+            .map(|column| {
+                let converter =
+                    RowConverter::new(vec![SortField::new(column.desc.data_type.clone())])?;
+                Ok((column.id.clone(), ColumnStatisticsBuilder::new(converter)))
+            })
+            .collect::<Result<_, ArrowError>>()?;
+        Ok(Self {
+            row_count: 0,
+            columns,
+        })
+    }
+
+    pub fn update(&mut self, batch: &RecordBatch) -> Result<(), ArrowError> {
+        self.row_count += batch.num_rows();
+        for ((_, column), array) in self.columns.iter_mut().zip(batch.columns()) {
+            let rows = column
+                .converter
+                .convert_columns(std::slice::from_ref(array))?;
+            for (i, row) in rows.iter().enumerate() {
+                if array.is_null(i) {
+                    column.null_count += 1;
+                } else {
+                    column.add(row.owned());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<TableStatistics, ArrowError> {
+        let columns = self
+            .columns
+            .into_iter()
+            .map(|(id, column)| Ok((id, column.finish()?)))
+            .collect::<Result<_, ArrowError>>()?;
+        Ok(TableStatistics {
+            row_count: self.row_count,
+            columns,
+        })
+    }
+}
+
+/// The values of a column are compared and hashed in the row format of arrow, whose
+/// bytes sort in the order of the values of any type.
+struct ColumnStatisticsBuilder {
+    converter: RowConverter,
+    null_count: usize,
+    min: Option<OwnedRow>,
+    max: Option<OwnedRow>,
+    sketch: HyperLogLog,
+    /// a uniform sample of the values other than null, kept by reservoir sampling
+    sample: Vec<OwnedRow>,
+    /// the number of values other than null seen so far
+    seen: usize,
+    /// the state of the generator choosing the sampled values, fixed so that analyzing
+    /// the same rows gives the same statistics
+    random: u64,
+}
+
+impl ColumnStatisticsBuilder {
+    fn new(converter: RowConverter) -> Self {
+        Self {
+            converter,
+            null_count: 0,
+            min: None,
+            max: None,
+            sketch: HyperLogLog::new(),
+            sample: vec![],
+            seen: 0,
+            random: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    fn add(&mut self, row: OwnedRow) {
+        let mut hasher = DefaultHasher::new();
+        row.as_ref().hash(&mut hasher);
+        self.sketch.add(hasher.finish());
+        if self.min.as_ref().is_none_or(|min| row < *min) {
+            self.min = Some(row.clone());
+        }
+        if self.max.as_ref().is_none_or(|max| row > *max) {
+            self.max = Some(row.clone());
+        }
+        self.seen += 1;
+        if self.sample.len() < HISTOGRAM_SAMPLE_SIZE {
+            self.sample.push(row);
+        } else {
+            let index = (self.next_random() % self.seen as u64) as usize;
+            if index < HISTOGRAM_SAMPLE_SIZE {
+                self.sample[index] = row;
+            }
+        }
+    }
+
+    /// xorshift64
+    fn next_random(&mut self) -> u64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        self.random
+    }
+
+    fn finish(mut self) -> Result<ColumnStatistics, ArrowError> {
+        self.sample.sort();
+        let mut bounds = vec![];
+        if !self.sample.is_empty() {
+            let last = self.sample.len() - 1;
+            let buckets = HISTOGRAM_BUCKETS.min(last.max(1));
+            bounds = (0..=buckets)
+                .map(|i| self.sample[i * last / buckets].clone())
+                .collect();
+        }
+        let rows = [&self.min, &self.max]
+            .into_iter()
+            .flatten()
+            .chain(&bounds)
+            .map(|row| row.row());
+        let values = self.converter.convert_rows(rows)?.remove(0);
+        let values = (0..values.len())
+            .map(|i| ScalarValue::try_from_array(&values, i))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (min, max, histogram) = match values.split_at(values.len().min(2)) {
+            ([min, max], histogram) => (min.clone(), max.clone(), histogram.to_vec()),
+            _ => (ScalarValue::Null, ScalarValue::Null, vec![]),
+        };
+        Ok(ColumnStatistics {
+            null_count: self.null_count,
+            min,
+            max,
+            distinct_count: self.sketch.estimate().min(self.seen),
+            histogram,
+        })
+    }
+}
+
+/// A HyperLogLog sketch estimating the number of distinct hashes added to it.
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+
+    fn add(&mut self, hash: u64) {
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // the position of the first one bit after the bits of the index
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // linear counting is more accurate for small cardinalities
+            return (m * (m / zeros as f64).ln()).round() as usize;
+        }
+        estimate.round() as usize
+    }
+}
+
+fn column_id(expr: &BoundExpr) -> Option<&str> {
+    match expr {
+        BoundExpr::ColumnRef(column_ref) => Some(&column_ref.column_catalog.id),
+        BoundExpr::TypeCast(cast) => column_id(&cast.expr),
+        _ => None,
+    }
+}
+
+fn constant(expr: &BoundExpr) -> Option<&ScalarValue> {
+    match expr {
+        BoundExpr::Constant(value) => Some(value),
+        BoundExpr::TypeCast(cast) => constant(&cast.expr),
+        _ => None,
+    }
+}
+
+/// the operator comparing the other way round, so that `1 < a` is `a > 1`
+fn flip(op: &BinaryOperator) -> Option<BinaryOperator> {
+    Some(match op {
+        BinaryOperator::Eq => BinaryOperator::Eq,
+        BinaryOperator::NotEq => BinaryOperator::NotEq,
+        BinaryOperator::Lt => BinaryOperator::Gt,
+        BinaryOperator::LtEq => BinaryOperator::GtEq,
+        BinaryOperator::Gt => BinaryOperator::Lt,
+        BinaryOperator::GtEq => BinaryOperator::LtEq,
+        _ => return None,
+    })
+}
+
+fn as_f64(value: &ScalarValue) -> Option<f64> {
+    match value {
+        ScalarValue::Int32(Some(v)) => Some(*v as f64),
+        ScalarValue::Int64(Some(v)) => Some(*v as f64),
+        ScalarValue::Float64(Some(v)) => Some(*v),
+        _ => None,
+    }
+}
+
+/// compare values of the same kind, where numbers of any type are comparable
+fn compare(left: &ScalarValue, right: &ScalarValue) -> Option<Ordering> {
+    match (left, right) {
+        (ScalarValue::String(Some(l)), ScalarValue::String(Some(r))) => Some(l.cmp(r)),
+        (ScalarValue::Boolean(Some(l)), ScalarValue::Boolean(Some(r))) => Some(l.cmp(r)),
+        _ => as_f64(left)?.partial_cmp(&as_f64(right)?),
+    }
+}
+
+#[cfg(test)]
+mod statistics_test {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Int32Array, StringArray},
+        datatypes::DataType,
+    };
+
+    use super::*;
+    use crate::{
+        binder::expression::{binary_op::BoundBinaryOp, BoundColumnRef},
+        catalog::ColumnDesc,
+    };
+
+    fn column(name: &str, data_type: DataType) -> ColumnCatalog {
+        ColumnCatalog {
+            id: name.to_string(),
+            desc: ColumnDesc {
+                name: name.to_string(),
+                data_type,
+                nullable: true,
+                default: None,
+            },
+        }
+    }
+
+    fn compare_expr(column: ColumnCatalog, op: BinaryOperator, value: i32) -> BoundExpr {
+        BoundExpr::BinaryOp(BoundBinaryOp {
+            op,
+            left: Box::new(BoundExpr::ColumnRef(BoundColumnRef {
+                column_catalog: column,
+            })),
+            right: Box::new(BoundExpr::Constant(value.into())),
+            return_type: Some(DataType::Boolean),
+        })
+    }
+
+    #[test]
+    fn test_statistics_of_batches() -> Result<(), ArrowError> {
+        let columns = vec![column("a", DataType::Int32), column("b", DataType::Utf8)];
+        let mut builder = StatisticsBuilder::new(&columns)?;
+        for start in [0, 500] {
+            let batch = RecordBatch::try_from_iter([
+                (
+                    "a",
+                    Arc::new(Int32Array::from_iter_values(start..start + 500)) as _,
+                ),
+                (
+                    "b",
+                    Arc::new(StringArray::from_iter((start..start + 500).map(|i| {
+                        (i % 4 != 0).then(|| ["x", "y", "z"][i as usize % 3].to_string())
+                    }))) as _,
+                ),
+            ])?;
+            builder.update(&batch)?;
+        }
+        let stats = builder.finish()?;
+        assert_eq!(stats.row_count, 1000);
+
+        let a = &stats.columns["a"];
+        assert_eq!(a.null_count, 0);
+        assert_eq!((&a.min, &a.max), (&0.into(), &999.into()));
+        assert!((950..=1050).contains(&a.distinct_count));
+        assert_eq!(a.histogram.len(), HISTOGRAM_BUCKETS + 1);
+        assert_eq!(a.histogram.first(), Some(&0.into()));
+        assert_eq!(a.histogram.last(), Some(&999.into()));
+
+        let b = &stats.columns["b"];
+        assert_eq!(b.null_count, 250);
+        assert_eq!(b.distinct_count, 3);
+        assert_eq!(
+            (&b.min, &b.max),
+            (&"x".to_string().into(), &"z".to_string().into())
+        );
+
+        let a = columns[0].clone();
+        let rows = |op, value| stats.estimated_rows(Some(&compare_expr(a.clone(), op, value)));
+        assert_eq!(rows(BinaryOperator::Eq, 10), 1);
+        assert_eq!(rows(BinaryOperator::Eq, 5000), 0);
+        assert!((240..=260).contains(&rows(BinaryOperator::Lt, 250)));
+        assert!((740..=760).contains(&rows(BinaryOperator::GtEq, 250)));
+        assert_eq!(stats.estimated_rows(None), 1000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_statistics_of_empty_table() -> Result<(), ArrowError> {
+        let stats = StatisticsBuilder::new(&[column("a", DataType::Int32)])?.finish()?;
+        assert_eq!(stats.row_count, 0);
+        let a = &stats.columns["a"];
+        assert_eq!((a.distinct_count, a.min.is_null()), (0, true));
+        assert!(a.histogram.is_empty());
+        Ok(())
+    }
+}
@@ -1,23 +1,53 @@
-use std::sync::Arc;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 use arrow::{array::RecordBatch, error::ArrowError};
-use sqlparser::parser::ParserError;
+use sqlparser::{ast::Statement, parser::ParserError};
 use thiserror::Error;
 
 use crate::{
-    binder::{BindError, Binder},
+    binder::{statement::BoundStatement, BindError, Binder},
     executor::{try_collect, ExecutorBuilder, ExecutorError},
+    function::{TableFunction, TableFunctions},
     optimizer::{
+        common_subexpr_eliminator::CommonSubexprEliminator, expr_simplifier::ExprSimplifier,
         input_ref_rewriter::InputRefRewriter, physical_rewriter::PhysicalRewriter,
-        plan_rewriter::PlanRewriter,
+        plan_rewriter::PlanRewriter, plan_validator::PlanValidator, PlanRef,
     },
     parser::parse,
     planner::{LogicalPlanError, Planner},
-    storage::{CsvStorage, Storage, StorageError, StorageImpl},
+    storage::{
+        CsvConfig, CsvStorage, DiskStorage, InMemoryStorage, Storage, StorageError,
+        StorageRegistry, Transaction,
+    },
+    substrait::{proto::Plan, SubstraitConsumer, SubstraitError, SubstraitProducer},
 };
 
+/// A session on the storages mounted into the database. Every statement runs in a
+/// transaction of its own, unless the session started one with `BEGIN`, which lasts until
+/// `COMMIT` or `ROLLBACK`.
 pub struct Database {
-    storage: StorageImpl,
+    storage: Arc<StorageRegistry>,
+    /// the table functions callable in FROM, shared by the sessions
+    table_functions: Arc<Mutex<TableFunctions>>,
+    txn: Mutex<Option<SessionTxn>>,
+    /// the number of partitions the queries run in
+    parallelism: usize,
+}
+
+#[derive(Clone)]
+enum SessionTxn {
+    Active(Arc<dyn Transaction>),
+    /// a statement of the transaction failed, which rolled the transaction back
+    Aborted,
+}
+
+/// a query to run, either a SQL statement or a logical plan resolved already
+enum Query<'a> {
+    Sql(&'a Statement),
+    Plan(PlanRef),
 }
 
 #[derive(Error, Debug)]
@@ -60,63 +90,742 @@ pub enum DatabaseError {
         #[backtrace]
         ArrowError,
     ),
+    #[error("substrait error: {0}")]
+    Substrait(
+        #[source]
+        #[from]
+        SubstraitError,
+    ),
+    #[error("transaction error: {0}")]
+    Transaction(String),
     #[error("Internal error: {0}")]
     InternalError(String),
 }
 
 impl Database {
+    /// create a database on the storage, where tables are created by default
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Database {
+            storage: Arc::new(StorageRegistry::new(storage)),
+            table_functions: Arc::new(Mutex::new(TableFunctions::default())),
+            txn: Mutex::new(None),
+            parallelism: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    /// Run the queries in up to `parallelism` partitions on the tokio runtime, which
+    /// defaults to the number of cores. Queries run outside of a runtime, or with a
+    /// parallelism of 1, run in a single partition.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
     pub fn new_on_csv() -> Self {
-        let storage = Arc::new(CsvStorage::new());
+        Self::new(Arc::new(CsvStorage::new()))
+    }
+
+    pub fn new_in_memory() -> Self {
+        Self::new(Arc::new(InMemoryStorage::new()))
+    }
+
+    /// open a database persisted in the data directory, reloading the tables created by
+    /// earlier runs on the same directory
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        Ok(Self::new(Arc::new(DiskStorage::open(dir)?)))
+    }
+
+    /// start another session on the same storages
+    pub fn new_session(&self) -> Database {
         Database {
-            storage: StorageImpl::CsvStorage(storage),
+            storage: self.storage.clone(),
+            table_functions: self.table_functions.clone(),
+            txn: Mutex::new(None),
+            parallelism: self.parallelism,
         }
     }
 
+    /// make the table function callable in FROM by the name, replacing a builtin or
+    /// registered function of the same name
+    pub fn register_table_function(&self, name: &str, function: Arc<dyn TableFunction>) {
+        self.table_functions
+            .lock()
+            .unwrap()
+            .register(name, function);
+    }
+
+    /// mount another storage, so that its tables can be queried along with the tables of
+    /// the storages mounted already
+    pub fn mount(&self, storage: Arc<dyn Storage>) -> Result<(), DatabaseError> {
+        Ok(self.storage.mount(storage)?)
+    }
+
+    /// create a table on the csv file, which is loaded into the default storage unless it
+    /// is csv storage
     pub fn create_csv_table(
         &self,
         table_name: String,
         filepath: String,
     ) -> Result<(), DatabaseError> {
-        if let StorageImpl::CsvStorage(ref storage) = self.storage {
-            storage.create_csv_table(table_name, filepath)?;
-            Ok(())
-        } else {
-            Err(DatabaseError::InternalError(
-                "currently only support csv storage".to_string(),
-            ))
-        }
+        Ok(self
+            .storage
+            .create_csv_table(table_name, filepath, CsvConfig::default())?)
     }
 
     pub async fn run(&self, sql: &str) -> Result<Vec<RecordBatch>, DatabaseError> {
-        let storage = if let StorageImpl::CsvStorage(ref storage) = self.storage {
-            storage
-        } else {
-            return Err(DatabaseError::InternalError(
-                "currently only support csv storage".to_string(),
-            ));
-        };
+        let stats = parse(sql)?;
+        match &stats[0] {
+            Statement::StartTransaction { .. } => return self.begin().map(|_| vec![]),
+            Statement::Commit { .. } => return self.commit().map(|_| vec![]),
+            Statement::Rollback {
+                savepoint: Some(_), ..
+            } => {
+                return Err(DatabaseError::Transaction(
+                    "savepoints are not supported".to_string(),
+                ))
+            }
+            Statement::Rollback { .. } => return self.rollback().map(|_| vec![]),
+            _ => {}
+        }
 
+        self.run_query(Query::Sql(&stats[0])).await
+    }
+
+    /// translate the statement into a Substrait plan of the logical plan it runs
+    pub fn to_substrait(&self, sql: &str) -> Result<Plan, DatabaseError> {
         let stats = parse(sql)?;
-        let catalog = storage.get_catalog();
-        let mut binder = Binder::new(Arc::new(catalog));
-        let bound_stmt = binder.bind(&stats[0])?;
-        println!("bound_stmt = {:#?}", bound_stmt);
+        let logical_plan = self.plan(&stats[0], false)?;
+        Ok(SubstraitProducer::default().produce(logical_plan)?)
+    }
 
-        let planner = Planner {};
-        let logical_plan = planner.plan(bound_stmt)?;
-        println!("logical_plan = {:#?}", logical_plan);
-        let mut input_ref_rewriter = InputRefRewriter::default();
-        let new_logical_plan = input_ref_rewriter.rewrite(logical_plan);
-        println!("new_logical_plan = {:#?}", new_logical_plan);
+    /// run a Substrait plan, which is produced by `to_substrait` or by other tools
+    pub async fn run_substrait(&self, plan: &Plan) -> Result<Vec<RecordBatch>, DatabaseError> {
+        let catalog = Arc::new(self.storage.get_catalog());
+        let logical_plan = SubstraitConsumer::new(catalog).consume(plan)?;
+        self.run_query(Query::Plan(logical_plan)).await
+    }
+
+    /// run the query in the transaction of the session, or in a transaction of its own
+    async fn run_query(&self, query: Query<'_>) -> Result<Vec<RecordBatch>, DatabaseError> {
+        let session_txn = self.txn.lock().unwrap().clone();
+        match session_txn {
+            Some(SessionTxn::Active(txn)) => {
+                let output = self.execute(&query, txn.clone(), true).await;
+                if output.is_err() {
+                    txn.rollback();
+                    *self.txn.lock().unwrap() = Some(SessionTxn::Aborted);
+                }
+                output
+            }
+            Some(SessionTxn::Aborted) => Err(DatabaseError::Transaction(
+                "the transaction is aborted, statements are ignored until ROLLBACK".to_string(),
+            )),
+            None => {
+                let txn = self.storage.begin()?;
+                match self.execute(&query, txn.clone(), false).await {
+                    Ok(output) => {
+                        txn.commit()?;
+                        Ok(output)
+                    }
+                    Err(e) => {
+                        txn.rollback();
+                        Err(e)
+                    }
+                }
+            }
+        }
+    }
+
+    async fn execute(
+        &self,
+        query: &Query<'_>,
+        txn: Arc<dyn Transaction>,
+        in_transaction: bool,
+    ) -> Result<Vec<RecordBatch>, DatabaseError> {
+        let logical_plan = match query {
+            Query::Sql(stmt) => self.plan(stmt, in_transaction)?,
+            Query::Plan(plan) => plan.clone(),
+        };
+        PlanValidator::default().validate(&logical_plan)?;
 
         let mut physical_rewriter = PhysicalRewriter {};
-        let physical_plan = physical_rewriter.rewrite(new_logical_plan);
-        println!("Physical plan = {:#?}", physical_plan);
+        let physical_plan = physical_rewriter.rewrite(logical_plan);
 
-        let mut builder = ExecutorBuilder::new(StorageImpl::CsvStorage(storage.clone()));
+        let mut builder =
+            ExecutorBuilder::new(self.storage.clone(), txn).with_parallelism(self.parallelism);
         let executor = builder.build(physical_plan);
 
         let output = try_collect(executor).await?;
         Ok(output)
     }
+
+    /// the logical plan of the statement, with its exprs resolved into input refs
+    fn plan(&self, stmt: &Statement, in_transaction: bool) -> Result<PlanRef, DatabaseError> {
+        let catalog = self.storage.get_catalog();
+        let table_functions = self.table_functions.lock().unwrap().clone();
+        let mut binder = Binder::new(Arc::new(catalog)).with_table_functions(table_functions);
+        let bound_stmt = binder.bind(stmt)?;
+        if in_transaction
+            && matches!(
+                bound_stmt,
+                BoundStatement::CreateTable(_) | BoundStatement::DropTable(_)
+            )
+        {
+            return Err(DatabaseError::Transaction(
+                "CREATE TABLE and DROP TABLE are not supported in a transaction".to_string(),
+            ));
+        }
+
+        let planner = Planner {};
+        let logical_plan = planner.plan(bound_stmt)?;
+        let mut expr_simplifier = ExprSimplifier::default();
+        let logical_plan = expr_simplifier.rewrite(logical_plan);
+        let mut common_subexpr_eliminator = CommonSubexprEliminator::default();
+        let logical_plan = common_subexpr_eliminator.rewrite(logical_plan);
+        let mut input_ref_rewriter = InputRefRewriter::default();
+        let new_logical_plan = input_ref_rewriter.rewrite(logical_plan);
+        Ok(new_logical_plan)
+    }
+
+    fn begin(&self) -> Result<(), DatabaseError> {
+        let mut session_txn = self.txn.lock().unwrap();
+        if session_txn.is_some() {
+            return Err(DatabaseError::Transaction(
+                "a transaction is already in progress".to_string(),
+            ));
+        }
+        *session_txn = Some(SessionTxn::Active(self.storage.begin()?));
+        Ok(())
+    }
+
+    /// commit the transaction of the session, which ends even if the commit fails
+    fn commit(&self) -> Result<(), DatabaseError> {
+        match self.txn.lock().unwrap().take() {
+            Some(SessionTxn::Active(txn)) => Ok(txn.commit()?),
+            Some(SessionTxn::Aborted) => Err(DatabaseError::Transaction(
+                "the transaction was aborted and has been rolled back".to_string(),
+            )),
+            None => Err(DatabaseError::Transaction(
+                "no transaction is in progress".to_string(),
+            )),
+        }
+    }
+
+    fn rollback(&self) -> Result<(), DatabaseError> {
+        match self.txn.lock().unwrap().take() {
+            Some(SessionTxn::Active(txn)) => txn.rollback(),
+            Some(SessionTxn::Aborted) => {}
+            None => {
+                return Err(DatabaseError::Transaction(
+                    "no transaction is in progress".to_string(),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod db_test {
+    use arrow::{
+        array::Int32Array,
+        datatypes::{DataType, Field, Schema},
+        error::ArrowError,
+    };
+
+    use super::*;
+    use crate::{
+        catalog::{ColumnCatalog, ColumnDesc, RootCatalog, TableCatalog},
+        function::{FunctionTable, TableFunctionArgs},
+        storage::{Table, TableReader},
+        utill::record_batch_to_string,
+    };
+
+    /// A read-only source of a single table `numbers (n int)` holding 1, 2 and 3.
+    struct NumbersStorage;
+
+    struct NumbersTable;
+
+    impl Storage for NumbersStorage {
+        fn begin(&self) -> Result<Arc<dyn Transaction>, StorageError> {
+            Ok(Arc::new(NumbersStorage))
+        }
+
+        fn get_catalog(&self) -> RootCatalog {
+            let column = ColumnCatalog {
+                id: "n".to_string(),
+                desc: ColumnDesc {
+                    name: "n".to_string(),
+                    data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
+                },
+            };
+            let table = TableCatalog {
+                id: "numbers".to_string(),
+                name: "numbers".to_string(),
+                column_ids: vec!["n".to_string()],
+                columns: [("n".to_string(), column)].into(),
+                statistics: None,
+            };
+            RootCatalog {
+                tables: [("numbers".to_string(), table)].into(),
+            }
+        }
+    }
+
+    impl Transaction for NumbersStorage {
+        fn get_table(&self, id: String) -> Result<Arc<dyn Table>, StorageError> {
+            match id.as_str() {
+                "numbers" => Ok(Arc::new(NumbersTable)),
+                _ => Err(StorageError::TableNotFound(id)),
+            }
+        }
+
+        fn commit(&self) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        fn rollback(&self) {}
+    }
+
+    impl Table for NumbersTable {
+        fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+            let schema = Schema::new(vec![Field::new("n", DataType::Int32, false)]);
+            let batch = RecordBatch::try_new(
+                Arc::new(schema),
+                vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+            )?;
+            Ok(Box::new(Some(batch)))
+        }
+    }
+
+    impl TableReader for Option<RecordBatch> {
+        fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError> {
+            Ok(self.take())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_database_reopens_on_same_dir() -> Result<(), DatabaseError> {
+        let dir = tempfile::tempdir().map_err(StorageError::from)?;
+        {
+            let db = Database::open(dir.path())?;
+            db.run("create table t (a int, b varchar)").await?;
+            db.run("insert into t values (1, 'x'), (2, 'y'), (3, 'z')")
+                .await?;
+            db.run("delete from t where a = 2").await?;
+        }
+
+        let db = Database::open(dir.path())?;
+        let output = db.run("select a, b from t").await?;
+        let output = output
+            .iter()
+            .map(record_batch_to_string)
+            .collect::<Result<String, _>>()?;
+        assert_eq!(output, "1 x\n3 z\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_keeps_not_null_columns_and_defaults() -> Result<(), DatabaseError> {
+        let dir = tempfile::tempdir().map_err(StorageError::from)?;
+        {
+            let db = Database::open(dir.path())?;
+            db.run("create table t (a int not null, b varchar default 'x')")
+                .await?;
+        }
+
+        let db = Database::open(dir.path())?;
+        db.run("insert into t (a) values (1)").await?;
+        assert_eq!(query(&db, "select a, b from t").await?, "1 x\n");
+        assert!(matches!(
+            db.run("insert into t (b) values ('y')").await,
+            Err(DatabaseError::Execute(ExecutorError::NotNullViolation(c))) if c == "a"
+        ));
+        let schema = db.run("select a, b from t").await?[0].schema();
+        assert!(!schema.field(0).is_nullable());
+        assert!(schema.field(1).is_nullable());
+        Ok(())
+    }
+
+    async fn query(db: &Database, sql: &str) -> Result<String, DatabaseError> {
+        let output = db.run(sql).await?;
+        Ok(output
+            .iter()
+            .map(record_batch_to_string)
+            .collect::<Result<String, _>>()?)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_database_runs_queries_in_partitions() -> Result<(), DatabaseError> {
+        let db = Database::new_in_memory().with_parallelism(4);
+        db.run("create table t (a int, b varchar)").await?;
+        for i in 0..10 {
+            let rows = (0..10)
+                .map(|j| format!("({}, 'g{}')", i * 10 + j, j % 3))
+                .collect::<Vec<_>>();
+            db.run(&format!("insert into t values {}", rows.join(", ")))
+                .await?;
+        }
+
+        // the rows of the partitions are gathered in order
+        let rows = query(&db, "select a from t where a > 20").await?;
+        let expected = (21..100).map(|a| format!("{a}\n")).collect::<String>();
+        assert_eq!(rows, expected);
+        assert_eq!(
+            query(
+                &db,
+                "select count(a), sum(a), min(a), max(a) from t where a > 10"
+            )
+            .await?,
+            "89 4895 11 99\n"
+        );
+
+        // the groups are combined across partitions, in no particular order
+        let mut groups = query(&db, "select b, count(a), sum(a), max(a) from t group by b")
+            .await?
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        groups.sort();
+        assert_eq!(groups, ["g0 40 1980 99", "g1 30 1470 97", "g2 30 1500 98"]);
+
+        // string_agg cannot combine partial results, so it aggregates the gathered rows
+        assert_eq!(
+            query(&db, "select string_agg(b, ',') from t where a < 4").await?,
+            "g0,g1,g2,g0\n"
+        );
+
+        // the partial sums fit in an int, while the final sum of them overflows
+        db.run("create table u (a int)").await?;
+        for _ in 0..4 {
+            db.run("insert into u values (1000000000)").await?;
+        }
+        assert!(matches!(
+            db.run("select sum(a) from u").await,
+            Err(DatabaseError::Execute(ExecutorError::Arrow(
+                ArrowError::ArithmeticOverflow(_)
+            )))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_database_scans_csv_values_holding_newlines() -> Result<(), DatabaseError> {
+        let dir = tempfile::tempdir().map_err(StorageError::from)?;
+        let path = dir.path().join("notes.csv");
+        let lines = (0..2000)
+            .map(|i| format!("{i},\"note {i}\nsecond line, {}\n\"\n", i * 50))
+            .collect::<String>();
+        std::fs::write(&path, format!("id,note\n{lines}")).map_err(StorageError::from)?;
+        let db = Database::new_on_csv().with_parallelism(4);
+        db.create_csv_table("notes".to_string(), path.display().to_string())?;
+        assert_eq!(
+            query(&db, "select count(id), max(id) from notes").await?,
+            "2000 1999\n"
+        );
+
+        // the lines of a file without them are split between the partitions
+        let path = dir.path().join("ids.csv");
+        let lines = (0..2000).map(|i| format!("{i}\n")).collect::<String>();
+        std::fs::write(&path, format!("id\n{lines}")).map_err(StorageError::from)?;
+        db.run(&format!(
+            "create external table ids (id int) stored as csv \
+             with (newlines_in_values false) location '{}'",
+            path.display()
+        ))
+        .await?;
+        assert_eq!(
+            query(&db, "select count(id), max(id) from ids").await?,
+            "2000 1999\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_isolates_sessions() -> Result<(), DatabaseError> {
+        let db = Database::new_in_memory();
+        let other = db.new_session();
+        db.run("create table t (a int)").await?;
+        db.run("insert into t values (1)").await?;
+
+        db.run("begin").await?;
+        db.run("insert into t values (2)").await?;
+        assert_eq!(query(&db, "select count(a) from t").await?, "2\n");
+        // the other session neither sees uncommitted rows nor rows committed after its
+        // transaction began
+        other.run("begin").await?;
+        assert_eq!(query(&other, "select count(a) from t").await?, "1\n");
+        db.run("commit").await?;
+        assert_eq!(query(&other, "select count(a) from t").await?, "1\n");
+        other.run("commit").await?;
+        assert_eq!(query(&other, "select count(a) from t").await?, "2\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_detects_write_conflicts() -> Result<(), DatabaseError> {
+        let db = Database::new_in_memory();
+        let other = db.new_session();
+        db.run("create table t (a int)").await?;
+        db.run("insert into t values (1), (2)").await?;
+
+        db.run("begin").await?;
+        other.run("begin").await?;
+        db.run("update t set a = 3 where a = 1").await?;
+        other.run("delete from t where a = 2").await?;
+        db.run("commit").await?;
+        assert!(matches!(
+            other.run("commit").await,
+            Err(DatabaseError::StorageError(StorageError::WriteConflict(_)))
+        ));
+        // the losing transaction is over, and none of its writes are applied
+        assert!(other.run("rollback").await.is_err());
+        assert_eq!(query(&other, "select a from t").await?, "3\n2\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_queries_across_mounted_storages() -> Result<(), DatabaseError> {
+        let db = Database::new_in_memory();
+        let csv = Arc::new(CsvStorage::new());
+        csv.create_csv_table(
+            "employee".to_string(),
+            "./tests/csv/sample.csv".to_string(),
+            CsvConfig::default(),
+        )?;
+        db.mount(csv)?;
+        db.mount(Arc::new(NumbersStorage))?;
+
+        db.run("create table t (n int)").await?;
+        db.run("insert into t select n from numbers where n > 1")
+            .await?;
+        db.run("insert into t select id from employee where first_name = 'Bill'")
+            .await?;
+        assert_eq!(query(&db, "select n from t").await?, "2\n3\n1\n");
+        assert!(matches!(
+            db.run("insert into numbers values (4)").await,
+            Err(DatabaseError::Execute(ExecutorError::Storage(
+                StorageError::Unsupported(_)
+            )))
+        ));
+        assert!(matches!(
+            db.mount(Arc::new(NumbersStorage)),
+            Err(DatabaseError::StorageError(StorageError::TableExists(_)))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_scans_partitioned_csv_tables() -> Result<(), DatabaseError> {
+        let db = Database::new_on_csv();
+        db.run("create external table orders stored as csv location './tests/external/orders'")
+            .await?;
+        assert_eq!(
+            query(&db, "select id from orders where year = 2026 and month = 2").await?,
+            "4\n5\n6\n"
+        );
+        assert_eq!(
+            query(&db, "select id from orders where month = 1 or amount > 50").await?,
+            "3\n6\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_queries_arrow_files() -> Result<(), DatabaseError> {
+        let dir = tempfile::tempdir().map_err(StorageError::from)?;
+        let path = dir.path().join("numbers.arrow");
+        let batch =
+            RecordBatch::try_from_iter([("n", Arc::new(Int32Array::from(vec![1, 2, 3])) as _)])?;
+        let file = std::fs::File::create(&path).map_err(StorageError::from)?;
+        let mut writer = arrow::ipc::writer::FileWriter::try_new(file, &batch.schema())?;
+        writer.write(&batch)?;
+        writer.finish()?;
+
+        let db = Database::new_on_csv();
+        db.run(&format!(
+            "create external table numbers stored as arrow location '{}'",
+            path.display()
+        ))
+        .await?;
+        assert_eq!(
+            query(&db, "select n from numbers where n > 1").await?,
+            "2\n3\n"
+        );
+        assert!(db
+            .run("create external table t (n int) stored as arrow location 'x.arrow'")
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_copies_query_results_to_files() -> Result<(), DatabaseError> {
+        let dir = tempfile::tempdir().map_err(StorageError::from)?;
+        let db = Database::new_in_memory();
+        db.run("create table t (a int, b varchar)").await?;
+        db.run("insert into t values (1, 'x'), (2, null), (3, 'z')")
+            .await?;
+
+        for (format, file) in [("csv", "t.csv"), ("json", "t.json"), ("arrow", "t.arrow")] {
+            let path = dir.path().join(file);
+            assert_eq!(
+                query(
+                    &db,
+                    &format!(
+                        "copy (select a, b from t where a > 1) to '{}' (format {format})",
+                        path.display()
+                    )
+                )
+                .await?,
+                "2\n"
+            );
+            db.run(&format!("create table {format} (a int, b varchar)"))
+                .await?;
+            db.run(&format!(
+                "copy {format} from '{}' (format {format})",
+                path.display()
+            ))
+            .await?;
+            assert_eq!(
+                query(&db, &format!("select a, b from {format}")).await?,
+                "2 NULL\n3 z\n"
+            );
+        }
+
+        let path = dir.path().join("empty.arrow");
+        db.run(&format!(
+            "copy (select a from t where a > 3) to '{}' (format arrow)",
+            path.display()
+        ))
+        .await?;
+        assert!(path.exists());
+        // parquet files are not supported yet
+        assert!(matches!(
+            db.run(&format!(
+                "copy t to '{}' (format parquet)",
+                dir.path().join("t.parquet").display()
+            ))
+            .await,
+            Err(DatabaseError::Bind(BindError::InvalidTableOption(e))) if e == "unsupported format parquet"
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_fails_copying_values_which_do_not_fit() -> Result<(), DatabaseError> {
+        let dir = tempfile::tempdir().map_err(StorageError::from)?;
+        let path = dir.path().join("big.arrow");
+        let db = Database::new_in_memory();
+        db.run("create table big (a bigint)").await?;
+        db.run("insert into big values (1), (3000000000)").await?;
+        db.run(&format!("copy big to '{}' (format arrow)", path.display()))
+            .await?;
+        db.run("create table small (a int)").await?;
+        assert!(matches!(
+            db.run(&format!(
+                "copy small from '{}' (format arrow)",
+                path.display()
+            ))
+            .await,
+            Err(DatabaseError::Execute(ExecutorError::Arrow(_)))
+        ));
+        assert_eq!(
+            query(&db, "select count(a) from small").await?,
+            "0
+"
+        );
+        Ok(())
+    }
+
+    /// A table function `numbers()` returning the table of [`NumbersStorage`].
+    struct NumbersFunction;
+
+    impl TableFunction for NumbersFunction {
+        fn call(&self, args: &TableFunctionArgs) -> Result<FunctionTable, BindError> {
+            args.expect_positional(0, 0)?;
+            Ok(FunctionTable {
+                schema: Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, false)])),
+                table: Arc::new(NumbersTable),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_database_calls_registered_table_functions() -> Result<(), DatabaseError> {
+        let db = Database::new_in_memory();
+        assert!(matches!(
+            db.run("select n from numbers()").await,
+            Err(DatabaseError::Bind(BindError::InvalidTableFunction(_)))
+        ));
+        db.register_table_function("numbers", Arc::new(NumbersFunction));
+        let session = db.new_session();
+        assert_eq!(
+            query(&session, "select n from numbers() where n > 1").await?,
+            "2\n3\n"
+        );
+        assert!(matches!(
+            db.run("select n from numbers(1)").await,
+            Err(DatabaseError::Bind(BindError::InvalidFunctionArgs(..)))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_runs_substrait_plans() -> Result<(), DatabaseError> {
+        let db = Database::new_in_memory();
+        db.run("create table t (a int, b bigint, c varchar)")
+            .await?;
+        db.run("insert into t values (1, 10, 'x'), (2, 20, 'y'), (3, 30, 'x')")
+            .await?;
+        for sql in [
+            "select a, b * 2 from t where a > 1",
+            "select c, sum(b), count(a) from t where c = 'x' group by c",
+            "select a + 1, (a + 1) * 2 from t where a + 1 < 4",
+            "select 1 + 2",
+        ] {
+            let plan = db.to_substrait(sql)?;
+            let json = serde_json::to_string(&plan).unwrap();
+            let plan: Plan = serde_json::from_str(&json).unwrap();
+            let output = db
+                .run_substrait(&plan)
+                .await?
+                .iter()
+                .map(record_batch_to_string)
+                .collect::<Result<String, _>>()?;
+            assert_eq!(output, query(&db, sql).await?, "{json}");
+        }
+
+        let plan = db.to_substrait("select a from t")?;
+        db.run("drop table t").await?;
+        assert!(matches!(
+            db.run_substrait(&plan).await,
+            Err(DatabaseError::Substrait(SubstraitError::TableNotFound(_)))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_database_plans_scans_with_analyzed_statistics() -> Result<(), DatabaseError> {
+        let db = Database::new_in_memory();
+        db.run("create table t as select * from generate_series(1, 1000) as s(a)")
+            .await?;
+        let estimated_rows = || -> Result<Option<usize>, DatabaseError> {
+            let stmt = &parse("select a from t where a <= 250")?[0];
+            let mut binder = Binder::new(Arc::new(db.storage.get_catalog()));
+            let plan = Planner {}.plan(binder.bind(stmt)?)?;
+            let scan = plan.children()[0].children()[0].clone();
+            Ok(scan.as_logical_table_scan().unwrap().estimated_rows())
+        };
+        assert_eq!(estimated_rows()?, None);
+
+        db.run("analyze t").await?;
+        let table = db.storage.get_catalog().get_table_by_name("t").unwrap();
+        assert_eq!(table.statistics.unwrap().row_count, 1000);
+        let rows = estimated_rows()?.unwrap();
+        assert!((240..=260).contains(&rows), "estimated {rows} rows");
+        Ok(())
+    }
 }
@@ -0,0 +1,296 @@
+mod ordered;
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray, Int32Array, Int64Array, RecordBatch},
+    compute,
+    datatypes::{DataType, Field, Float64Type, Int32Type, Int64Type},
+    error::ArrowError,
+};
+use itertools::Itertools;
+
+use crate::{
+    binder::expression::{
+        agg_func::{AggFunc, BoundAggFunc},
+        BoundExpr, BoundInputRef,
+    },
+    executor::{
+        aggregation::ordered::{ArrayAggAccumulator, PercentileAccumulator, StringAggAccumulator},
+        ExecutorError,
+    },
+    types::{build_array_from_scalar_values, ScalarValue},
+};
+
+/// The state of an aggregate function over a group of rows.
+pub trait Accumulator: Send + Sync {
+    /// Update the state with the evaluated arguments of a batch of rows, followed by
+    /// the evaluated ORDER BY keys of the agg func.
+    fn update_batch(&mut self, columns: &[ArrayRef]) -> Result<(), ExecutorError>;
+
+    /// Get the aggregated value of all rows seen so far as an array of one element.
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError>;
+}
+
+pub fn create_accumulator(agg: &BoundAggFunc) -> Box<dyn Accumulator> {
+    match agg.func {
+        AggFunc::Count => Box::new(CountAccumulator::new()),
+        AggFunc::Sum => Box::new(SumAccumulator::new(&agg.return_type)),
+        AggFunc::Min => Box::new(MinMaxAccumulator::new(&agg.return_type, true)),
+        AggFunc::Max => Box::new(MinMaxAccumulator::new(&agg.return_type, false)),
+        AggFunc::Grouping => unreachable!("GROUPING is evaluated from the grouping set"),
+        AggFunc::StringAgg => Box::new(StringAggAccumulator::new(&agg.order_by)),
+        AggFunc::ArrayAgg => Box::new(ArrayAggAccumulator::new(&agg.return_type, &agg.order_by)),
+        AggFunc::PercentileCont | AggFunc::PercentileDisc => Box::new(PercentileAccumulator::new(
+            &agg.return_type,
+            &agg.order_by[0],
+            agg.func == AggFunc::PercentileCont,
+        )),
+    }
+}
+
+/// Evaluate the input columns of agg func on a batch: the arguments, the ORDER BY keys,
+/// and the FILTER predicate at last if there is one.
+pub fn eval_agg_columns(
+    agg: &BoundAggFunc,
+    batch: &RecordBatch,
+) -> Result<Vec<ArrayRef>, ExecutorError> {
+    agg.exprs
+        .iter()
+        .chain(agg.order_by.iter().map(|o| &o.expr))
+        .chain(agg.filter.iter().map(|f| f.as_ref()))
+        .map(|e| e.eval_column(batch))
+        .try_collect()
+}
+
+/// Feed the columns from [`eval_agg_columns`] into the accumulator, skipping the rows
+/// rejected by the FILTER predicate.
+pub fn update_accumulator(
+    acc: &mut dyn Accumulator,
+    agg: &BoundAggFunc,
+    columns: &[ArrayRef],
+) -> Result<(), ExecutorError> {
+    if agg.filter.is_none() {
+        return acc.update_batch(columns);
+    }
+    let (predicate, columns) = columns.split_last().unwrap();
+    let predicate = predicate.as_boolean();
+    let columns: Vec<ArrayRef> = columns
+        .iter()
+        .map(|c| compute::filter(c, predicate))
+        .try_collect()?;
+    acc.update_batch(&columns)
+}
+
+/// Unwrap the agg funcs collected by planner, which are always `BoundExpr::AggFunc`.
+pub fn as_agg_funcs(exprs: &[BoundExpr]) -> Vec<BoundAggFunc> {
+    exprs
+        .iter()
+        .map(|expr| match expr {
+            BoundExpr::AggFunc(agg) => agg.clone(),
+            _ => unreachable!("expected agg func, got {:?}", expr),
+        })
+        .collect()
+}
+
+/// The agg funcs combining the results of the agg funcs over partitions of the rows, which
+/// read the partial result of `agg_funcs[i]` from the column `offset + i`. Only count,
+/// sum, min and max without ORDER BY can be combined, and their FILTER is applied by the
+/// partial aggs.
+pub fn final_agg_funcs(agg_funcs: &[BoundExpr], offset: usize) -> Option<Vec<BoundExpr>> {
+    as_agg_funcs(agg_funcs)
+        .into_iter()
+        .enumerate()
+        .map(|(i, agg)| {
+            let func = match agg.func {
+                AggFunc::Count | AggFunc::Sum => AggFunc::Sum,
+                AggFunc::Min => AggFunc::Min,
+                AggFunc::Max => AggFunc::Max,
+                _ => return None,
+            };
+            if !agg.order_by.is_empty() {
+                return None;
+            }
+            let partial = BoundExpr::InputRef(BoundInputRef {
+                index: offset + i,
+                return_type: agg.return_type.clone(),
+            });
+            Some(BoundExpr::AggFunc(BoundAggFunc {
+                func,
+                exprs: vec![partial],
+                return_type: agg.return_type,
+                filter: None,
+                order_by: vec![],
+            }))
+        })
+        .collect()
+}
+
+pub fn agg_field(agg: &BoundAggFunc) -> Field {
+    Field::new(
+        format!("{:?}", agg.func).to_lowercase(),
+        agg.return_type.clone(),
+        true,
+    )
+}
+
+pub struct CountAccumulator {
+    count: i64,
+}
+
+impl CountAccumulator {
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+}
+
+impl Accumulator for CountAccumulator {
+    fn update_batch(&mut self, columns: &[ArrayRef]) -> Result<(), ExecutorError> {
+        let array = &columns[0];
+        self.count += (array.len() - array.logical_null_count()) as i64;
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError> {
+        Ok(Arc::new(Int64Array::from(vec![self.count])))
+    }
+}
+
+pub struct SumAccumulator {
+    result: ScalarValue,
+    data_type: DataType,
+}
+
+impl SumAccumulator {
+    pub fn new(data_type: &DataType) -> Self {
+        Self {
+            result: ScalarValue::new_null(data_type),
+            data_type: data_type.clone(),
+        }
+    }
+}
+
+impl Accumulator for SumAccumulator {
+    fn update_batch(&mut self, columns: &[ArrayRef]) -> Result<(), ExecutorError> {
+        let array = &columns[0];
+        let batch_sum = match array.data_type() {
+            DataType::Int32 => {
+                ScalarValue::Int32(compute::sum_checked(array.as_primitive::<Int32Type>())?)
+            }
+            DataType::Int64 => {
+                ScalarValue::Int64(compute::sum_checked(array.as_primitive::<Int64Type>())?)
+            }
+            DataType::Float64 => {
+                ScalarValue::Float64(compute::sum(array.as_primitive::<Float64Type>()))
+            }
+            data_type => return Err(ExecutorError::UnsupportedAggArgument(data_type.clone())),
+        };
+        let overflow =
+            || ArrowError::ArithmeticOverflow(format!("sum overflows {}", self.data_type));
+        self.result = match (&self.result, batch_sum) {
+            (_, value) if value.is_null() => self.result.clone(),
+            (result, value) if result.is_null() => value,
+            (ScalarValue::Int32(Some(a)), ScalarValue::Int32(Some(b))) => {
+                ScalarValue::Int32(Some(a.checked_add(b).ok_or_else(overflow)?))
+            }
+            (ScalarValue::Int64(Some(a)), ScalarValue::Int64(Some(b))) => {
+                ScalarValue::Int64(Some(a.checked_add(b).ok_or_else(overflow)?))
+            }
+            (ScalarValue::Float64(Some(a)), ScalarValue::Float64(Some(b))) => {
+                ScalarValue::Float64(Some(a + b))
+            }
+            (result, value) => unreachable!("sum type mismatch: {:?} + {:?}", result, value),
+        };
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError> {
+        Ok(build_array_from_scalar_values(
+            &self.data_type,
+            std::slice::from_ref(&self.result),
+        ))
+    }
+}
+
+pub struct MinMaxAccumulator {
+    result: ScalarValue,
+    data_type: DataType,
+    is_min: bool,
+}
+
+impl MinMaxAccumulator {
+    pub fn new(data_type: &DataType, is_min: bool) -> Self {
+        Self {
+            result: ScalarValue::new_null(data_type),
+            data_type: data_type.clone(),
+            is_min,
+        }
+    }
+}
+
+macro_rules! min_max_batch {
+    ($ARRAY:expr, $PRIMITIVE:ident, $STRING:ident) => {
+        match $ARRAY.data_type() {
+            DataType::Int32 => {
+                ScalarValue::Int32(compute::$PRIMITIVE($ARRAY.as_primitive::<Int32Type>()))
+            }
+            DataType::Int64 => {
+                ScalarValue::Int64(compute::$PRIMITIVE($ARRAY.as_primitive::<Int64Type>()))
+            }
+            DataType::Float64 => {
+                ScalarValue::Float64(compute::$PRIMITIVE($ARRAY.as_primitive::<Float64Type>()))
+            }
+            DataType::Utf8 => {
+                ScalarValue::String(compute::$STRING($ARRAY.as_string::<i32>()).map(String::from))
+            }
+            data_type => return Err(ExecutorError::UnsupportedAggArgument(data_type.clone())),
+        }
+    };
+}
+
+impl Accumulator for MinMaxAccumulator {
+    fn update_batch(&mut self, columns: &[ArrayRef]) -> Result<(), ExecutorError> {
+        let array = &columns[0];
+        let value = if self.is_min {
+            min_max_batch!(array, min, min_string)
+        } else {
+            min_max_batch!(array, max, max_string)
+        };
+        let replace = !value.is_null()
+            && (self.result.is_null()
+                || (self.is_min && value < self.result)
+                || (!self.is_min && value > self.result));
+        if replace {
+            self.result = value;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError> {
+        Ok(build_array_from_scalar_values(
+            &self.data_type,
+            std::slice::from_ref(&self.result),
+        ))
+    }
+}
+
+/// `GROUPING(args)` of a group, which is fixed by the grouping set the group belongs to.
+pub struct GroupingAccumulator {
+    value: i32,
+}
+
+impl GroupingAccumulator {
+    pub fn new(value: i32) -> Self {
+        Self { value }
+    }
+}
+
+impl Accumulator for GroupingAccumulator {
+    fn update_batch(&mut self, _: &[ArrayRef]) -> Result<(), ExecutorError> {
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError> {
+        Ok(Arc::new(Int32Array::from(vec![self.value])))
+    }
+}
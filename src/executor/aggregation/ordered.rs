@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{new_null_array, Array, ArrayRef, AsArray, Float64Array, ListArray, StringArray},
+    buffer::OffsetBuffer,
+    compute::{self, SortColumn, SortOptions},
+    datatypes::{DataType, Float64Type},
+    error::ArrowError,
+};
+use itertools::Itertools;
+
+use crate::{
+    binder::expression::BoundOrderBy,
+    executor::{aggregation::Accumulator, ExecutorError},
+};
+
+/// Rows buffered by an ordered aggregation, which are sorted by the trailing ORDER BY
+/// key columns once the aggregation is evaluated.
+struct SortedRows {
+    sort_options: Vec<SortOptions>,
+    chunks: Vec<Vec<ArrayRef>>,
+}
+
+impl SortedRows {
+    fn new(order_by: &[BoundOrderBy]) -> Self {
+        Self {
+            sort_options: order_by
+                .iter()
+                .map(|o| SortOptions {
+                    descending: !o.asc,
+                    nulls_first: o.nulls_first,
+                })
+                .collect(),
+            chunks: vec![],
+        }
+    }
+
+    fn push(&mut self, columns: &[ArrayRef]) {
+        self.chunks.push(columns.to_vec());
+    }
+
+    /// Get all buffered columns sorted by the ORDER BY keys, or None if no rows are buffered.
+    fn sorted(&self) -> Result<Option<Vec<ArrayRef>>, ExecutorError> {
+        let Some(first) = self.chunks.first() else {
+            return Ok(None);
+        };
+        let columns: Vec<ArrayRef> = (0..first.len())
+            .map(|i| {
+                let arrays = self.chunks.iter().map(|c| c[i].as_ref()).collect_vec();
+                compute::concat(&arrays)
+            })
+            .try_collect()?;
+        if columns[0].is_empty() {
+            return Ok(None);
+        }
+        if self.sort_options.is_empty() {
+            return Ok(Some(columns));
+        }
+
+        let num_args = columns.len() - self.sort_options.len();
+        let sort_columns = columns[num_args..]
+            .iter()
+            .zip(self.sort_options.iter())
+            .map(|(values, options)| SortColumn {
+                values: values.clone(),
+                options: Some(*options),
+            })
+            .collect_vec();
+        let indices = compute::lexsort_to_indices(&sort_columns, None)?;
+        let columns = columns
+            .iter()
+            .map(|c| compute::take(c.as_ref(), &indices, None))
+            .try_collect()?;
+        Ok(Some(columns))
+    }
+}
+
+/// `string_agg(value, delimiter)` concatenates the non-null values, each one but the
+/// first preceded by the delimiter of its row.
+pub struct StringAggAccumulator {
+    rows: SortedRows,
+}
+
+impl StringAggAccumulator {
+    pub fn new(order_by: &[BoundOrderBy]) -> Self {
+        Self {
+            rows: SortedRows::new(order_by),
+        }
+    }
+}
+
+impl Accumulator for StringAggAccumulator {
+    fn update_batch(&mut self, columns: &[ArrayRef]) -> Result<(), ExecutorError> {
+        self.rows.push(columns);
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError> {
+        let mut result: Option<String> = None;
+        if let Some(columns) = self.rows.sorted()? {
+            let values = compute::cast(&columns[0], &DataType::Utf8)?;
+            let values = values.as_string::<i32>();
+            let delimiters = compute::cast(&columns[1], &DataType::Utf8)?;
+            let delimiters = delimiters.as_string::<i32>();
+            for i in 0..values.len() {
+                if values.is_null(i) {
+                    continue;
+                }
+                match result.as_mut() {
+                    Some(s) => {
+                        if delimiters.is_valid(i) {
+                            s.push_str(delimiters.value(i));
+                        }
+                        s.push_str(values.value(i));
+                    }
+                    None => result = Some(values.value(i).to_string()),
+                }
+            }
+        }
+        Ok(Arc::new(StringArray::from(vec![result])))
+    }
+}
+
+/// `array_agg(value)` collects all values, including nulls, into a list.
+pub struct ArrayAggAccumulator {
+    return_type: DataType,
+    rows: SortedRows,
+}
+
+impl ArrayAggAccumulator {
+    pub fn new(return_type: &DataType, order_by: &[BoundOrderBy]) -> Self {
+        Self {
+            return_type: return_type.clone(),
+            rows: SortedRows::new(order_by),
+        }
+    }
+}
+
+impl Accumulator for ArrayAggAccumulator {
+    fn update_batch(&mut self, columns: &[ArrayRef]) -> Result<(), ExecutorError> {
+        self.rows.push(columns);
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError> {
+        let Some(columns) = self.rows.sorted()? else {
+            return Ok(new_null_array(&self.return_type, 1));
+        };
+        let DataType::List(field) = &self.return_type else {
+            unreachable!("array_agg should return list, got {:?}", self.return_type)
+        };
+        let values = columns[0].clone();
+        let offsets = OffsetBuffer::from_lengths([values.len()]);
+        Ok(Arc::new(ListArray::try_new(
+            field.clone(),
+            offsets,
+            values,
+            None,
+        )?))
+    }
+}
+
+/// `percentile_cont(fraction)` and `percentile_disc(fraction)` over the non-null values
+/// of the single WITHIN GROUP key. The continuous one interpolates between adjacent
+/// values while the discrete one returns the first value whose position reaches the
+/// fraction.
+pub struct PercentileAccumulator {
+    return_type: DataType,
+    continuous: bool,
+    rows: SortedRows,
+}
+
+impl PercentileAccumulator {
+    pub fn new(return_type: &DataType, order_by: &BoundOrderBy, continuous: bool) -> Self {
+        Self {
+            return_type: return_type.clone(),
+            continuous,
+            rows: SortedRows::new(std::slice::from_ref(order_by)),
+        }
+    }
+}
+
+impl Accumulator for PercentileAccumulator {
+    fn update_batch(&mut self, columns: &[ArrayRef]) -> Result<(), ExecutorError> {
+        self.rows.push(columns);
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ArrayRef, ExecutorError> {
+        let Some(columns) = self.rows.sorted()? else {
+            return Ok(new_null_array(&self.return_type, 1));
+        };
+        let fractions = compute::cast(&columns[0], &DataType::Float64)?;
+        let fraction = fractions.as_primitive::<Float64Type>().value(0);
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "percentile value {fraction} is not between 0 and 1"
+            ))
+            .into());
+        }
+        let values = compute::filter(&columns[1], &compute::is_not_null(&columns[1])?)?;
+        if values.is_empty() {
+            return Ok(new_null_array(&self.return_type, 1));
+        }
+
+        let n = values.len();
+        if !self.continuous {
+            let index = ((fraction * n as f64).ceil() as usize).max(1) - 1;
+            return Ok(values.slice(index, 1));
+        }
+
+        let values = compute::cast(&values, &DataType::Float64)?;
+        let values = values.as_primitive::<Float64Type>();
+        let position = fraction * (n - 1) as f64;
+        let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+        let (lower_value, upper_value) = (values.value(lower), values.value(upper));
+        let result = lower_value + (upper_value - lower_value) * (position - lower as f64);
+        Ok(Arc::new(Float64Array::from(vec![result])))
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use futures_async_stream::try_stream;
+
+use crate::{
+    catalog::{ColumnCatalog, StatisticsBuilder, TableId},
+    executor::{BoxedExecutor, ExecutorError},
+    storage::Storage,
+};
+
+/// Collects the statistics of the columns from the rows of the child, and keeps them in
+/// the storage once all rows are read. The statistics of the other columns of the table
+/// are kept from the last `ANALYZE`.
+pub struct AnalyzeExecutor {
+    pub table_id: TableId,
+    pub columns: Vec<ColumnCatalog>,
+    pub child: BoxedExecutor,
+    pub storage: Arc<dyn Storage>,
+}
+
+impl AnalyzeExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let mut builder = StatisticsBuilder::new(&self.columns)?;
+        #[for_await]
+        for batch in self.child {
+            builder.update(&batch?)?;
+        }
+        let mut statistics = builder.finish()?;
+        let previous = self
+            .storage
+            .get_catalog()
+            .tables
+            .remove(&self.table_id)
+            .and_then(|table| table.statistics);
+        if let Some(previous) = previous {
+            for (id, column) in &previous.columns {
+                statistics
+                    .columns
+                    .entry(id.clone())
+                    .or_insert_with(|| column.clone());
+            }
+        }
+        self.storage.set_statistics(self.table_id, statistics)?;
+    }
+}
@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
 use arrow::{
-    array::ArrayRef,
+    array::{ArrayRef, AsArray},
     compute::kernels::{
+        boolean::{and_kleene, or_kleene},
         cmp::{eq, gt, gt_eq, lt, lt_eq},
         numeric::{add, div, mul, sub},
     },
@@ -63,8 +64,8 @@ pub fn binary_op(
         BinaryOperator::LtEq => Ok(Arc::new(lt_eq(left, right)?)),
         BinaryOperator::Eq => Ok(Arc::new(eq(left, right)?)),
         BinaryOperator::NotEq => todo!(),
-        BinaryOperator::And => todo!(),
-        BinaryOperator::Or => todo!(),
+        BinaryOperator::And => Ok(Arc::new(and_kleene(left.as_boolean(), right.as_boolean())?)),
+        BinaryOperator::Or => Ok(Arc::new(or_kleene(left.as_boolean(), right.as_boolean())?)),
         _ => todo!(),
     }
 }
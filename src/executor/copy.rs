@@ -0,0 +1,135 @@
+use std::{path::Path, sync::Arc};
+
+use arrow::{
+    array::{new_null_array, RecordBatch},
+    datatypes::{Field, Schema},
+    error::ArrowError,
+};
+use futures_async_stream::try_stream;
+
+use crate::{
+    binder::statement::create_table::{ExternalFormat, ExternalTable},
+    catalog::{ColumnCatalog, TableCatalog},
+    executor::{
+        affected_rows_batch, check_not_null, evaluator::strict_cast, BoxedExecutor, ExecutorError,
+    },
+    storage::{CsvTable, FileWriter, IpcTable, JsonTable, StorageError, Table, Transaction},
+    types::build_scalar_value_array,
+};
+
+/// Write the rows of the child into the file batch by batch, and output the number of
+/// written rows.
+pub struct CopyToFileExecutor {
+    pub file: ExternalTable,
+    /// the output columns of the child, which an empty Arrow IPC file is written with
+    pub columns: Vec<ColumnCatalog>,
+    pub child: BoxedExecutor,
+}
+
+impl CopyToFileExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let path = Path::new(&self.file.location);
+        let mut writer = None;
+        let mut rows = 0;
+        #[for_await]
+        for batch in self.child {
+            let batch = batch?;
+            rows += batch.num_rows() as i64;
+            let writer = match &mut writer {
+                Some(writer) => writer,
+                None => writer.insert(create_writer(path, &self.file.format, &batch.schema())?),
+            };
+            writer.write(&batch)?;
+        }
+        let writer = match writer {
+            Some(writer) => writer,
+            None => create_writer(path, &self.file.format, &schema_of(&self.columns))?,
+        };
+        writer.finish()?;
+        yield affected_rows_batch(rows)?;
+    }
+}
+
+/// Append the rows of the file to the table, and output the number of inserted rows. The
+/// columns of the file are casted into the copied columns of the table in order, while
+/// the other columns of the table take their default, or null without one.
+pub struct CopyFromFileExecutor {
+    pub table_catalog: TableCatalog,
+    pub columns: Vec<ColumnCatalog>,
+    pub file: ExternalTable,
+    pub txn: Arc<dyn Transaction>,
+}
+
+impl CopyFromFileExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let table = self.txn.get_table(self.table_catalog.id.clone())?;
+        let source = open_source(self.table_catalog.id.clone(), self.file)?;
+        let table_columns = self.table_catalog.get_all_columns();
+        let schema = Arc::new(schema_of(&table_columns));
+        let mut rows = 0;
+        #[for_await]
+        for batch in source.scan() {
+            let batch = batch?;
+            if batch.num_columns() != self.columns.len() {
+                Err(ArrowError::SchemaError(format!(
+                    "the file has {} columns but {} columns are copied",
+                    batch.num_columns(),
+                    self.columns.len()
+                )))?;
+            }
+            let columns = table_columns
+                .iter()
+                .map(|column| {
+                    let data_type = &column.desc.data_type;
+                    match self.columns.iter().position(|c| c.id == column.id) {
+                        Some(i) => strict_cast(batch.column(i), data_type),
+                        None => match &column.desc.default {
+                            Some(default) => strict_cast(
+                                &build_scalar_value_array(default, batch.num_rows()),
+                                data_type,
+                            ),
+                            None => Ok(new_null_array(data_type, batch.num_rows())),
+                        },
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            check_not_null(&table_columns, &columns)?;
+            rows += batch.num_rows() as i64;
+            table.append(RecordBatch::try_new(schema.clone(), columns)?)?;
+        }
+        yield affected_rows_batch(rows)?;
+    }
+}
+
+fn create_writer(
+    path: &Path,
+    format: &ExternalFormat,
+    schema: &Schema,
+) -> Result<FileWriter, StorageError> {
+    match format {
+        ExternalFormat::Csv(config) => FileWriter::csv(path, config),
+        ExternalFormat::Json(_) => FileWriter::json(path),
+        ExternalFormat::Ipc(_) => FileWriter::ipc(path, schema),
+    }
+}
+
+/// the file read as a table, whose columns are those of the file
+fn open_source(id: String, file: ExternalTable) -> Result<Arc<dyn Table>, StorageError> {
+    let ExternalTable { location, format } = file;
+    Ok(match format {
+        ExternalFormat::Csv(config) => Arc::new(CsvTable::new(id, location, config)?),
+        ExternalFormat::Json(config) => Arc::new(JsonTable::new(id, location, config)?),
+        ExternalFormat::Ipc(config) => Arc::new(IpcTable::new(id, location, config)?),
+    })
+}
+
+fn schema_of(columns: &[ColumnCatalog]) -> Schema {
+    Schema::new(
+        columns
+            .iter()
+            .map(|c| Field::new(c.id.clone(), c.desc.data_type.clone(), true))
+            .collect::<Vec<_>>(),
+    )
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use futures::StreamExt;
+use futures_async_stream::try_stream;
+
+use crate::{
+    binder::statement::create_table::{ExternalFormat, ExternalTable},
+    catalog::{ColumnCatalog, TableId},
+    executor::{BoxedExecutor, ExecutorError},
+    storage::Storage,
+};
+
+pub struct CreateTableExecutor {
+    pub table_id: TableId,
+    pub columns: Vec<ColumnCatalog>,
+    /// the rows of `CREATE TABLE ... AS SELECT`
+    pub child: Option<BoxedExecutor>,
+    /// the file of `CREATE EXTERNAL TABLE`
+    pub external: Option<ExternalTable>,
+    pub storage: Arc<dyn Storage>,
+}
+
+impl CreateTableExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        if let Some(ExternalTable { location, format }) = self.external {
+            match format {
+                ExternalFormat::Csv(config) => {
+                    self.storage
+                        .create_csv_table(self.table_id, location, config)?
+                }
+                ExternalFormat::Json(config) => {
+                    self.storage
+                        .create_json_table(self.table_id, location, config)?
+                }
+                ExternalFormat::Ipc(config) => {
+                    self.storage
+                        .create_ipc_table(self.table_id, location, config)?
+                }
+            }
+            return Ok(());
+        }
+        self.storage
+            .create_table(self.table_id.clone(), self.columns)?;
+        if let Some(child) = self.child {
+            // the table is created ahead of the transaction of its rows, so it is dropped
+            // again when the query fails rather than left behind empty
+            if let Err(e) = append_rows(self.storage.as_ref(), &self.table_id, child).await {
+                self.storage.drop_table(self.table_id)?;
+                Err(e)?;
+            }
+        }
+    }
+}
+
+async fn append_rows(
+    storage: &dyn Storage,
+    table_id: &TableId,
+    mut child: BoxedExecutor,
+) -> Result<(), ExecutorError> {
+    let txn = storage.begin()?;
+    let table = txn.get_table(table_id.clone())?;
+    while let Some(batch) = child.next().await {
+        if let Err(e) = batch.and_then(|batch| Ok(table.append(batch)?)) {
+            txn.rollback();
+            return Err(e);
+        }
+    }
+    txn.commit()?;
+    Ok(())
+}
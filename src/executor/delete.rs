@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, BooleanArray, RecordBatch},
+    compute::{filter_record_batch, not, prep_null_mask_filter},
+};
+use futures_async_stream::try_stream;
+
+use crate::{
+    binder::expression::BoundExpr,
+    catalog::TableId,
+    executor::{affected_rows_batch, BoxedExecutor, ExecutorError},
+    storage::Transaction,
+};
+
+/// Delete the rows of the child, a scan over the whole table, that the predicate evaluates
+/// to true by rewriting the table with the remaining rows, and output the number of
+/// deleted rows.
+pub struct DeleteExecutor {
+    pub table_id: TableId,
+    pub predicate: Option<BoundExpr>,
+    pub child: BoxedExecutor,
+    pub txn: Arc<dyn Transaction>,
+}
+
+/// Evaluate the rows that the predicate is true for, nulls are treated as false.
+/// All rows are selected without predicate.
+pub fn eval_selected_rows(
+    predicate: &Option<BoundExpr>,
+    batch: &RecordBatch,
+) -> Result<BooleanArray, ExecutorError> {
+    let Some(predicate) = predicate else {
+        return Ok(BooleanArray::from(vec![true; batch.num_rows()]));
+    };
+    let eval_mark = predicate.eval_column(batch)?;
+    let mark = eval_mark
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .expect("predicate expected evaluate boolean array");
+    if mark.null_count() == 0 {
+        return Ok(mark.clone());
+    }
+    Ok(prep_null_mask_filter(mark))
+}
+
+impl DeleteExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let table = self.txn.get_table(self.table_id)?;
+        let mut rows = 0;
+        let mut remaining = vec![];
+        #[for_await]
+        for batch in self.child {
+            let batch = batch?;
+            let deleted = eval_selected_rows(&self.predicate, &batch)?;
+            rows += deleted.true_count() as i64;
+            let batch = filter_record_batch(&batch, &not(&deleted)?)?;
+            if batch.num_rows() > 0 {
+                remaining.push(batch);
+            }
+        }
+        table.overwrite(remaining)?;
+        yield affected_rows_batch(rows)?;
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use futures_async_stream::try_stream;
+
+use crate::{
+    catalog::TableId,
+    executor::ExecutorError,
+    storage::{Storage, StorageError},
+};
+
+pub struct DropTableExecutor {
+    pub table_id: TableId,
+    pub if_exists: bool,
+    pub storage: Arc<dyn Storage>,
+}
+
+impl DropTableExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        match self.storage.drop_table(self.table_id) {
+            Err(StorageError::TableNotFound(_)) if self.if_exists => {}
+            result => result?,
+        }
+    }
+}
@@ -3,11 +3,14 @@ use crate::{
     executor::{array_compute::binary_op, ExecutorError},
     types::build_scalar_value_array,
 };
-use arrow::compute::cast;
 use arrow::{
     array::{ArrayRef, RecordBatch},
-    datatypes::Field,
+    datatypes::DataType,
+    error::ArrowError,
 };
+use std::sync::Arc;
+
+use arrow::compute::{cast_with_options, is_not_null, is_null, CastOptions};
 
 impl BoundExpr {
     pub fn eval_column(&self, batch: &RecordBatch) -> Result<ArrayRef, ExecutorError> {
@@ -20,17 +23,31 @@ impl BoundExpr {
             }
             BoundExpr::Constant(val) => Ok(build_scalar_value_array(val, batch.num_rows())),
             BoundExpr::ColumnRef(_) => panic!("column ref should be resolved"),
-            BoundExpr::TypeCast(tc) => Ok(cast(&tc.expr.eval_column(batch)?, &tc.cast_type)?),
+            BoundExpr::TypeCast(tc) => {
+                Ok(strict_cast(&tc.expr.eval_column(batch)?, &tc.cast_type)?)
+            }
             BoundExpr::AggFunc(_) => todo!(),
+            BoundExpr::IsNull(e) => {
+                let value = e.expr.eval_column(batch)?;
+                let result = if e.negated {
+                    is_not_null(&value)?
+                } else {
+                    is_null(&value)?
+                };
+                Ok(Arc::new(result))
+            }
         }
     }
+}
 
-    pub fn eval_field(&self, batch: &RecordBatch) -> Field {
-        match &self {
-            BoundExpr::InputRef(input_ref) => batch.schema().field(input_ref.index).clone(),
-            _ => unimplemented!("expr type {:?} not implemented yet", self),
-        }
-    }
+/// Cast the array, failing on a value which does not fit the type instead of turning it
+/// into null.
+pub fn strict_cast(array: &ArrayRef, data_type: &DataType) -> Result<ArrayRef, ArrowError> {
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    cast_with_options(array, data_type, &options)
 }
 
 #[cfg(test)]
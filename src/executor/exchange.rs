@@ -0,0 +1,161 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    panic::{resume_unwind, AssertUnwindSafe},
+};
+
+use arrow::{
+    array::{ArrayRef, RecordBatch, UInt32Array},
+    compute::take_record_batch,
+    row::{RowConverter, SortField},
+};
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use tokio::sync::mpsc;
+
+use crate::{
+    binder::expression::BoundExpr,
+    executor::{BoxedExecutor, ExecutorError},
+};
+
+/// the number of batches a partition runs ahead of the consumer of an exchange
+const EXCHANGE_BUFFER_SIZE: usize = 8;
+
+/// a batch of a partition, or the panic of its task, which is resumed by the consumer so
+/// that the query does not end early with the rows read so far
+type Message = std::thread::Result<Result<RecordBatch, ExecutorError>>;
+type BatchSender = mpsc::Sender<Message>;
+type BatchReceiver = mpsc::Receiver<Message>;
+
+/// Merges partitions into a single stream. Each partition runs on its own task of the
+/// runtime, while the batches are yielded partition after partition, so the rows keep the
+/// order they have in a single partition.
+pub struct GatherExecutor {
+    pub inputs: Vec<BoxedExecutor>,
+}
+
+impl GatherExecutor {
+    pub fn execute(mut self) -> BoxedExecutor {
+        if self.inputs.len() == 1 {
+            return self.inputs.pop().unwrap();
+        }
+        self.gather()
+    }
+
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    async fn gather(self) {
+        let receivers = self.inputs.into_iter().map(spawn_partition).collect_vec();
+        for mut receiver in receivers {
+            while let Some(message) = receiver.recv().await {
+                yield message.unwrap_or_else(|panic| resume_unwind(panic))?;
+            }
+        }
+    }
+}
+
+/// run the partition on a task which sends its batches over a bounded channel, and stops
+/// at the first error or once the receiver is dropped
+fn spawn_partition(mut input: BoxedExecutor) -> BatchReceiver {
+    let (sender, receiver) = mpsc::channel(EXCHANGE_BUFFER_SIZE);
+    tokio::spawn(async move {
+        let sent = AssertUnwindSafe(async {
+            while let Some(batch) = input.next().await {
+                let failed = batch.is_err();
+                if sender.send(Ok(batch)).await.is_err() || failed {
+                    break;
+                }
+            }
+        })
+        .catch_unwind()
+        .await;
+        if let Err(panic) = sent {
+            let _ = sender.send(Err(panic)).await;
+        }
+    });
+    receiver
+}
+
+/// Redistributes the rows of the inputs between `partitions` outputs by the hash of the
+/// keys, so the rows having the same keys end up in the same output. Each input runs on
+/// its own task of the runtime, which is spawned right away.
+pub struct RepartitionExecutor {
+    pub inputs: Vec<BoxedExecutor>,
+    pub keys: Vec<BoundExpr>,
+    pub partitions: usize,
+}
+
+impl RepartitionExecutor {
+    pub fn execute(self) -> Vec<BoxedExecutor> {
+        let (senders, receivers): (Vec<BatchSender>, Vec<BatchReceiver>) = (0..self.partitions)
+            .map(|_| mpsc::channel(EXCHANGE_BUFFER_SIZE))
+            .unzip();
+        for input in self.inputs {
+            let keys = self.keys.clone();
+            let senders = senders.clone();
+            tokio::spawn(async move {
+                let message = match AssertUnwindSafe(send_partitioned(input, &keys, &senders))
+                    .catch_unwind()
+                    .await
+                {
+                    Ok(Ok(())) => return,
+                    Ok(Err(e)) => Ok(Err(e)),
+                    Err(panic) => Err(panic),
+                };
+                // the error fails the query through any one of the outputs
+                let _ = senders[0].send(message).await;
+            });
+        }
+        receivers
+            .into_iter()
+            .map(|receiver| {
+                futures::stream::unfold(receiver, |mut receiver| async move {
+                    let message = receiver.recv().await?;
+                    Some((
+                        message.unwrap_or_else(|panic| resume_unwind(panic)),
+                        receiver,
+                    ))
+                })
+                .boxed()
+            })
+            .collect()
+    }
+}
+
+async fn send_partitioned(
+    mut input: BoxedExecutor,
+    keys: &[BoundExpr],
+    senders: &[BatchSender],
+) -> Result<(), ExecutorError> {
+    let mut converter = None;
+    while let Some(batch) = input.try_next().await? {
+        let columns: Vec<ArrayRef> = keys.iter().map(|e| e.eval_column(&batch)).try_collect()?;
+        let converter = match &mut converter {
+            Some(converter) => converter,
+            None => converter.insert(RowConverter::new(
+                columns
+                    .iter()
+                    .map(|c| SortField::new(c.data_type().clone()))
+                    .collect(),
+            )?),
+        };
+        // the rows of the batch going to each output
+        let mut indices = vec![vec![]; senders.len()];
+        for (i, row) in converter.convert_columns(&columns)?.iter().enumerate() {
+            // the hasher has fixed keys, so equal rows of all inputs hash alike
+            let mut hasher = DefaultHasher::new();
+            row.as_ref().hash(&mut hasher);
+            indices[hasher.finish() as usize % senders.len()].push(i as u32);
+        }
+        for (sender, indices) in senders.iter().zip(indices) {
+            if indices.is_empty() {
+                continue;
+            }
+            let batch = take_record_batch(&batch, &UInt32Array::from(indices))?;
+            // an output is only dropped once the query is given up
+            if sender.send(Ok(Ok(batch))).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
@@ -2,7 +2,7 @@ use arrow::{
     array::{BooleanArray, RecordBatch},
     compute::filter_record_batch,
 };
-use futures_async_stream::{for_await, try_stream};
+use futures_async_stream::try_stream;
 
 use crate::{
     binder::expression::BoundExpr,
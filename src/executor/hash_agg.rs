@@ -0,0 +1,220 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{new_empty_array, new_null_array, ArrayRef, Int64Array, RecordBatch, UInt32Array},
+    compute::{concat, take},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    row::{OwnedRow, RowConverter, SortField},
+};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+
+use crate::{
+    binder::expression::{
+        agg_func::{AggFunc, BoundAggFunc},
+        BoundExpr,
+    },
+    executor::{
+        aggregation::{
+            agg_field, as_agg_funcs, create_accumulator, eval_agg_columns, update_accumulator,
+            Accumulator, GroupingAccumulator,
+        },
+        BoxedExecutor, ExecutorError,
+    },
+};
+
+/// Hash aggregation over the group by exprs. The rows of each grouping set are
+/// aggregated separately, keyed by the group by columns with the ones absent from
+/// the set nulled out, plus the grouping id of the set.
+pub struct HashAggExecutor {
+    pub agg_funcs: Vec<BoundExpr>,
+    pub group_by: Vec<BoundExpr>,
+    pub grouping_sets: Vec<Vec<usize>>,
+    pub child: BoxedExecutor,
+}
+
+/// The grouping id of a set has bit `n - 1 - i` set if `group_by[i]` is not in the set.
+fn grouping_id(set: &[usize], num_group_by: usize) -> i64 {
+    (0..num_group_by)
+        .filter(|i| !set.contains(i))
+        .map(|i| 1 << (num_group_by - 1 - i))
+        .sum()
+}
+
+fn create_group_accumulators(
+    agg_funcs: &[BoundAggFunc],
+    group_by: &[BoundExpr],
+    grouping_id: i64,
+) -> Vec<Box<dyn Accumulator>> {
+    agg_funcs
+        .iter()
+        .map(|agg| match agg.func {
+            AggFunc::Grouping => {
+                let value = agg.exprs.iter().fold(0, |value, arg| {
+                    let index = group_by.iter().position(|e| e == arg).unwrap();
+                    let bit = (grouping_id >> (group_by.len() - 1 - index)) & 1;
+                    (value << 1) | bit as i32
+                });
+                Box::new(GroupingAccumulator::new(value)) as Box<dyn Accumulator>
+            }
+            _ => create_accumulator(agg),
+        })
+        .collect()
+}
+
+impl HashAggExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let agg_funcs = as_agg_funcs(&self.agg_funcs);
+        let num_group_by = self.group_by.len();
+        let group_types = self
+            .group_by
+            .iter()
+            .map(|e| e.return_type().unwrap())
+            .collect_vec();
+        let grouping_sets = if self.grouping_sets.is_empty() {
+            vec![(0..num_group_by).collect_vec()]
+        } else {
+            self.grouping_sets.clone()
+        };
+        let grouping_ids = grouping_sets
+            .iter()
+            .map(|set| grouping_id(set, num_group_by))
+            .collect_vec();
+
+        // group keys are the group by columns followed by the grouping id
+        let mut sort_fields = group_types
+            .iter()
+            .map(|t| SortField::new(t.clone()))
+            .collect_vec();
+        sort_fields.push(SortField::new(DataType::Int64));
+        let converter = RowConverter::new(sort_fields)?;
+
+        let mut groups: HashMap<OwnedRow, usize> = HashMap::new();
+        let mut group_keys: Vec<OwnedRow> = vec![];
+        let mut accumulators: Vec<Vec<Box<dyn Accumulator>>> = vec![];
+        let mut input_schema: Option<SchemaRef> = None;
+
+        #[for_await]
+        for batch in self.child {
+            let batch = batch?;
+            input_schema.get_or_insert_with(|| batch.schema());
+            let num_rows = batch.num_rows();
+            let group_columns: Vec<ArrayRef> = self
+                .group_by
+                .iter()
+                .map(|e| e.eval_column(&batch))
+                .try_collect()?;
+            let agg_columns: Vec<Vec<ArrayRef>> = agg_funcs
+                .iter()
+                .map(|agg| eval_agg_columns(agg, &batch))
+                .try_collect()?;
+
+            for (set, grouping_id) in grouping_sets.iter().zip(grouping_ids.iter()) {
+                let mut keys = group_columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, column)| {
+                        if set.contains(&i) {
+                            column.clone()
+                        } else {
+                            new_null_array(column.data_type(), num_rows)
+                        }
+                    })
+                    .collect_vec();
+                keys.push(Arc::new(Int64Array::from(vec![*grouping_id; num_rows])));
+                let rows = converter.convert_columns(&keys)?;
+
+                // row indices of the batch belonging to each group
+                let mut group_rows: HashMap<usize, Vec<u32>> = HashMap::new();
+                for (row_idx, row) in rows.iter().enumerate() {
+                    let row = row.owned();
+                    let group = match groups.get(&row) {
+                        Some(group) => *group,
+                        None => {
+                            groups.insert(row.clone(), group_keys.len());
+                            group_keys.push(row);
+                            accumulators.push(create_group_accumulators(
+                                &agg_funcs,
+                                &self.group_by,
+                                *grouping_id,
+                            ));
+                            group_keys.len() - 1
+                        }
+                    };
+                    group_rows.entry(group).or_default().push(row_idx as u32);
+                }
+
+                for (group, row_indices) in group_rows {
+                    let indices = UInt32Array::from(row_indices);
+                    for ((acc, agg), columns) in accumulators[group]
+                        .iter_mut()
+                        .zip(agg_funcs.iter())
+                        .zip(agg_columns.iter())
+                    {
+                        let columns: Vec<ArrayRef> = columns
+                            .iter()
+                            .map(|c| take(c.as_ref(), &indices, None))
+                            .try_collect()?;
+                        update_accumulator(acc.as_mut(), agg, &columns)?;
+                    }
+                }
+            }
+        }
+
+        // an empty grouping set yields one row even without any input, like simple agg
+        for (set, grouping_id) in grouping_sets.iter().zip(grouping_ids.iter()) {
+            if !set.is_empty() {
+                continue;
+            }
+            let mut keys = group_types
+                .iter()
+                .map(|t| new_null_array(t, 1))
+                .collect_vec();
+            keys.push(Arc::new(Int64Array::from(vec![*grouping_id])));
+            let row = converter.convert_columns(&keys)?.row(0).owned();
+            if !groups.contains_key(&row) {
+                groups.insert(row.clone(), group_keys.len());
+                group_keys.push(row);
+                accumulators.push(create_group_accumulators(
+                    &agg_funcs,
+                    &self.group_by,
+                    *grouping_id,
+                ));
+            }
+        }
+
+        let mut fields = self
+            .group_by
+            .iter()
+            .zip(group_types.iter())
+            .map(|(expr, data_type)| {
+                let name = match (expr, &input_schema) {
+                    (BoundExpr::InputRef(input_ref), Some(schema)) => {
+                        schema.field(input_ref.index).name().clone()
+                    }
+                    _ => "?column?".to_string(),
+                };
+                Field::new(name, data_type.clone(), true)
+            })
+            .collect_vec();
+        fields.extend(agg_funcs.iter().map(agg_field));
+
+        let mut columns = converter.convert_rows(group_keys.iter().map(|row| row.row()))?;
+        // drop the grouping id column
+        columns.pop();
+        for (i, agg) in agg_funcs.iter().enumerate() {
+            let values: Vec<ArrayRef> = accumulators
+                .iter()
+                .map(|accs| accs[i].evaluate())
+                .try_collect()?;
+            let values = values.iter().map(|v| v.as_ref()).collect_vec();
+            columns.push(if values.is_empty() {
+                new_empty_array(&agg.return_type)
+            } else {
+                concat(&values)?
+            });
+        }
+        yield RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+    }
+}
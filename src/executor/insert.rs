@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use futures_async_stream::try_stream;
+
+use crate::{
+    catalog::{ColumnCatalog, TableId},
+    executor::{affected_rows_batch, check_not_null, BoxedExecutor, ExecutorError},
+    storage::Transaction,
+};
+
+/// Append the rows of the child to the table, and output the number of inserted rows. Rows
+/// with nulls in columns which are not nullable fail the insert.
+pub struct InsertExecutor {
+    pub table_id: TableId,
+    pub columns: Vec<ColumnCatalog>,
+    pub child: BoxedExecutor,
+    pub txn: Arc<dyn Transaction>,
+}
+
+impl InsertExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let table = self.txn.get_table(self.table_id)?;
+        let mut rows = 0;
+        #[for_await]
+        for batch in self.child {
+            let batch = batch?;
+            check_not_null(&self.columns, batch.columns())?;
+            rows += batch.num_rows() as i64;
+            table.append(batch)?;
+        }
+        yield affected_rows_batch(rows)?;
+    }
+}
@@ -1,41 +1,119 @@
+mod aggregation;
+mod analyze;
 mod array_compute;
+mod copy;
+mod create_table;
+mod delete;
+mod drop_table;
 mod evaluator;
+mod exchange;
 mod filter;
+mod hash_agg;
+mod insert;
 mod project;
+mod simple_agg;
+mod table_function;
 mod table_scan;
-use arrow::array::RecordBatch;
+mod update;
+mod values;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Int64Array, RecordBatch};
+use arrow::datatypes::{DataType, Field, Schema};
 use arrow::error::ArrowError;
 use futures::stream::BoxStream;
+use itertools::Itertools;
 
+use crate::executor::analyze::AnalyzeExecutor;
+use crate::executor::copy::{CopyFromFileExecutor, CopyToFileExecutor};
+use crate::executor::create_table::CreateTableExecutor;
+use crate::executor::delete::DeleteExecutor;
+use crate::executor::drop_table::DropTableExecutor;
+use crate::executor::exchange::{GatherExecutor, RepartitionExecutor};
 use crate::executor::filter::FilterExecutor;
+use crate::executor::hash_agg::HashAggExecutor;
+use crate::executor::insert::InsertExecutor;
+use crate::executor::simple_agg::SimpleAggExecutor;
+use crate::executor::table_function::TableFunctionExecutor;
+use crate::executor::update::UpdateExecutor;
+use crate::executor::values::ValuesExecutor;
 use crate::optimizer::plan_visitor::PlanVisitor;
-use crate::storage;
 use crate::{
-    executor::{project::ProjectExecutor, table_scan::TableScanExecutor},
-    optimizer::{physical_project::PhysicalProject, PlanRef, PlanTreeNode},
-    storage::{StorageError, StorageImpl},
+    binder::expression::{BoundExpr, BoundInputRef},
+    catalog::ColumnCatalog,
+    executor::{
+        aggregation::final_agg_funcs, project::ProjectExecutor, table_scan::TableScanExecutor,
+    },
+    optimizer::{
+        physical_analyze::PhysicalAnalyze, physical_copy_from_file::PhysicalCopyFromFile,
+        physical_copy_to_file::PhysicalCopyToFile, physical_create_table::PhysicalCreateTable,
+        physical_delete::PhysicalDelete, physical_drop_table::PhysicalDropTable,
+        physical_insert::PhysicalInsert, physical_project::PhysicalProject,
+        physical_table_function::PhysicalTableFunction, physical_update::PhysicalUpdate,
+        physical_values::PhysicalValues, PhysicalHashAgg, PhysicalSimpleAgg, PlanNode, PlanRef,
+        PlanTreeNode,
+    },
+    storage::{Storage, StorageError, Transaction},
 };
 use futures::TryStreamExt;
-use futures_async_stream::try_stream;
 use thiserror::Error;
 
 pub type BoxedExecutor = BoxStream<'static, Result<RecordBatch, ExecutorError>>;
 
+/// Builds the executors of a plan, which read and write the tables through the
+/// transaction. DDL changes the storage directly.
+///
+/// A plan is built into partitions, streams of its rows which run concurrently on tasks
+/// of the tokio runtime. Scans split the rows of the tables into partitions, filters and
+/// projections run on each partition, and aggregations aggregate each partition before
+/// combining the partial results, repartitioned by the group keys. Wherever a single
+/// stream is needed the partitions are gathered in order, so the rows keep the order of a
+/// single partition.
 pub struct ExecutorBuilder {
-    storage: StorageImpl,
+    storage: Arc<dyn Storage>,
+    txn: Arc<dyn Transaction>,
+    /// the number of partitions the scans are split into, 1 by default
+    parallelism: usize,
 }
 
 impl ExecutorBuilder {
-    pub fn new(storage: StorageImpl) -> Self {
-        Self { storage }
+    pub fn new(storage: Arc<dyn Storage>, txn: Arc<dyn Transaction>) -> Self {
+        Self {
+            storage,
+            txn,
+            parallelism: 1,
+        }
+    }
+
+    /// split the scans into up to `parallelism` partitions, which needs the executors to
+    /// run within a tokio runtime, otherwise they run in a single partition
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
     }
 
     pub fn build(&mut self, plan: PlanRef) -> BoxedExecutor {
-        self.visit(plan).unwrap()
+        GatherExecutor {
+            inputs: self.visit(plan).unwrap(),
+        }
+        .execute()
     }
 
     pub fn try_collect(&mut self, plan: PlanRef) -> BoxedExecutor {
-        self.visit(plan).unwrap()
+        self.build(plan)
+    }
+
+    fn partitions(&self) -> usize {
+        match tokio::runtime::Handle::try_current() {
+            Ok(_) => self.parallelism,
+            Err(_) => 1,
+        }
+    }
+
+    /// the partitions of the only child of the plan
+    fn build_partitions(&mut self, plan: &impl PlanTreeNode) -> Vec<BoxedExecutor> {
+        self.visit(plan.children().first().unwrap().clone())
+            .unwrap()
     }
 }
 
@@ -47,61 +125,323 @@ pub async fn try_collect(mut executor: BoxedExecutor) -> Result<Vec<RecordBatch>
     Ok(output)
 }
 
+/// The output of INSERT, UPDATE and DELETE, a single row of the number of affected rows.
+fn affected_rows_batch(rows: i64) -> Result<RecordBatch, ArrowError> {
+    let schema = Schema::new(vec![Field::new("rows", DataType::Int64, false)]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(Int64Array::from(vec![rows]))],
+    )
+}
+
+/// Check that the arrays, which are the columns of the table in order, hold no nulls in the
+/// columns which are not nullable.
+fn check_not_null(columns: &[ColumnCatalog], arrays: &[ArrayRef]) -> Result<(), ExecutorError> {
+    for (column, array) in columns.iter().zip(arrays) {
+        if !column.desc.nullable && array.null_count() > 0 {
+            return Err(ExecutorError::NotNullViolation(column.desc.name.clone()));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum ExecutorError {
+    #[error("null value in column {0} violates not-null constraint")]
+    NotNullViolation(String),
     #[error("storage error: {0}")]
     Storage(#[from] StorageError),
     #[error("arrow error: {0}")]
     Arrow(#[from] ArrowError),
+    #[error("unsupported aggregate argument type: {0}")]
+    UnsupportedAggArgument(DataType),
 }
 
-impl PlanVisitor<BoxedExecutor> for ExecutorBuilder {
+impl PlanVisitor<Vec<BoxedExecutor>> for ExecutorBuilder {
     fn visit_physical_table_scan(
         &mut self,
         plan: &crate::optimizer::physical_table::PhysicalTableScan,
-    ) -> Option<BoxedExecutor> {
-        Some(match &self.storage {
-            StorageImpl::CsvStorage(storage) => TableScanExecutor {
-                plan: plan.clone(),
-                storage: storage.clone(),
-            }
-            .execute(),
-            StorageImpl::InMemoryStorage(storage) => TableScanExecutor {
+    ) -> Option<Vec<BoxedExecutor>> {
+        Some(
+            TableScanExecutor {
                 plan: plan.clone(),
-                storage: storage.clone(),
+                txn: self.txn.clone(),
+                partitions: self.partitions(),
             }
             .execute(),
-        })
+        )
+    }
+
+    fn visit_physical_values(&mut self, plan: &PhysicalValues) -> Option<Vec<BoxedExecutor>> {
+        Some(vec![ValuesExecutor {
+            columns: plan.logical().columns(),
+            rows: plan.logical().rows(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_table_function(
+        &mut self,
+        plan: &PhysicalTableFunction,
+    ) -> Option<Vec<BoxedExecutor>> {
+        Some(vec![TableFunctionExecutor {
+            function: plan.logical().function(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_create_table(
+        &mut self,
+        plan: &PhysicalCreateTable,
+    ) -> Option<Vec<BoxedExecutor>> {
+        let table_id = plan.logical().table_id();
+        let columns = plan.logical().columns();
+        let child = plan
+            .children()
+            .first()
+            .map(|child| self.build(child.clone()));
+        Some(vec![CreateTableExecutor {
+            table_id,
+            columns,
+            child,
+            external: plan.logical().external(),
+            storage: self.storage.clone(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_insert(&mut self, plan: &PhysicalInsert) -> Option<Vec<BoxedExecutor>> {
+        let table_id = plan.logical().table_id();
+        let child = self.build(plan.children().first().unwrap().clone());
+        Some(vec![InsertExecutor {
+            table_id,
+            columns: plan.logical().columns(),
+            child,
+            txn: self.txn.clone(),
+        }
+        .execute()])
     }
 
-    fn visit_physical_project(&mut self, plan: &PhysicalProject) -> Option<BoxedExecutor> {
+    fn visit_physical_update(&mut self, plan: &PhysicalUpdate) -> Option<Vec<BoxedExecutor>> {
+        let table_id = plan.logical().table_id();
+        let assignments = plan.logical().assignments();
+        let predicate = plan.logical().predicate();
+        let child = self.build(plan.children().first().unwrap().clone());
+        Some(vec![UpdateExecutor {
+            table_id,
+            columns: plan.logical().input().schema(),
+            assignments,
+            predicate,
+            child,
+            txn: self.txn.clone(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_delete(&mut self, plan: &PhysicalDelete) -> Option<Vec<BoxedExecutor>> {
+        let table_id = plan.logical().table_id();
+        let predicate = plan.logical().predicate();
+        let child = self.build(plan.children().first().unwrap().clone());
+        Some(vec![DeleteExecutor {
+            table_id,
+            predicate,
+            child,
+            txn: self.txn.clone(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_copy_to_file(
+        &mut self,
+        plan: &PhysicalCopyToFile,
+    ) -> Option<Vec<BoxedExecutor>> {
+        let child = self.build(plan.children().first().unwrap().clone());
+        Some(vec![CopyToFileExecutor {
+            file: plan.logical().file(),
+            columns: plan.logical().columns(),
+            child,
+        }
+        .execute()])
+    }
+
+    fn visit_physical_copy_from_file(
+        &mut self,
+        plan: &PhysicalCopyFromFile,
+    ) -> Option<Vec<BoxedExecutor>> {
+        Some(vec![CopyFromFileExecutor {
+            table_catalog: plan.logical().table_catalog(),
+            columns: plan.logical().columns(),
+            file: plan.logical().file(),
+            txn: self.txn.clone(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_analyze(&mut self, plan: &PhysicalAnalyze) -> Option<Vec<BoxedExecutor>> {
+        let child = self.build(plan.children().first().unwrap().clone());
+        Some(vec![AnalyzeExecutor {
+            table_id: plan.logical().table_id(),
+            columns: plan.logical().columns(),
+            child,
+            storage: self.storage.clone(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_drop_table(
+        &mut self,
+        plan: &PhysicalDropTable,
+    ) -> Option<Vec<BoxedExecutor>> {
+        let table_id = plan.logical().table_id();
+        let if_exists = plan.logical().if_exists();
+        Some(vec![DropTableExecutor {
+            table_id,
+            if_exists,
+            storage: self.storage.clone(),
+        }
+        .execute()])
+    }
+
+    fn visit_physical_project(&mut self, plan: &PhysicalProject) -> Option<Vec<BoxedExecutor>> {
+        let exprs = plan.logical().exprs();
+        let schema = plan.schema();
+        let partitions = self.build_partitions(plan);
         Some(
-            ProjectExecutor {
-                exprs: plan.logical().exprs(),
-                child: self
-                    .visit(plan.children().first().unwrap().clone())
-                    .unwrap(),
-            }
-            .execute(),
+            partitions
+                .into_iter()
+                .map(|child| {
+                    ProjectExecutor {
+                        exprs: exprs.clone(),
+                        schema: schema.clone(),
+                        child,
+                    }
+                    .execute()
+                })
+                .collect(),
         )
     }
 
     fn visit_physical_filter(
         &mut self,
         plan: &crate::optimizer::physical_filter::PhysicalFilter,
-    ) -> Option<BoxedExecutor> {
+    ) -> Option<Vec<BoxedExecutor>> {
+        let expr = plan.logical().expr();
+        let partitions = self.build_partitions(plan);
         Some(
-            FilterExecutor {
-                expr: plan.logical().expr(),
-                child: self
-                    .visit(plan.children().first().unwrap().clone())
-                    .unwrap(),
+            partitions
+                .into_iter()
+                .map(|child| {
+                    FilterExecutor {
+                        expr: expr.clone(),
+                        child,
+                    }
+                    .execute()
+                })
+                .collect(),
+        )
+    }
+
+    /// aggregate each partition and then the gathered partial results, unless the agg
+    /// funcs cannot combine partial results
+    fn visit_physical_simple_agg(
+        &mut self,
+        plan: &PhysicalSimpleAgg,
+    ) -> Option<Vec<BoxedExecutor>> {
+        let agg_funcs = plan.logical().agg_funcs();
+        let partitions = self.build_partitions(plan);
+        let (agg_funcs, partitions) = match final_agg_funcs(&agg_funcs, 0) {
+            Some(final_aggs) if partitions.len() > 1 => {
+                let partial_aggs = partitions
+                    .into_iter()
+                    .map(|child| {
+                        SimpleAggExecutor {
+                            agg_funcs: agg_funcs.clone(),
+                            child,
+                        }
+                        .execute()
+                    })
+                    .collect();
+                (final_aggs, partial_aggs)
             }
-            .execute(),
+            _ => (agg_funcs, partitions),
+        };
+        Some(vec![SimpleAggExecutor {
+            agg_funcs,
+            child: GatherExecutor { inputs: partitions }.execute(),
+        }
+        .execute()])
+    }
+
+    /// Aggregate each partition, and then the partial results repartitioned by the group
+    /// keys, so that each group is combined in a single partition, which leaves the groups
+    /// in no particular order. Grouping sets and agg funcs which cannot combine partial
+    /// results aggregate the gathered partitions.
+    fn visit_physical_hash_agg(&mut self, plan: &PhysicalHashAgg) -> Option<Vec<BoxedExecutor>> {
+        let agg_funcs = plan.logical().agg_funcs();
+        let group_by = plan.logical().group_by();
+        let grouping_sets = plan.logical().grouping_sets();
+        let partitions = self.build_partitions(plan);
+        let parallel = partitions.len() > 1 && !group_by.is_empty() && grouping_sets.is_empty();
+        let final_aggs = match final_agg_funcs(&agg_funcs, group_by.len()) {
+            Some(final_aggs) if parallel => final_aggs,
+            _ => {
+                let child = GatherExecutor { inputs: partitions }.execute();
+                let agg = HashAggExecutor {
+                    agg_funcs,
+                    group_by,
+                    grouping_sets,
+                    child,
+                };
+                return Some(vec![agg.execute()]);
+            }
+        };
+        // the partial results have the group by columns followed by the agg funcs
+        let group_keys = group_by
+            .iter()
+            .enumerate()
+            .map(|(index, expr)| {
+                BoundExpr::InputRef(BoundInputRef {
+                    index,
+                    return_type: expr.return_type().unwrap(),
+                })
+            })
+            .collect_vec();
+        let partial_aggs = partitions
+            .into_iter()
+            .map(|child| {
+                HashAggExecutor {
+                    agg_funcs: agg_funcs.clone(),
+                    group_by: group_by.clone(),
+                    grouping_sets: vec![],
+                    child,
+                }
+                .execute()
+            })
+            .collect();
+        let partitions = RepartitionExecutor {
+            inputs: partial_aggs,
+            keys: group_keys.clone(),
+            partitions: self.partitions(),
+        }
+        .execute();
+        Some(
+            partitions
+                .into_iter()
+                .map(|child| {
+                    HashAggExecutor {
+                        agg_funcs: final_aggs.clone(),
+                        group_by: group_keys.clone(),
+                        grouping_sets: vec![],
+                        child,
+                    }
+                    .execute()
+                })
+                .collect(),
         )
     }
 }
 
+#[cfg(test)]
 mod executor_test {
     use std::sync::Arc;
 
@@ -116,7 +456,7 @@ mod executor_test {
         },
         parser::parse,
         planner::Planner,
-        storage::{CsvStorage, Storage, StorageImpl},
+        storage::{CsvConfig, CsvStorage, Storage},
     };
 
     #[tokio::test]
@@ -125,7 +465,9 @@ mod executor_test {
 
         let filepath = "./tests/csv/sample.csv".to_string();
         let storage = CsvStorage::default();
-        storage.create_csv_table(id.clone(), filepath).unwrap();
+        storage
+            .create_csv_table(id.clone(), filepath, CsvConfig::default())
+            .unwrap();
 
         let stmts = parse("select first_name from employee where id = 1").unwrap();
 
@@ -144,7 +486,9 @@ mod executor_test {
         let mut physical_rewriter = PhysicalRewriter {};
         let physical_plan = physical_rewriter.rewrite(new_logical_plan);
         println!("physical_plan = {physical_plan:#?}");
-        let mut builder = ExecutorBuilder::new(StorageImpl::CsvStorage(Arc::new(storage)));
+        let storage = Arc::new(storage);
+        let txn = storage.begin().unwrap();
+        let mut builder = ExecutorBuilder::new(storage, txn);
         let executor = builder.build(physical_plan);
         let output = try_collect(executor).await.unwrap();
         let a = output[0]
@@ -152,7 +496,7 @@ mod executor_test {
             .as_any()
             .downcast_ref::<StringArray>()
             .unwrap();
-        assert_eq!(*a, StringArray::from(vec!["Bill", "Gregg", "John", "Von"]));
+        assert_eq!(*a, StringArray::from(vec!["Bill"]));
         println!("output: {output:#?}");
     }
 }
@@ -6,17 +6,32 @@ use futures_async_stream::try_stream;
 
 use crate::{
     binder::expression::BoundExpr,
+    catalog::ColumnCatalog,
     executor::{BoxedExecutor, ExecutorError},
 };
 
+/// Evaluate the exprs over the rows of the child, outputting the columns of the plan's
+/// schema.
 pub struct ProjectExecutor {
     pub exprs: Vec<BoundExpr>,
+    pub schema: Vec<ColumnCatalog>,
     pub child: BoxedExecutor,
 }
 
 impl ProjectExecutor {
     #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
     pub async fn execute(self) {
+        let fields: Vec<Field> = self
+            .schema
+            .iter()
+            .map(|c| {
+                Field::new(
+                    c.desc.name.clone(),
+                    c.desc.data_type.clone(),
+                    c.desc.nullable,
+                )
+            })
+            .collect();
         #[for_await]
         for batch in self.child {
             let batch = batch?;
@@ -25,9 +40,8 @@ impl ProjectExecutor {
                 .iter()
                 .map(|e| e.eval_column(&batch))
                 .try_collect();
-            let fields: Vec<Field> = self.exprs.iter().map(|e| e.eval_field(&batch)).collect();
             let schema = SchemaRef::new(Schema::new_with_metadata(
-                fields,
+                fields.clone(),
                 batch.schema().metadata().clone(),
             ));
             yield RecordBatch::try_new(schema, columns?)?;
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, RecordBatch},
+    datatypes::{Field, Schema},
+};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+
+use crate::{
+    binder::expression::BoundExpr,
+    executor::{
+        aggregation::{
+            agg_field, as_agg_funcs, create_accumulator, eval_agg_columns, update_accumulator,
+        },
+        BoxedExecutor, ExecutorError,
+    },
+};
+
+/// Aggregation without group by, which always outputs exactly one row.
+pub struct SimpleAggExecutor {
+    pub agg_funcs: Vec<BoundExpr>,
+    pub child: BoxedExecutor,
+}
+
+impl SimpleAggExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let agg_funcs = as_agg_funcs(&self.agg_funcs);
+        let mut accumulators = agg_funcs.iter().map(create_accumulator).collect_vec();
+
+        #[for_await]
+        for batch in self.child {
+            let batch = batch?;
+            for (agg, acc) in agg_funcs.iter().zip(accumulators.iter_mut()) {
+                let columns = eval_agg_columns(agg, &batch)?;
+                update_accumulator(acc.as_mut(), agg, &columns)?;
+            }
+        }
+
+        let fields: Vec<Field> = agg_funcs.iter().map(agg_field).collect();
+        let columns: Vec<ArrayRef> = accumulators
+            .iter()
+            .map(|acc| acc.evaluate())
+            .try_collect()?;
+        yield RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+    }
+}
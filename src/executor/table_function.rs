@@ -0,0 +1,19 @@
+use arrow::array::RecordBatch;
+use futures_async_stream::try_stream;
+
+use crate::{executor::ExecutorError, function::FunctionTable};
+
+/// Scan the table returned by a table function.
+pub struct TableFunctionExecutor {
+    pub function: FunctionTable,
+}
+
+impl TableFunctionExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        #[for_await]
+        for batch in self.function.table.scan() {
+            yield batch?;
+        }
+    }
+}
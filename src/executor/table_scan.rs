@@ -1,36 +1,50 @@
 use std::sync::Arc;
 
-use futures_async_stream::try_stream;
+use futures::{StreamExt, TryStreamExt};
 
-use crate::executor::ExecutorError;
-use crate::{
-    optimizer::physical_table::PhysicalTableScan,
-    storage::{Storage, Table, Transaction},
-};
+use crate::binder::expression::BoundExpr;
+use crate::executor::{BoxedExecutor, ExecutorError};
+use crate::{optimizer::physical_table::PhysicalTableScan, storage::Transaction};
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::DataType;
 use arrow::record_batch::RecordBatch;
 
-pub struct TableScanExecutor<S: Storage> {
+pub struct TableScanExecutor {
     pub plan: PhysicalTableScan,
-    pub storage: Arc<S>,
+    pub txn: Arc<dyn Transaction>,
+    /// the number of partitions the table is split into at most
+    pub partitions: usize,
 }
 
-impl<S: Storage> TableScanExecutor<S> {
-    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
-    pub async fn execute(self) {
-        let table_id = self.plan.logical().table_id();
-        let table = self.storage.get_table(table_id)?;
-        let mut tx = table.read()?;
-        loop {
-            match tx.next_batch() {
-                Ok(batch) => {
-                    if let Some(batch) = batch {
-                        yield batch;
-                    } else {
-                        break;
-                    }
-                }
-                Err(err) => return Err(err.into()),
-            }
-        }
+impl TableScanExecutor {
+    pub fn execute(self) -> Vec<BoxedExecutor> {
+        let table = match self.txn.get_table(self.plan.logical().table_id()) {
+            Ok(table) => table,
+            Err(e) => return vec![futures::stream::iter([Err(e.into())]).boxed()],
+        };
+        let filter = self.plan.logical().filter();
+        let pruner = |partitions: &RecordBatch| match &filter {
+            Some(filter) => prune_partitions(filter, partitions),
+            None => vec![true; partitions.num_rows()],
+        };
+        table
+            .scan_partitions(self.partitions, &pruner)
+            .into_iter()
+            .map(|batches| batches.map_err(ExecutorError::from).boxed())
+            .collect()
+    }
+}
+
+/// Keep the partitions unless the filter is false for them. As the partitions have nulls
+/// in the columns other than the partition columns, a filter on those columns is null and
+/// keeps them all.
+fn prune_partitions(filter: &BoundExpr, partitions: &RecordBatch) -> Vec<bool> {
+    match filter.eval_column(partitions) {
+        Ok(keep) if keep.data_type() == &DataType::Boolean => keep
+            .as_boolean()
+            .iter()
+            .map(|keep| keep != Some(false))
+            .collect(),
+        _ => vec![true; partitions.num_rows()],
     }
 }
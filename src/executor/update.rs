@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, RecordBatch},
+    compute::kernels::zip::zip,
+};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+
+use crate::{
+    binder::expression::BoundExpr,
+    catalog::{ColumnCatalog, TableId},
+    executor::{
+        affected_rows_batch, check_not_null, delete::eval_selected_rows, BoxedExecutor,
+        ExecutorError,
+    },
+    storage::Transaction,
+};
+
+/// Update the rows of the child, a scan over the whole table, that the predicate evaluates
+/// to true by rewriting the table with the new values, and output the number of updated
+/// rows. Setting a column which is not nullable to null fails the update.
+pub struct UpdateExecutor {
+    pub table_id: TableId,
+    pub columns: Vec<ColumnCatalog>,
+    pub assignments: Vec<BoundExpr>,
+    pub predicate: Option<BoundExpr>,
+    pub child: BoxedExecutor,
+    pub txn: Arc<dyn Transaction>,
+}
+
+impl UpdateExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        let table = self.txn.get_table(self.table_id)?;
+        let mut rows = 0;
+        let mut batches = vec![];
+        #[for_await]
+        for batch in self.child {
+            let batch = batch?;
+            let updated = eval_selected_rows(&self.predicate, &batch)?;
+            rows += updated.true_count() as i64;
+            let columns: Vec<ArrayRef> = self
+                .assignments
+                .iter()
+                .zip(batch.columns())
+                .map(|(expr, column)| {
+                    let value = expr.eval_column(&batch)?;
+                    Ok::<_, ExecutorError>(zip(&updated, &value, column)?)
+                })
+                .try_collect()?;
+            check_not_null(&self.columns, &columns)?;
+            batches.push(RecordBatch::try_new(batch.schema(), columns)?);
+        }
+        table.overwrite(batches)?;
+        yield affected_rows_batch(rows)?;
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{new_empty_array, ArrayRef, RecordBatch, RecordBatchOptions},
+    compute::concat,
+    datatypes::{Field, Schema},
+};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+
+use crate::{binder::expression::BoundExpr, catalog::ColumnCatalog, executor::ExecutorError};
+
+pub struct ValuesExecutor {
+    pub columns: Vec<ColumnCatalog>,
+    pub rows: Vec<Vec<BoundExpr>>,
+}
+
+impl ValuesExecutor {
+    #[try_stream(boxed, ok = RecordBatch, error = ExecutorError)]
+    pub async fn execute(self) {
+        // the constant exprs of each row are evaluated against a single row without columns
+        let options = RecordBatchOptions::new().with_row_count(Some(1));
+        let row = RecordBatch::try_new_with_options(Arc::new(Schema::empty()), vec![], &options)?;
+
+        let mut columns: Vec<ArrayRef> = vec![];
+        for (i, column) in self.columns.iter().enumerate() {
+            if self.rows.is_empty() {
+                columns.push(new_empty_array(&column.desc.data_type));
+                continue;
+            }
+            let values: Vec<ArrayRef> = self
+                .rows
+                .iter()
+                .map(|r| r[i].eval_column(&row))
+                .try_collect()?;
+            columns.push(concat(&values.iter().map(|v| v.as_ref()).collect_vec())?);
+        }
+
+        let fields = self
+            .columns
+            .iter()
+            .map(|c| Field::new(c.desc.name.clone(), c.desc.data_type.clone(), true))
+            .collect_vec();
+        let options = RecordBatchOptions::new().with_row_count(Some(self.rows.len()));
+        yield RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), columns, &options)?;
+    }
+}
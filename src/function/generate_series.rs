@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Int64Array, RecordBatch},
+    compute::cast,
+    datatypes::{DataType, Field, Schema, SchemaRef},
+};
+
+use crate::{
+    binder::BindError,
+    function::{FunctionTable, TableFunction, TableFunctionArgs},
+    storage::{StorageError, Table, TableReader},
+    types::ScalarValue,
+};
+
+/// the number of values in a batch of a series
+const SERIES_BATCH_SIZE: usize = 1024;
+
+/// `generate_series(start, stop [, step])`, the integers from start to stop inclusive,
+/// counting by step which is 1 by default. The column is a bigint unless all arguments
+/// are ints.
+pub struct GenerateSeries;
+
+impl TableFunction for GenerateSeries {
+    fn call(&self, args: &TableFunctionArgs) -> Result<FunctionTable, BindError> {
+        args.expect_positional(2, 3)?;
+        args.named(&[])?;
+        let mut data_type = DataType::Int32;
+        let mut values = vec![];
+        for arg in &args.positional {
+            values.push(match arg {
+                ScalarValue::Int32(Some(v)) => *v as i64,
+                ScalarValue::Int64(Some(v)) => {
+                    data_type = DataType::Int64;
+                    *v
+                }
+                arg => return Err(args.invalid(format!("expected an integer, got {arg:?}"))),
+            });
+        }
+        let step = values.get(2).copied().unwrap_or(1);
+        if step == 0 {
+            return Err(args.invalid("step size cannot equal zero"));
+        }
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "generate_series",
+            data_type,
+            false,
+        )]));
+        Ok(FunctionTable {
+            schema: schema.clone(),
+            table: Arc::new(SeriesTable {
+                start: values[0],
+                stop: values[1],
+                step,
+                schema,
+            }),
+        })
+    }
+}
+
+struct SeriesTable {
+    start: i64,
+    stop: i64,
+    step: i64,
+    schema: SchemaRef,
+}
+
+impl Table for SeriesTable {
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+        Ok(Box::new(SeriesReader {
+            next: Some(self.start),
+            stop: self.stop,
+            step: self.step,
+            schema: self.schema.clone(),
+        }))
+    }
+}
+
+struct SeriesReader {
+    /// the next value, none once past the stop or the range of bigint
+    next: Option<i64>,
+    stop: i64,
+    step: i64,
+    schema: SchemaRef,
+}
+
+impl TableReader for SeriesReader {
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError> {
+        let in_range = |v: i64| {
+            if self.step > 0 {
+                v <= self.stop
+            } else {
+                v >= self.stop
+            }
+        };
+        let mut values = Vec::with_capacity(SERIES_BATCH_SIZE);
+        while let Some(value) = self.next.filter(|v| in_range(*v)) {
+            values.push(value);
+            self.next = value.checked_add(self.step);
+            if values.len() == SERIES_BATCH_SIZE {
+                break;
+            }
+        }
+        if values.is_empty() {
+            return Ok(None);
+        }
+        let column = cast(&Int64Array::from(values), self.schema.field(0).data_type())?;
+        Ok(Some(RecordBatch::try_new(
+            self.schema.clone(),
+            vec![column],
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod generate_series_test {
+    use arrow::array::AsArray;
+    use arrow::datatypes::Int32Type;
+
+    use super::*;
+
+    fn series(positional: Vec<ScalarValue>) -> Result<Vec<i64>, BindError> {
+        let args = TableFunctionArgs {
+            function: "generate_series".to_string(),
+            positional,
+            named: vec![],
+        };
+        let table = GenerateSeries.call(&args)?;
+        let mut reader = table.table.read().unwrap();
+        let mut values = vec![];
+        while let Some(batch) = reader.next_batch().unwrap() {
+            let column = cast(batch.column(0), &DataType::Int64).unwrap();
+            values.extend(
+                column
+                    .as_primitive::<arrow::datatypes::Int64Type>()
+                    .values(),
+            );
+        }
+        Ok(values)
+    }
+
+    #[test]
+    fn test_generate_series() -> Result<(), BindError> {
+        assert_eq!(series(vec![1.into(), 5.into()])?, vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            series(vec![5.into(), 1.into(), (-2).into()])?,
+            vec![5, 3, 1]
+        );
+        assert_eq!(series(vec![5.into(), 1.into()])?, Vec::<i64>::new());
+        assert_eq!(series(vec![0.into(), 2047.into()])?.len(), 2048);
+        assert_eq!(
+            series(vec![(i64::MAX - 1).into(), i64::MAX.into()])?,
+            vec![i64::MAX - 1, i64::MAX]
+        );
+        assert!(series(vec![1.into(), 5.into(), 0.into()]).is_err());
+        assert!(series(vec![1.into()]).is_err());
+        assert!(series(vec![1.into(), "a".to_string().into()]).is_err());
+
+        let args = TableFunctionArgs {
+            function: "generate_series".to_string(),
+            positional: vec![1.into(), 3.into()],
+            named: vec![],
+        };
+        let batch = GenerateSeries
+            .call(&args)?
+            .table
+            .read()
+            .unwrap()
+            .next_batch()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            batch.column(0).as_primitive::<Int32Type>().values(),
+            &[1, 2, 3]
+        );
+        Ok(())
+    }
+}
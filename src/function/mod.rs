@@ -0,0 +1,117 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use arrow::datatypes::SchemaRef;
+
+use crate::{binder::BindError, storage::Table, types::ScalarValue};
+
+mod generate_series;
+mod read_file;
+pub use generate_series::*;
+pub use read_file::*;
+
+/// A function returning a table, which is called in the FROM clause like
+/// `select * from generate_series(1, 10)`. It is called while binding the query, so the
+/// columns of its table are known, while the rows are read when the query runs.
+pub trait TableFunction: Sync + Send + 'static {
+    fn call(&self, args: &TableFunctionArgs) -> Result<FunctionTable, BindError>;
+}
+
+/// The constant arguments of a table function call, given by position or by name like
+/// `read_csv('data.csv', delim => '|')`.
+#[derive(Debug, Clone, Default)]
+pub struct TableFunctionArgs {
+    pub function: String,
+    pub positional: Vec<ScalarValue>,
+    /// the named arguments with lowercase names
+    pub named: Vec<(String, ScalarValue)>,
+}
+
+impl TableFunctionArgs {
+    /// fail unless the number of positional arguments is between `min` and `max`
+    pub fn expect_positional(&self, min: usize, max: usize) -> Result<(), BindError> {
+        let count = self.positional.len();
+        if count < min || count > max {
+            return Err(self.invalid(format!("expected {min} to {max} arguments, got {count}")));
+        }
+        Ok(())
+    }
+
+    /// the positional argument at the index, which must be a string
+    pub fn string(&self, index: usize) -> Result<String, BindError> {
+        match self.positional.get(index) {
+            Some(ScalarValue::String(Some(s))) => Ok(s.clone()),
+            arg => Err(self.invalid(format!("expected a string, got {arg:?}"))),
+        }
+    }
+
+    /// the named arguments, failing on names the function does not take
+    pub fn named(&self, names: &[&str]) -> Result<&[(String, ScalarValue)], BindError> {
+        match self
+            .named
+            .iter()
+            .find(|(name, _)| !names.contains(&name.as_str()))
+        {
+            Some((name, _)) => Err(self.invalid(format!("unknown argument {name}"))),
+            None => Ok(&self.named),
+        }
+    }
+
+    pub fn invalid(&self, reason: impl Into<String>) -> BindError {
+        BindError::InvalidFunctionArgs(self.function.clone(), reason.into())
+    }
+}
+
+/// The table returned by a table function, whose columns are those of the schema.
+#[derive(Clone)]
+pub struct FunctionTable {
+    pub schema: SchemaRef,
+    pub table: Arc<dyn Table>,
+}
+
+impl fmt::Debug for FunctionTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FunctionTable")
+            .field("schema", &self.schema)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The table functions callable by name, starting with the builtin ones:
+///
+/// - `generate_series(start, stop [, step])`
+/// - `read_csv(location, delim =>, header =>, nullstr =>, datetime_format =>, compression =>)`
+/// - `read_json(location, compression =>)`
+/// - `read_arrow(location)`
+///
+/// The location of the files may be a file, a directory or a glob, like in
+/// `CREATE EXTERNAL TABLE`.
+#[derive(Clone)]
+pub struct TableFunctions {
+    functions: HashMap<String, Arc<dyn TableFunction>>,
+}
+
+impl Default for TableFunctions {
+    fn default() -> Self {
+        let mut functions = Self {
+            functions: HashMap::new(),
+        };
+        functions.register("generate_series", Arc::new(GenerateSeries));
+        functions.register("read_csv", Arc::new(ReadCsv));
+        functions.register("read_json", Arc::new(ReadJson));
+        functions.register("read_ndjson", Arc::new(ReadJson));
+        functions.register("read_arrow", Arc::new(ReadArrow));
+        functions.register("read_ipc", Arc::new(ReadArrow));
+        functions
+    }
+}
+
+impl TableFunctions {
+    /// add a function, replacing the function of the same name
+    pub fn register(&mut self, name: &str, function: Arc<dyn TableFunction>) {
+        self.functions.insert(name.to_lowercase(), function);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn TableFunction>> {
+        self.functions.get(&name.to_lowercase()).cloned()
+    }
+}
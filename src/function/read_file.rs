@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use arrow::datatypes::{Field, Schema, SchemaRef};
+
+use crate::{
+    binder::BindError,
+    catalog::ColumnCatalog,
+    function::{FunctionTable, TableFunction, TableFunctionArgs},
+    storage::{CsvConfig, CsvTable, FileCompression, IpcConfig, IpcTable, JsonConfig, JsonTable},
+    types::ScalarValue,
+};
+
+/// `read_csv(location, ...)`, the rows of csv files, with the options of csv tables as
+/// named arguments
+pub struct ReadCsv;
+
+impl TableFunction for ReadCsv {
+    fn call(&self, args: &TableFunctionArgs) -> Result<FunctionTable, BindError> {
+        args.expect_positional(1, 1)?;
+        let mut config = CsvConfig::new();
+        let names = [
+            "delim",
+            "delimiter",
+            "header",
+            "newlines_in_values",
+            "nullstr",
+            "datetime_format",
+            "compression",
+        ];
+        for (name, value) in args.named(&names)? {
+            let invalid = || args.invalid(format!("invalid {name} {value:?}"));
+            config = match (name.as_str(), value) {
+                ("delim" | "delimiter", ScalarValue::String(Some(s))) => match s.as_bytes() {
+                    [delimiter] => config.with_delimiter(*delimiter),
+                    _ => return Err(invalid()),
+                },
+                ("header", ScalarValue::Boolean(Some(header))) => config.with_header(*header),
+                ("newlines_in_values", ScalarValue::Boolean(Some(newlines))) => {
+                    config.with_newlines_in_values(*newlines)
+                }
+                ("nullstr", ScalarValue::String(Some(s))) => config.with_null_marker(s),
+                ("datetime_format", ScalarValue::String(Some(s))) => config.with_datetime_format(s),
+                ("compression", value) => {
+                    config.with_compression(compression(value).ok_or_else(invalid)?)
+                }
+                _ => return Err(invalid()),
+            };
+        }
+        let table = CsvTable::new(args.function.clone(), args.string(0)?, config)?;
+        Ok(FunctionTable {
+            schema: schema_of(table.catalog().get_all_columns()),
+            table: Arc::new(table),
+        })
+    }
+}
+
+/// `read_json(location, compression => ...)`, the rows of newline-delimited json files
+pub struct ReadJson;
+
+impl TableFunction for ReadJson {
+    fn call(&self, args: &TableFunctionArgs) -> Result<FunctionTable, BindError> {
+        args.expect_positional(1, 1)?;
+        let mut config = JsonConfig::new();
+        for (name, value) in args.named(&["compression"])? {
+            let invalid = || args.invalid(format!("invalid {name} {value:?}"));
+            config = config.with_compression(compression(value).ok_or_else(invalid)?);
+        }
+        let table = JsonTable::new(args.function.clone(), args.string(0)?, config)?;
+        Ok(FunctionTable {
+            schema: schema_of(table.catalog().get_all_columns()),
+            table: Arc::new(table),
+        })
+    }
+}
+
+/// `read_arrow(location)`, the rows of Arrow IPC files or streams
+pub struct ReadArrow;
+
+impl TableFunction for ReadArrow {
+    fn call(&self, args: &TableFunctionArgs) -> Result<FunctionTable, BindError> {
+        args.expect_positional(1, 1)?;
+        args.named(&[])?;
+        let table = IpcTable::new(args.function.clone(), args.string(0)?, IpcConfig::new())?;
+        Ok(FunctionTable {
+            schema: schema_of(table.catalog().get_all_columns()),
+            table: Arc::new(table),
+        })
+    }
+}
+
+fn compression(value: &ScalarValue) -> Option<FileCompression> {
+    match value {
+        ScalarValue::String(Some(s)) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn schema_of(columns: Vec<ColumnCatalog>) -> SchemaRef {
+    let fields = columns
+        .into_iter()
+        .map(|c| Field::new(c.id, c.desc.data_type, true))
+        .collect::<Vec<_>>();
+    Arc::new(Schema::new(fields))
+}
@@ -2,15 +2,16 @@
 #![feature(coroutines)]
 #![feature(iterator_try_collect)]
 #![feature(error_generic_member_access)]
-#![feature(assert_matches)]
 
 pub mod binder;
 pub mod catalog;
 pub mod db;
 pub mod executor;
+pub mod function;
 pub mod optimizer;
 pub mod parser;
 pub mod planner;
 pub mod storage;
+pub mod substrait;
 pub mod types;
 pub mod utill;
@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::expression::BoundExpr,
+    optimizer::{
+        expr_visitor::ExprVisitor, logical_filter::LogicalFilter, logical_project::LogicalProject,
+        plan_rewriter::PlanRewriter, LogicalAgg, PlanRef,
+    },
+};
+
+/// Computes the subexprs repeated in the exprs of a project or an aggregation and in the
+/// filter below it once, in a project below them. Those in the filter are computed below
+/// the filter, as the filter evaluates them for every input row anyway, and the others
+/// above it, so they are only computed for the rows passing it.
+///
+/// The exprs of the plan itself are not changed: the `InputRefRewriter` resolves a
+/// repeated subexpr to the column of the project computing it, as the subexpr equals the
+/// expr bound to that column. For `SELECT c1 + c2, (c1 + c2) * 2 FROM t WHERE c1 + c2 > 1`
+/// the plan turns into
+///
+/// ```text
+/// Project: c1 + c2, (c1 + c2) * 2
+///   Filter: c1 + c2 > 1
+///     Project: c1 + c2
+///       TableScan: t
+/// ```
+///
+/// Only the exprs over the columns of a table, values or table function are looked at,
+/// not those over the outputs of an aggregation.
+#[derive(Default)]
+pub struct CommonSubexprEliminator {}
+
+impl CommonSubexprEliminator {
+    /// rewrite the input of the exprs of a project or an aggregation, which is either a
+    /// filter or the relation the exprs are computed over
+    fn rewrite_input(&mut self, input: PlanRef, exprs: &[BoundExpr]) -> PlanRef {
+        let (filter, relation) = match input.as_logical_filter() {
+            Ok(filter) => (Some(filter.expr()), filter.input()),
+            Err(_) => (None, input.clone()),
+        };
+        if relation.as_logical_agg().is_ok() || relation.as_logical_project().is_ok() {
+            return self.rewrite(input);
+        }
+
+        let mut counter = SubexprCounter::default();
+        filter
+            .iter()
+            .chain(exprs)
+            .for_each(|e| counter.visit_expr(e));
+        let mut below = vec![];
+        if let Some(filter) = &filter {
+            counter.collect_common(filter, &mut below);
+        }
+        let mut above = vec![];
+        for expr in exprs {
+            counter.collect_common(expr, &mut above);
+        }
+        above.retain(|e| !below.contains(e));
+
+        let mut plan = self.rewrite(relation);
+        if !below.is_empty() {
+            let mut columns = vec![];
+            for expr in filter.iter().chain(exprs) {
+                collect_inputs(expr, &below, &mut columns);
+            }
+            plan = Arc::new(LogicalProject::new(columns, plan));
+        }
+        if let Some(filter) = filter {
+            plan = Arc::new(LogicalFilter::new(filter, plan));
+        }
+        if !above.is_empty() {
+            let computed = [below, above].concat();
+            let mut columns = vec![];
+            for expr in exprs {
+                collect_inputs(expr, &computed, &mut columns);
+            }
+            plan = Arc::new(LogicalProject::new(columns, plan));
+        }
+        plan
+    }
+}
+
+impl PlanRewriter for CommonSubexprEliminator {
+    fn rewrite_logical_project(&mut self, plan: &LogicalProject) -> PlanRef {
+        let exprs = plan.exprs();
+        let input = self.rewrite_input(plan.input(), &exprs);
+        Arc::new(LogicalProject::new(exprs, input))
+    }
+
+    fn rewrite_logical_agg(&mut self, plan: &LogicalAgg) -> PlanRef {
+        let exprs = [plan.agg_funcs(), plan.group_by()].concat();
+        let input = self.rewrite_input(plan.input(), &exprs);
+        Arc::new(LogicalAgg::new_with_grouping_sets(
+            plan.agg_funcs(),
+            plan.group_by(),
+            plan.grouping_sets(),
+            input,
+        ))
+    }
+}
+
+/// Counts how many times each subexpr which is worth computing once occurs.
+#[derive(Default)]
+struct SubexprCounter {
+    counts: Vec<(BoundExpr, usize)>,
+}
+
+impl SubexprCounter {
+    fn count(&self, expr: &BoundExpr) -> usize {
+        self.counts
+            .iter()
+            .find(|(e, _)| e == expr)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// collect the largest subexprs of expr which occur more than once
+    fn collect_common(&self, expr: &BoundExpr, common: &mut Vec<BoundExpr>) {
+        if self.count(expr) > 1 {
+            if !common.contains(expr) {
+                common.push(expr.clone());
+            }
+            return;
+        }
+        for child in children(expr) {
+            self.collect_common(child, common);
+        }
+    }
+}
+
+impl ExprVisitor for SubexprCounter {
+    fn pre_visit(&mut self, expr: &BoundExpr) {
+        // columns and constants cost nothing to compute, while aggregations are computed
+        // once by the aggregation already
+        if !matches!(expr, BoundExpr::BinaryOp(_) | BoundExpr::TypeCast(_)) || has_agg(expr) {
+            return;
+        }
+        match self.counts.iter_mut().find(|(e, _)| e == expr) {
+            Some((_, count)) => *count += 1,
+            None => self.counts.push((expr.clone(), 1)),
+        }
+    }
+}
+
+fn children(expr: &BoundExpr) -> Vec<&BoundExpr> {
+    match expr {
+        BoundExpr::BinaryOp(e) => vec![&e.left, &e.right],
+        BoundExpr::TypeCast(e) => vec![&e.expr],
+        BoundExpr::IsNull(e) => vec![&e.expr],
+        BoundExpr::AggFunc(e) => e
+            .exprs
+            .iter()
+            .chain(e.filter.as_deref())
+            .chain(e.order_by.iter().map(|o| &o.expr))
+            .collect(),
+        BoundExpr::Constant(_) | BoundExpr::ColumnRef(_) | BoundExpr::InputRef(_) => vec![],
+    }
+}
+
+fn has_agg(expr: &BoundExpr) -> bool {
+    matches!(expr, BoundExpr::AggFunc(_)) || children(expr).into_iter().any(has_agg)
+}
+
+/// collect the exprs expr is computed from, which are the computed subexprs it contains
+/// and the columns outside of them
+fn collect_inputs(expr: &BoundExpr, computed: &[BoundExpr], inputs: &mut Vec<BoundExpr>) {
+    if computed.contains(expr) || matches!(expr, BoundExpr::ColumnRef(_)) {
+        if !inputs.contains(expr) {
+            inputs.push(expr.clone());
+        }
+        return;
+    }
+    for child in children(expr) {
+        collect_inputs(child, computed, inputs);
+    }
+}
+
+#[cfg(test)]
+mod common_subexpr_eliminator_test {
+    use arrow::datatypes::DataType;
+    use sqlparser::ast::BinaryOperator;
+
+    use super::*;
+    use crate::{
+        binder::expression::{
+            agg_func::{AggFunc, BoundAggFunc},
+            BoundInputRef,
+        },
+        optimizer::{
+            input_ref_rewriter::InputRefRewriter,
+            logical_table_scan::LogicalTableScan,
+            test_util::{binary, column, constant},
+            PlanNodeType,
+        },
+    };
+
+    fn scan() -> PlanRef {
+        let columns = ["c1", "c2", "c3"]
+            .iter()
+            .map(|name| match column(name) {
+                BoundExpr::ColumnRef(c) => c.column_catalog,
+                _ => unreachable!(),
+            })
+            .collect();
+        Arc::new(LogicalTableScan::new("t".to_string(), columns))
+    }
+
+    #[test]
+    fn test_eliminate_subexprs_of_project_and_filter() {
+        use BinaryOperator::*;
+        let sum = binary(Plus, column("c1"), column("c2"));
+        let product = binary(Multiply, column("c3"), constant(2));
+        // c1 + c2 is in the filter and c3 * 2 is only in the select list
+        let filter = binary(Gt, sum.clone(), constant(1));
+        let exprs = vec![
+            binary(Multiply, sum.clone(), constant(2)),
+            product.clone(),
+            binary(Plus, product.clone(), sum.clone()),
+        ];
+        let plan = Arc::new(LogicalProject::new(
+            exprs.clone(),
+            Arc::new(LogicalFilter::new(filter.clone(), scan())),
+        ));
+
+        let plan = CommonSubexprEliminator::default().rewrite(plan);
+        let project = plan.as_logical_project().unwrap();
+        assert_eq!(project.exprs(), exprs);
+        let above = project.input();
+        assert_eq!(
+            above.as_logical_project().unwrap().exprs(),
+            vec![sum.clone(), product.clone()]
+        );
+        let filter_plan = above.children()[0].clone();
+        assert_eq!(filter_plan.as_logical_filter().unwrap().expr(), filter);
+        let below = filter_plan.children()[0].clone();
+        assert_eq!(
+            below.as_logical_project().unwrap().exprs(),
+            vec![sum.clone(), column("c3")]
+        );
+        assert_eq!(
+            below.children()[0].node_type(),
+            PlanNodeType::LogicalTableScan
+        );
+
+        // the repeated subexprs resolve to the columns computing them
+        let plan = InputRefRewriter::default().rewrite(plan);
+        let filter = plan.children()[0].children()[0].clone();
+        let input_ref = BoundExpr::InputRef(BoundInputRef {
+            index: 0,
+            return_type: DataType::Int32,
+        });
+        assert_eq!(
+            filter.as_logical_filter().unwrap().expr(),
+            binary(Gt, input_ref, constant(1))
+        );
+    }
+
+    #[test]
+    fn test_keep_plan_without_common_subexprs() {
+        use BinaryOperator::*;
+        let sum = binary(Plus, column("c1"), column("c2"));
+        let agg = BoundExpr::AggFunc(BoundAggFunc {
+            func: AggFunc::Sum,
+            exprs: vec![sum.clone()],
+            return_type: DataType::Int32,
+            filter: None,
+            order_by: vec![],
+        });
+        let plan: PlanRef = Arc::new(LogicalAgg::new(
+            vec![agg.clone()],
+            vec![column("c3")],
+            Arc::new(LogicalFilter::new(
+                binary(Gt, column("c1"), constant(1)),
+                scan(),
+            )),
+        ));
+        let plan = CommonSubexprEliminator::default().rewrite(plan);
+        assert_eq!(plan.children()[0].node_type(), PlanNodeType::LogicalFilter);
+
+        // the argument of an aggregation is computed once for it and the filter
+        let plan: PlanRef = Arc::new(LogicalAgg::new(
+            vec![agg],
+            vec![column("c3")],
+            Arc::new(LogicalFilter::new(
+                binary(Gt, sum.clone(), constant(1)),
+                scan(),
+            )),
+        ));
+        let plan = CommonSubexprEliminator::default().rewrite(plan);
+        let below = plan.children()[0].children()[0].clone();
+        assert_eq!(
+            below.as_logical_project().unwrap().exprs(),
+            vec![sum, column("c3")]
+        );
+    }
+}
@@ -9,6 +9,7 @@ pub trait ExprRewriter {
             BoundExpr::BinaryOp(_) => self.rewrite_binary_op(expr),
             BoundExpr::TypeCast(_) => self.rewrite_type_cast(expr),
             BoundExpr::AggFunc(_) => self.rewrite_agg_func(expr),
+            BoundExpr::IsNull(_) => self.rewrite_is_null(expr),
         }
     }
 
@@ -30,12 +31,25 @@ pub trait ExprRewriter {
         }
     }
 
+    fn rewrite_is_null(&self, expr: &mut BoundExpr) {
+        match expr {
+            BoundExpr::IsNull(e) => self.rewrite_expr(&mut e.expr),
+            _ => unreachable!(),
+        }
+    }
+
     fn rewrite_agg_func(&self, expr: &mut BoundExpr) {
         match expr {
             BoundExpr::AggFunc(e) => {
                 for arg in &mut e.exprs {
                     self.rewrite_expr(arg);
                 }
+                if let Some(filter) = &mut e.filter {
+                    self.rewrite_expr(filter);
+                }
+                for order_by in &mut e.order_by {
+                    self.rewrite_expr(&mut order_by.expr);
+                }
             }
             _ => unreachable!(),
         }
@@ -0,0 +1,428 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{RecordBatch, RecordBatchOptions},
+    datatypes::{DataType, Schema},
+};
+use sqlparser::ast::BinaryOperator;
+
+use crate::{
+    binder::expression::{binary_op::BoundBinaryOp, BoundExpr, BoundIsNull},
+    optimizer::{
+        expr_rewriter::ExprRewriter, logical_delete::LogicalDelete, logical_filter::LogicalFilter,
+        logical_project::LogicalProject, logical_table_scan::LogicalTableScan,
+        logical_update::LogicalUpdate, logical_values::LogicalValues, plan_rewriter::PlanRewriter,
+        LogicalAgg, PlanRef,
+    },
+    types::ScalarValue,
+};
+
+/// Simplifies the exprs of a logical plan before it is executed:
+///
+/// - constant subtrees like `1 + 2` or `CAST(1 AS BIGINT)` are folded into constants, also
+///   across a column like in `c1 + 1 + 2`
+/// - casts to the type of their expr are removed
+/// - `c IS NOT NULL` is true and `c IS NULL` is false for a column `c` which is not
+///   nullable
+/// - `true AND p` is `p`, `false AND p` is false, `true OR p` is true and `false OR p` is `p`
+/// - `x = x` is `x IS NOT NULL`, as it is null rather than true where `x` is null
+/// - a filter which is always true is removed, and one which is never true turns into an
+///   empty relation, so its input is not read at all
+///
+/// Constants are evaluated like they are when the plan runs, and an expr whose evaluation
+/// fails, like `1 / 0`, is kept so that it fails when the plan runs.
+#[derive(Default)]
+pub struct ExprSimplifier {}
+
+impl ExprSimplifier {
+    fn simplify(&self, mut expr: BoundExpr) -> BoundExpr {
+        self.rewrite_expr(&mut expr);
+        expr
+    }
+
+    fn simplify_all(&self, exprs: Vec<BoundExpr>) -> Vec<BoundExpr> {
+        exprs.into_iter().map(|e| self.simplify(e)).collect()
+    }
+
+    /// the value of an expr without columns, unless it fails or has no scalar value
+    fn evaluate(&self, expr: &BoundExpr) -> Option<ScalarValue> {
+        let data_type = expr.return_type()?;
+        if !matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::Float64
+                | DataType::Utf8
+        ) {
+            return None;
+        }
+        let options = RecordBatchOptions::new().with_row_count(Some(1));
+        let row =
+            RecordBatch::try_new_with_options(Arc::new(Schema::empty()), vec![], &options).ok()?;
+        let array = expr.eval_column(&row).ok()?;
+        ScalarValue::try_from_array(&array, 0).ok()
+    }
+
+    /// `(x + c1) + c2` is `x + (c1 + c2)` with integers, for which it fails to overflow the
+    /// same, as long as the constants have the same sign; likewise for `*` unless a
+    /// constant is zero
+    fn reassociate(&self, binary_op: &BoundBinaryOp) -> Option<BoundExpr> {
+        let BoundExpr::Constant(outer) = binary_op.right.as_ref() else {
+            return None;
+        };
+        let BoundExpr::BinaryOp(inner) = binary_op.left.as_ref() else {
+            return None;
+        };
+        if inner.op != binary_op.op {
+            return None;
+        }
+        let (x, inner_constant) = match (inner.left.as_ref(), inner.right.as_ref()) {
+            (x, BoundExpr::Constant(c)) | (BoundExpr::Constant(c), x) => (x, c),
+            _ => return None,
+        };
+        let signum = |value: &ScalarValue| match value {
+            ScalarValue::Int32(Some(v)) => Some(v.signum() as i64),
+            ScalarValue::Int64(Some(v)) => Some(v.signum()),
+            _ => None,
+        };
+        let (a, b) = (signum(inner_constant)?, signum(outer)?);
+        let reassociable = match binary_op.op {
+            BinaryOperator::Plus => a * b >= 0,
+            BinaryOperator::Multiply => a != 0 && b != 0,
+            _ => false,
+        };
+        if !reassociable {
+            return None;
+        }
+        let constant = self.evaluate(&BoundExpr::BinaryOp(BoundBinaryOp {
+            op: binary_op.op.clone(),
+            left: Box::new(BoundExpr::Constant(inner_constant.clone())),
+            right: Box::new(BoundExpr::Constant(outer.clone())),
+            return_type: binary_op.return_type.clone(),
+        }))?;
+        Some(BoundExpr::BinaryOp(BoundBinaryOp {
+            op: binary_op.op.clone(),
+            left: Box::new(x.clone()),
+            right: Box::new(BoundExpr::Constant(constant)),
+            return_type: binary_op.return_type.clone(),
+        }))
+    }
+}
+
+impl ExprRewriter for ExprSimplifier {
+    fn rewrite_binary_op(&self, expr: &mut BoundExpr) {
+        let BoundExpr::BinaryOp(binary_op) = expr else {
+            unreachable!()
+        };
+        self.rewrite_expr(&mut binary_op.left);
+        self.rewrite_expr(&mut binary_op.right);
+
+        use BinaryOperator as Op;
+        let (left, right) = (binary_op.left.as_ref(), binary_op.right.as_ref());
+        let simplified = match (&binary_op.op, left, right) {
+            (Op::And, BoundExpr::Constant(ScalarValue::Boolean(Some(false))), _)
+            | (Op::And, _, BoundExpr::Constant(ScalarValue::Boolean(Some(false)))) => {
+                Some(BoundExpr::Constant(false.into()))
+            }
+            (Op::Or, BoundExpr::Constant(ScalarValue::Boolean(Some(true))), _)
+            | (Op::Or, _, BoundExpr::Constant(ScalarValue::Boolean(Some(true)))) => {
+                Some(BoundExpr::Constant(true.into()))
+            }
+            (Op::And, BoundExpr::Constant(ScalarValue::Boolean(Some(true))), p)
+            | (Op::And, p, BoundExpr::Constant(ScalarValue::Boolean(Some(true))))
+            | (Op::Or, BoundExpr::Constant(ScalarValue::Boolean(Some(false))), p)
+            | (Op::Or, p, BoundExpr::Constant(ScalarValue::Boolean(Some(false)))) => {
+                Some(p.clone())
+            }
+            // only the operators the executor evaluates are folded
+            (
+                Op::Plus
+                | Op::Minus
+                | Op::Multiply
+                | Op::Divide
+                | Op::Gt
+                | Op::Lt
+                | Op::GtEq
+                | Op::LtEq
+                | Op::Eq
+                | Op::And
+                | Op::Or,
+                BoundExpr::Constant(_),
+                BoundExpr::Constant(_),
+            ) => self.evaluate(expr).map(BoundExpr::Constant),
+            (Op::Eq, left, right) if left == right => {
+                let mut is_not_null = BoundExpr::IsNull(BoundIsNull {
+                    expr: Box::new(left.clone()),
+                    negated: true,
+                });
+                self.rewrite_is_null(&mut is_not_null);
+                Some(is_not_null)
+            }
+            (Op::Plus | Op::Multiply, _, BoundExpr::Constant(_)) => self.reassociate(binary_op),
+            _ => None,
+        };
+        if let Some(simplified) = simplified {
+            *expr = simplified;
+        }
+    }
+
+    fn rewrite_is_null(&self, expr: &mut BoundExpr) {
+        let BoundExpr::IsNull(is_null) = expr else {
+            unreachable!()
+        };
+        self.rewrite_expr(&mut is_null.expr);
+        match is_null.expr.as_ref() {
+            BoundExpr::Constant(_) => {
+                if let Some(value) = self.evaluate(expr) {
+                    *expr = BoundExpr::Constant(value);
+                }
+            }
+            // a column which is not nullable is never null
+            BoundExpr::ColumnRef(column_ref) if !column_ref.column_catalog.desc.nullable => {
+                *expr = BoundExpr::Constant(is_null.negated.into());
+            }
+            _ => {}
+        }
+    }
+
+    fn rewrite_type_cast(&self, expr: &mut BoundExpr) {
+        let BoundExpr::TypeCast(type_cast) = expr else {
+            unreachable!()
+        };
+        self.rewrite_expr(&mut type_cast.expr);
+        if type_cast.expr.return_type().as_ref() == Some(&type_cast.cast_type) {
+            *expr = *type_cast.expr.clone();
+        } else if let BoundExpr::Constant(_) = type_cast.expr.as_ref() {
+            if let Some(value) = self.evaluate(expr) {
+                *expr = BoundExpr::Constant(value);
+            }
+        }
+    }
+}
+
+impl PlanRewriter for ExprSimplifier {
+    fn rewrite_logical_filter(&mut self, plan: &LogicalFilter) -> PlanRef {
+        let input = self.rewrite(plan.input());
+        match self.simplify(plan.expr()) {
+            BoundExpr::Constant(ScalarValue::Boolean(Some(true))) => input,
+            // a filter of null passes no rows either
+            BoundExpr::Constant(value) if value.is_null() || value == false.into() => {
+                Arc::new(LogicalValues::new(input.schema(), vec![]))
+            }
+            expr => Arc::new(LogicalFilter::new(expr, input)),
+        }
+    }
+
+    fn rewrite_logical_table_scan(&mut self, plan: &LogicalTableScan) -> PlanRef {
+        let filter = plan.filter().map(|filter| self.simplify(filter));
+        Arc::new(plan.clone().with_filter(filter))
+    }
+
+    fn rewrite_logical_values(&mut self, plan: &LogicalValues) -> PlanRef {
+        let rows = plan
+            .rows()
+            .into_iter()
+            .map(|row| self.simplify_all(row))
+            .collect();
+        Arc::new(LogicalValues::new(plan.columns(), rows))
+    }
+
+    fn rewrite_logical_project(&mut self, plan: &LogicalProject) -> PlanRef {
+        let input = self.rewrite(plan.input());
+        Arc::new(LogicalProject::new(self.simplify_all(plan.exprs()), input))
+    }
+
+    /// the exprs of the aggregation are simplified like those of the project above it,
+    /// which refers to them by equality
+    fn rewrite_logical_agg(&mut self, plan: &LogicalAgg) -> PlanRef {
+        let input = self.rewrite(plan.input());
+        Arc::new(LogicalAgg::new_with_grouping_sets(
+            self.simplify_all(plan.agg_funcs()),
+            self.simplify_all(plan.group_by()),
+            plan.grouping_sets(),
+            input,
+        ))
+    }
+
+    fn rewrite_logical_update(&mut self, plan: &LogicalUpdate) -> PlanRef {
+        let input = self.rewrite(plan.input());
+        Arc::new(LogicalUpdate::new(
+            plan.table_id(),
+            self.simplify_all(plan.assignments()),
+            plan.predicate().map(|p| self.simplify(p)),
+            input,
+        ))
+    }
+
+    fn rewrite_logical_delete(&mut self, plan: &LogicalDelete) -> PlanRef {
+        let input = self.rewrite(plan.input());
+        Arc::new(LogicalDelete::new(
+            plan.table_id(),
+            plan.predicate().map(|p| self.simplify(p)),
+            input,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod expr_simplifier_test {
+    use arrow::datatypes::DataType;
+
+    use super::*;
+    use crate::{
+        binder::expression::BoundTypeCast,
+        catalog::{ColumnCatalog, ColumnDesc},
+        optimizer::{
+            test_util::{binary, column, constant},
+            PlanNodeType,
+        },
+    };
+
+    fn cast(expr: BoundExpr, cast_type: DataType) -> BoundExpr {
+        BoundExpr::TypeCast(BoundTypeCast {
+            expr: Box::new(expr),
+            cast_type,
+        })
+    }
+
+    #[test]
+    fn test_simplify_exprs() {
+        use BinaryOperator::*;
+        let simplifier = ExprSimplifier::default();
+        let c1 = column("c1");
+
+        let expr = binary(Plus, binary(Plus, c1.clone(), constant(1)), constant(2));
+        assert_eq!(
+            simplifier.simplify(expr),
+            binary(Plus, c1.clone(), constant(3))
+        );
+        let expr = binary(Plus, binary(Plus, constant(1), constant(2)), c1.clone());
+        assert_eq!(
+            simplifier.simplify(expr),
+            binary(Plus, constant(3), c1.clone())
+        );
+        // the inner sum may overflow where the folded one does not
+        let expr = binary(Plus, binary(Plus, c1.clone(), constant(1)), constant(-2));
+        assert_eq!(simplifier.simplify(expr.clone()), expr);
+
+        let expr = binary(Gt, cast(constant(1), DataType::Int64), constant(2i64));
+        assert_eq!(simplifier.simplify(expr), constant(false));
+        assert_eq!(
+            simplifier.simplify(cast(c1.clone(), DataType::Int32)),
+            c1.clone()
+        );
+        let c1_int64 = cast(c1.clone(), DataType::Int64);
+        assert_eq!(simplifier.simplify(c1_int64.clone()), c1_int64);
+
+        let p = binary(Eq, c1.clone(), constant(1));
+        let always = binary(Eq, constant(1), constant(1));
+        let never = binary(Lt, constant(2), constant(1));
+        assert_eq!(
+            simplifier.simplify(binary(And, always.clone(), p.clone())),
+            p
+        );
+        assert_eq!(
+            simplifier.simplify(binary(And, p.clone(), never.clone())),
+            constant(false)
+        );
+        assert_eq!(
+            simplifier.simplify(binary(Or, p.clone(), always.clone())),
+            constant(true)
+        );
+        assert_eq!(simplifier.simplify(binary(Or, never, p.clone())), p);
+
+        // failing exprs are kept to fail when run
+        let expr = binary(Divide, constant(1), constant(0));
+        assert_eq!(simplifier.simplify(expr.clone()), expr);
+    }
+
+    #[test]
+    fn test_simplify_filters() {
+        let scan: PlanRef = Arc::new(LogicalTableScan::new(
+            "t".to_string(),
+            vec![ColumnCatalog {
+                id: "c1".to_string(),
+                desc: ColumnDesc {
+                    name: "c1".to_string(),
+                    data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
+                },
+            }],
+        ));
+        let filter = |expr| -> PlanRef { Arc::new(LogicalFilter::new(expr, scan.clone())) };
+        let mut simplifier = ExprSimplifier::default();
+
+        let plan = simplifier.rewrite(filter(binary(BinaryOperator::Eq, constant(1), constant(1))));
+        assert_eq!(plan.node_type(), PlanNodeType::LogicalTableScan);
+
+        let plan = simplifier.rewrite(filter(binary(
+            BinaryOperator::And,
+            binary(BinaryOperator::Gt, column("c1"), constant(1)),
+            binary(BinaryOperator::Eq, constant(1), constant(2)),
+        )));
+        assert_eq!(plan.node_type(), PlanNodeType::LogicalValues);
+        let values = plan.as_logical_values().unwrap();
+        assert!(values.rows().is_empty());
+        assert_eq!(values.columns(), scan.schema());
+        assert!(plan.children().is_empty());
+    }
+
+    #[test]
+    fn test_simplify_is_null() {
+        let simplifier = ExprSimplifier::default();
+        let is_null = |expr, negated| {
+            BoundExpr::IsNull(BoundIsNull {
+                expr: Box::new(expr),
+                negated,
+            })
+        };
+        let nullable = column("c1");
+        let mut not_nullable = nullable.clone();
+        if let BoundExpr::ColumnRef(c) = &mut not_nullable {
+            c.column_catalog.desc.nullable = false;
+        }
+
+        let expr = is_null(nullable.clone(), true);
+        assert_eq!(simplifier.simplify(expr.clone()), expr);
+        assert_eq!(
+            simplifier.simplify(is_null(not_nullable.clone(), true)),
+            constant(true)
+        );
+        assert_eq!(
+            simplifier.simplify(is_null(not_nullable.clone(), false)),
+            constant(false)
+        );
+        assert_eq!(
+            simplifier.simplify(is_null(constant(ScalarValue::Int32(None)), false)),
+            constant(true)
+        );
+
+        // `x = x` keeps being null where `x` is null
+        let expr = binary(BinaryOperator::Eq, nullable.clone(), nullable.clone());
+        assert_eq!(simplifier.simplify(expr), is_null(nullable.clone(), true));
+        let c1_plus_1 = binary(BinaryOperator::Plus, nullable.clone(), constant(1));
+        let expr = binary(BinaryOperator::Eq, c1_plus_1.clone(), c1_plus_1.clone());
+        assert_eq!(simplifier.simplify(expr), is_null(c1_plus_1, true));
+        let expr = binary(
+            BinaryOperator::Eq,
+            not_nullable.clone(),
+            not_nullable.clone(),
+        );
+        assert_eq!(simplifier.simplify(expr), constant(true));
+
+        // the filter of IS NOT NULL on a column which is not nullable is removed
+        let scan: PlanRef = Arc::new(LogicalTableScan::new("t".to_string(), vec![]));
+        let filter = binary(
+            BinaryOperator::And,
+            is_null(not_nullable.clone(), true),
+            binary(BinaryOperator::Gt, nullable, constant(1)),
+        );
+        let plan = ExprSimplifier::default().rewrite(Arc::new(LogicalFilter::new(filter, scan)));
+        assert_eq!(
+            plan.as_logical_filter().unwrap().expr(),
+            binary(BinaryOperator::Gt, column("c1"), constant(1))
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     binder::expression::{
         agg_func::BoundAggFunc, binary_op::BoundBinaryOp, BoundColumnRef, BoundExpr, BoundInputRef,
-        BoundTypeCast,
+        BoundIsNull, BoundTypeCast,
     },
     types::ScalarValue,
 };
@@ -18,6 +18,7 @@ pub trait ExprVisitor {
             BoundExpr::BinaryOp(expr) => self.visit_binary_op(expr),
             BoundExpr::TypeCast(expr) => self.visit_type_cast(expr),
             BoundExpr::AggFunc(expr) => self.visit_agg_func(expr),
+            BoundExpr::IsNull(expr) => self.visit_is_null(expr),
         }
     }
 
@@ -34,9 +35,19 @@ pub trait ExprVisitor {
         self.visit_expr(&expr.expr);
     }
 
+    fn visit_is_null(&mut self, expr: &BoundIsNull) {
+        self.visit_expr(&expr.expr);
+    }
+
     fn visit_agg_func(&mut self, expr: &BoundAggFunc) {
         for arg in &expr.exprs {
             self.visit_expr(arg);
         }
+        if let Some(filter) = &expr.filter {
+            self.visit_expr(filter);
+        }
+        for order_by in &expr.order_by {
+            self.visit_expr(&order_by.expr);
+        }
     }
 }
@@ -1,8 +1,10 @@
 use crate::{
     binder::expression::{BoundColumnRef, BoundExpr, BoundInputRef},
     optimizer::{
-        expr_rewriter::ExprRewriter, logical_filter::LogicalFilter,
-        logical_project::LogicalProject, plan_rewriter::PlanRewriter, LogicalAgg,
+        expr_rewriter::ExprRewriter, logical_delete::LogicalDelete, logical_filter::LogicalFilter,
+        logical_project::LogicalProject, logical_table_function::LogicalTableFunction,
+        logical_update::LogicalUpdate, logical_values::LogicalValues, plan_rewriter::PlanRewriter,
+        LogicalAgg,
     },
 };
 use std::sync::Arc;
@@ -32,10 +34,17 @@ impl InputRefRewriter {
                 self.rewrite_expr(&mut e.right);
             }
             BoundExpr::TypeCast(e) => self.rewrite_expr(&mut e.expr),
+            BoundExpr::IsNull(e) => self.rewrite_expr(&mut e.expr),
             BoundExpr::AggFunc(e) => {
                 for arg in &mut e.exprs {
                     self.rewrite_expr(arg);
                 }
+                if let Some(filter) = &mut e.filter {
+                    self.rewrite_expr(filter);
+                }
+                for order_by in &mut e.order_by {
+                    self.rewrite_expr(&mut order_by.expr);
+                }
             }
             _ => unreachable!(
                 "unexpected expr type {:?} for InputRefRewriter, binding: {:?}",
@@ -61,6 +70,10 @@ impl ExprRewriter for InputRefRewriter {
     fn rewrite_agg_func(&self, expr: &mut BoundExpr) {
         self.rewrite_internal(expr);
     }
+
+    fn rewrite_is_null(&self, expr: &mut BoundExpr) {
+        self.rewrite_internal(expr);
+    }
 }
 
 impl PlanRewriter for InputRefRewriter {
@@ -68,6 +81,36 @@ impl PlanRewriter for InputRefRewriter {
         &mut self,
         plan: &super::logical_table_scan::LogicalTableScan,
     ) -> super::PlanRef {
+        self.bindings = plan
+            .columns()
+            .iter()
+            .map(|c| {
+                BoundExpr::ColumnRef(BoundColumnRef {
+                    column_catalog: c.clone(),
+                })
+            })
+            .collect();
+        let mut filter = plan.filter();
+        if let Some(expr) = &mut filter {
+            self.rewrite_expr(expr);
+        }
+        Arc::new(plan.clone().with_filter(filter))
+    }
+
+    fn rewrite_logical_values(&mut self, plan: &LogicalValues) -> super::PlanRef {
+        self.bindings = plan
+            .columns()
+            .iter()
+            .map(|c| {
+                BoundExpr::ColumnRef(BoundColumnRef {
+                    column_catalog: c.clone(),
+                })
+            })
+            .collect();
+        Arc::new(plan.clone())
+    }
+
+    fn rewrite_logical_table_function(&mut self, plan: &LogicalTableFunction) -> super::PlanRef {
         self.bindings = plan
             .columns()
             .iter()
@@ -108,15 +151,52 @@ impl PlanRewriter for InputRefRewriter {
         Arc::new(new_plan)
     }
 
+    fn rewrite_logical_update(&mut self, plan: &LogicalUpdate) -> super::PlanRef {
+        let new_child = self.rewrite(plan.input());
+        let mut new_assignments = plan.assignments();
+        for expr in &mut new_assignments {
+            self.rewrite_expr(expr);
+        }
+        let mut new_predicate = plan.predicate();
+        if let Some(expr) = &mut new_predicate {
+            self.rewrite_expr(expr);
+        }
+        let new_plan =
+            LogicalUpdate::new(plan.table_id(), new_assignments, new_predicate, new_child);
+        Arc::new(new_plan)
+    }
+
+    fn rewrite_logical_delete(&mut self, plan: &LogicalDelete) -> super::PlanRef {
+        let new_child = self.rewrite(plan.input());
+        let mut new_predicate = plan.predicate();
+        if let Some(expr) = &mut new_predicate {
+            self.rewrite_expr(expr);
+        }
+        let new_plan = LogicalDelete::new(plan.table_id(), new_predicate, new_child);
+        Arc::new(new_plan)
+    }
+
     fn rewrite_logical_agg(&mut self, plan: &super::LogicalAgg) -> super::PlanRef {
         let new_child = self.rewrite(plan.input());
-        let bindings = plan.agg_funcs();
+        // aggregation outputs group by columns first, followed by agg funcs
+        let bindings = [plan.group_by(), plan.agg_funcs()].concat();
+
         let mut new_exprs = plan.agg_funcs();
         for expr in &mut new_exprs {
             self.rewrite_expr(expr);
         }
+        let mut new_group_by = plan.group_by();
+        for expr in &mut new_group_by {
+            self.rewrite_expr(expr);
+        }
+
         self.bindings = bindings;
-        let new_plan = LogicalAgg::new(new_exprs, vec![], new_child);
+        let new_plan = LogicalAgg::new_with_grouping_sets(
+            new_exprs,
+            new_group_by,
+            plan.grouping_sets(),
+            new_child,
+        );
         Arc::new(new_plan)
     }
 }
@@ -149,6 +229,8 @@ mod input_ref_rewriter_test {
             desc: ColumnDesc {
                 name: column_name,
                 data_type: DataType::Int32,
+                nullable: true,
+                default: None,
             },
         }
     }
@@ -195,6 +277,8 @@ mod input_ref_rewriter_test {
                 column_catalog: build_test_column("c1".to_string()),
             })],
             return_type: DataType::Int32,
+            filter: None,
+            order_by: vec![],
         });
         let simple_agg = LogicalAgg::new(vec![expr.clone()], vec![], input);
         LogicalProject::new(vec![expr], Arc::new(simple_agg))
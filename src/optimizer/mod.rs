@@ -1,8 +1,13 @@
+pub mod common_subexpr_eliminator;
 pub mod expr_rewriter;
+pub mod expr_simplifier;
 pub mod input_ref_rewriter;
 pub mod physical_rewriter;
 pub mod plan_node;
 pub mod plan_rewriter;
+pub mod plan_validator;
 pub mod plan_visitor;
+#[cfg(test)]
+mod test_util;
 pub use plan_node::*;
 pub mod expr_visitor;
@@ -1,9 +1,19 @@
 use std::sync::Arc;
 
 use crate::optimizer::{
-    logical_filter::LogicalFilter, physical_filter::PhysicalFilter,
-    physical_project::PhysicalProject, physical_table::PhysicalTableScan,
-    plan_rewriter::PlanRewriter, PlanTreeNode,
+    logical_analyze::LogicalAnalyze, logical_copy_from_file::LogicalCopyFromFile,
+    logical_copy_to_file::LogicalCopyToFile, logical_create_table::LogicalCreateTable,
+    logical_delete::LogicalDelete, logical_drop_table::LogicalDropTable,
+    logical_filter::LogicalFilter, logical_insert::LogicalInsert,
+    logical_table_function::LogicalTableFunction, logical_update::LogicalUpdate,
+    logical_values::LogicalValues, physical_analyze::PhysicalAnalyze,
+    physical_copy_from_file::PhysicalCopyFromFile, physical_copy_to_file::PhysicalCopyToFile,
+    physical_create_table::PhysicalCreateTable, physical_delete::PhysicalDelete,
+    physical_drop_table::PhysicalDropTable, physical_filter::PhysicalFilter,
+    physical_insert::PhysicalInsert, physical_project::PhysicalProject,
+    physical_table::PhysicalTableScan, physical_table_function::PhysicalTableFunction,
+    physical_update::PhysicalUpdate, physical_values::PhysicalValues, plan_rewriter::PlanRewriter,
+    LogicalAgg, PhysicalHashAgg, PhysicalSimpleAgg, PlanTreeNode,
 };
 
 pub struct PhysicalRewriter {}
@@ -16,6 +26,74 @@ impl PlanRewriter for PhysicalRewriter {
         Arc::new(PhysicalTableScan::new(plan.clone()))
     }
 
+    fn rewrite_logical_values(&mut self, plan: &LogicalValues) -> super::PlanRef {
+        Arc::new(PhysicalValues::new(plan.clone()))
+    }
+
+    fn rewrite_logical_table_function(&mut self, plan: &LogicalTableFunction) -> super::PlanRef {
+        Arc::new(PhysicalTableFunction::new(plan.clone()))
+    }
+
+    fn rewrite_logical_create_table(&mut self, plan: &LogicalCreateTable) -> super::PlanRef {
+        let children = plan
+            .children()
+            .into_iter()
+            .map(|child| self.rewrite(child))
+            .collect();
+        let logical = plan.clone_with_children(children);
+        Arc::new(PhysicalCreateTable::new(
+            logical.as_logical_create_table().unwrap().clone(),
+        ))
+    }
+
+    fn rewrite_logical_insert(&mut self, plan: &LogicalInsert) -> super::PlanRef {
+        let child = self.rewrite(plan.children().first().unwrap().clone());
+        let logical = plan.clone_with_children([child].to_vec());
+        Arc::new(PhysicalInsert::new(
+            logical.as_logical_insert().unwrap().clone(),
+        ))
+    }
+
+    fn rewrite_logical_update(&mut self, plan: &LogicalUpdate) -> super::PlanRef {
+        let child = self.rewrite(plan.children().first().unwrap().clone());
+        let logical = plan.clone_with_children([child].to_vec());
+        Arc::new(PhysicalUpdate::new(
+            logical.as_logical_update().unwrap().clone(),
+        ))
+    }
+
+    fn rewrite_logical_delete(&mut self, plan: &LogicalDelete) -> super::PlanRef {
+        let child = self.rewrite(plan.children().first().unwrap().clone());
+        let logical = plan.clone_with_children([child].to_vec());
+        Arc::new(PhysicalDelete::new(
+            logical.as_logical_delete().unwrap().clone(),
+        ))
+    }
+
+    fn rewrite_logical_copy_to_file(&mut self, plan: &LogicalCopyToFile) -> super::PlanRef {
+        let child = self.rewrite(plan.children().first().unwrap().clone());
+        let logical = plan.clone_with_children([child].to_vec());
+        Arc::new(PhysicalCopyToFile::new(
+            logical.as_logical_copy_to_file().unwrap().clone(),
+        ))
+    }
+
+    fn rewrite_logical_analyze(&mut self, plan: &LogicalAnalyze) -> super::PlanRef {
+        let child = self.rewrite(plan.children().first().unwrap().clone());
+        let logical = plan.clone_with_children([child].to_vec());
+        Arc::new(PhysicalAnalyze::new(
+            logical.as_logical_analyze().unwrap().clone(),
+        ))
+    }
+
+    fn rewrite_logical_copy_from_file(&mut self, plan: &LogicalCopyFromFile) -> super::PlanRef {
+        Arc::new(PhysicalCopyFromFile::new(plan.clone()))
+    }
+
+    fn rewrite_logical_drop_table(&mut self, plan: &LogicalDropTable) -> super::PlanRef {
+        Arc::new(PhysicalDropTable::new(plan.clone()))
+    }
+
     fn rewrite_logical_project(
         &mut self,
         plan: &super::logical_project::LogicalProject,
@@ -34,6 +112,17 @@ impl PlanRewriter for PhysicalRewriter {
             logical.as_logical_filter().unwrap().clone(),
         ))
     }
+
+    fn rewrite_logical_agg(&mut self, plan: &LogicalAgg) -> super::PlanRef {
+        let child = self.rewrite(plan.children().first().unwrap().clone());
+        let logical = plan.clone_with_children([child].to_vec());
+        let logical = logical.as_logical_agg().unwrap().clone();
+        if logical.group_by().is_empty() && logical.grouping_sets().is_empty() {
+            Arc::new(PhysicalSimpleAgg::new(logical))
+        } else {
+            Arc::new(PhysicalHashAgg::new(logical))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -56,6 +145,8 @@ mod physical_rewriter_test {
             desc: ColumnDesc {
                 name: column_name,
                 data_type: Int32,
+                nullable: true,
+                default: None,
             },
         }
     }
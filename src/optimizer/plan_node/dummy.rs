@@ -5,7 +5,11 @@ use crate::optimizer::{PlanNode, PlanRef, PlanTreeNode};
 #[derive(Debug, Clone)]
 pub struct Dummy {}
 
-impl PlanNode for Dummy {}
+impl PlanNode for Dummy {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        vec![]
+    }
+}
 
 impl PlanTreeNode for Dummy {
     fn children(&self) -> Vec<PlanRef> {
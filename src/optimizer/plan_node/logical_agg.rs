@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use crate::{
     binder::expression::BoundExpr,
@@ -9,14 +9,28 @@ use crate::{
 pub struct LogicalAgg {
     agg_funcs: Vec<BoundExpr>,
     group_by: Vec<BoundExpr>,
+    /// Grouping sets as indices into `group_by`, empty for a plain group by.
+    /// Each set is aggregated under its own grouping id, whose bits mark the
+    /// `group_by` exprs that are not part of the set.
+    grouping_sets: Vec<Vec<usize>>,
     input: PlanRef,
 }
 
 impl LogicalAgg {
     pub fn new(agg_funcs: Vec<BoundExpr>, group_by: Vec<BoundExpr>, input: PlanRef) -> Self {
+        Self::new_with_grouping_sets(agg_funcs, group_by, vec![], input)
+    }
+
+    pub fn new_with_grouping_sets(
+        agg_funcs: Vec<BoundExpr>,
+        group_by: Vec<BoundExpr>,
+        grouping_sets: Vec<Vec<usize>>,
+        input: PlanRef,
+    ) -> Self {
         Self {
             agg_funcs,
             group_by,
+            grouping_sets,
             input,
         }
     }
@@ -29,14 +43,27 @@ impl LogicalAgg {
         self.group_by.clone()
     }
 
+    pub fn grouping_sets(&self) -> Vec<Vec<usize>> {
+        self.grouping_sets.clone()
+    }
+
     pub fn input(&self) -> PlanRef {
         self.input.clone()
     }
 }
 
 impl PlanNode for LogicalAgg {
+    /// the group by columns followed by the aggregations
     fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
-        self.input.schema()
+        let input = self.input.schema();
+        let group_by = self.group_by.iter().map(|e| {
+            let mut column = e.output_column(&input);
+            // the group by exprs left out of a grouping set are null in its rows
+            column.desc.nullable |= !self.grouping_sets.is_empty();
+            column
+        });
+        let agg_funcs = self.agg_funcs.iter().map(|e| e.output_column(&input));
+        group_by.chain(agg_funcs).collect()
     }
 }
 
@@ -48,10 +75,21 @@ impl PlanTreeNode for LogicalAgg {
     fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
         assert_eq!(children.len(), 1);
 
-        Arc::new(Self::new(
+        Arc::new(Self::new_with_grouping_sets(
             self.agg_funcs.clone(),
             self.group_by.clone(),
+            self.grouping_sets.clone(),
             children[0].clone(),
         ))
     }
 }
+
+impl fmt::Display for LogicalAgg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalAgg: agg_funcs {:?} group_by {:?} grouping_sets {:?}",
+            self.agg_funcs, self.group_by, self.grouping_sets
+        )
+    }
+}
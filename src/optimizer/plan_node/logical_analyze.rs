@@ -0,0 +1,71 @@
+use std::{fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::{
+    catalog::{ColumnCatalog, TableId},
+    optimizer::{PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Collect the statistics of the columns of the table from the rows of the input, which
+/// scans the columns in the same order.
+#[derive(Debug, Clone)]
+pub struct LogicalAnalyze {
+    table_id: TableId,
+    columns: Vec<ColumnCatalog>,
+    input: PlanRef,
+}
+
+impl LogicalAnalyze {
+    pub fn new(table_id: TableId, columns: Vec<ColumnCatalog>, input: PlanRef) -> Self {
+        Self {
+            table_id,
+            columns,
+            input,
+        }
+    }
+
+    pub fn table_id(&self) -> TableId {
+        self.table_id.clone()
+    }
+
+    pub fn columns(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+
+    pub fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+}
+
+impl PlanNode for LogicalAnalyze {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        vec![]
+    }
+}
+
+impl PlanTreeNode for LogicalAnalyze {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![self.input.clone()]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert_eq!(children.len(), 1);
+        Arc::new(Self::new(
+            self.table_id.clone(),
+            self.columns.clone(),
+            children[0].clone(),
+        ))
+    }
+}
+
+impl fmt::Display for LogicalAnalyze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalAnalyze: table: #{}, columns: [{}]",
+            self.table_id,
+            self.columns.iter().map(|c| c.id.clone()).join(", ")
+        )
+    }
+}
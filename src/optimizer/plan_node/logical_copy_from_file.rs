@@ -0,0 +1,73 @@
+use std::{fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::{
+    binder::statement::create_table::ExternalTable,
+    catalog::{ColumnCatalog, TableCatalog},
+    optimizer::{affected_rows_schema, PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Append the rows of the file to the table. The file has the given columns of the table,
+/// and the other columns are null.
+#[derive(Debug, Clone)]
+pub struct LogicalCopyFromFile {
+    table_catalog: TableCatalog,
+    columns: Vec<ColumnCatalog>,
+    file: ExternalTable,
+}
+
+impl LogicalCopyFromFile {
+    pub fn new(
+        table_catalog: TableCatalog,
+        columns: Vec<ColumnCatalog>,
+        file: ExternalTable,
+    ) -> Self {
+        Self {
+            table_catalog,
+            columns,
+            file,
+        }
+    }
+
+    pub fn table_catalog(&self) -> TableCatalog {
+        self.table_catalog.clone()
+    }
+
+    pub fn columns(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+
+    pub fn file(&self) -> ExternalTable {
+        self.file.clone()
+    }
+}
+
+impl PlanNode for LogicalCopyFromFile {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        affected_rows_schema()
+    }
+}
+
+impl PlanTreeNode for LogicalCopyFromFile {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert!(children.is_empty());
+        Arc::new(self.clone())
+    }
+}
+
+impl fmt::Display for LogicalCopyFromFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalCopyFromFile: table: #{}, columns: [{}], location: {}",
+            self.table_catalog.id,
+            self.columns.iter().map(|c| c.id.clone()).join(", "),
+            self.file.location
+        )
+    }
+}
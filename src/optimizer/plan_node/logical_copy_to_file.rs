@@ -0,0 +1,72 @@
+use std::{fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::{
+    binder::statement::create_table::ExternalTable,
+    catalog::ColumnCatalog,
+    optimizer::{affected_rows_schema, PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Write the rows of the input into the file, whose columns are the output columns of
+/// the input.
+#[derive(Debug, Clone)]
+pub struct LogicalCopyToFile {
+    file: ExternalTable,
+    columns: Vec<ColumnCatalog>,
+    input: PlanRef,
+}
+
+impl LogicalCopyToFile {
+    pub fn new(file: ExternalTable, columns: Vec<ColumnCatalog>, input: PlanRef) -> Self {
+        Self {
+            file,
+            columns,
+            input,
+        }
+    }
+
+    pub fn file(&self) -> ExternalTable {
+        self.file.clone()
+    }
+
+    pub fn columns(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+
+    pub fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+}
+
+impl PlanNode for LogicalCopyToFile {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        affected_rows_schema()
+    }
+}
+
+impl PlanTreeNode for LogicalCopyToFile {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![self.input.clone()]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert_eq!(children.len(), 1);
+        Arc::new(Self::new(
+            self.file.clone(),
+            self.columns.clone(),
+            children[0].clone(),
+        ))
+    }
+}
+
+impl fmt::Display for LogicalCopyToFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalCopyToFile: location: {}, columns: [{}]",
+            self.file.location,
+            self.columns.iter().map(|c| c.id.clone()).join(", ")
+        )
+    }
+}
@@ -0,0 +1,94 @@
+use std::{fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::{
+    binder::statement::create_table::ExternalTable,
+    catalog::{ColumnCatalog, TableId},
+    optimizer::{PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Create a table, filled by the rows of the input for `CREATE TABLE ... AS SELECT`, or
+/// by the rows of the file for `CREATE EXTERNAL TABLE`.
+#[derive(Debug, Clone)]
+pub struct LogicalCreateTable {
+    table_id: TableId,
+    columns: Vec<ColumnCatalog>,
+    input: Option<PlanRef>,
+    external: Option<ExternalTable>,
+}
+
+impl LogicalCreateTable {
+    pub fn new(table_id: TableId, columns: Vec<ColumnCatalog>, input: Option<PlanRef>) -> Self {
+        Self {
+            table_id,
+            columns,
+            input,
+            external: None,
+        }
+    }
+
+    pub fn new_external(
+        table_id: TableId,
+        columns: Vec<ColumnCatalog>,
+        external: ExternalTable,
+    ) -> Self {
+        Self {
+            table_id,
+            columns,
+            input: None,
+            external: Some(external),
+        }
+    }
+
+    pub fn table_id(&self) -> TableId {
+        self.table_id.clone()
+    }
+
+    pub fn columns(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+
+    pub fn input(&self) -> Option<PlanRef> {
+        self.input.clone()
+    }
+
+    pub fn external(&self) -> Option<ExternalTable> {
+        self.external.clone()
+    }
+}
+
+impl PlanNode for LogicalCreateTable {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        vec![]
+    }
+}
+
+impl PlanTreeNode for LogicalCreateTable {
+    fn children(&self) -> Vec<PlanRef> {
+        self.input.iter().cloned().collect()
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert!(children.len() <= 1);
+        Arc::new(Self {
+            input: children.first().cloned(),
+            ..self.clone()
+        })
+    }
+}
+
+impl fmt::Display for LogicalCreateTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LogicalCreateTable: table: #{}, columns: [{}]",
+            self.table_id,
+            self.columns.iter().map(|c| format!("{c:?}")).join(", ")
+        )?;
+        if let Some(external) = &self.external {
+            write!(f, ", location: {}", external.location)?;
+        }
+        writeln!(f)
+    }
+}
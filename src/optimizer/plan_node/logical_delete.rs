@@ -0,0 +1,69 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    binder::expression::BoundExpr,
+    catalog::TableId,
+    optimizer::{affected_rows_schema, PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Delete the rows of the input, a scan over the whole table, that the predicate evaluates
+/// to true, or all rows without predicate.
+#[derive(Debug, Clone)]
+pub struct LogicalDelete {
+    table_id: TableId,
+    predicate: Option<BoundExpr>,
+    input: PlanRef,
+}
+
+impl LogicalDelete {
+    pub fn new(table_id: TableId, predicate: Option<BoundExpr>, input: PlanRef) -> Self {
+        Self {
+            table_id,
+            predicate,
+            input,
+        }
+    }
+
+    pub fn table_id(&self) -> TableId {
+        self.table_id.clone()
+    }
+
+    pub fn predicate(&self) -> Option<BoundExpr> {
+        self.predicate.clone()
+    }
+
+    pub fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+}
+
+impl PlanNode for LogicalDelete {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        affected_rows_schema()
+    }
+}
+
+impl PlanTreeNode for LogicalDelete {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![self.input.clone()]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert_eq!(children.len(), 1);
+        Arc::new(Self::new(
+            self.table_id.clone(),
+            self.predicate.clone(),
+            children[0].clone(),
+        ))
+    }
+}
+
+impl fmt::Display for LogicalDelete {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalDelete: table: #{}, predicate: {:?}",
+            self.table_id, self.predicate
+        )
+    }
+}
@@ -0,0 +1,56 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    catalog::TableId,
+    optimizer::{PlanNode, PlanRef, PlanTreeNode},
+};
+
+#[derive(Debug, Clone)]
+pub struct LogicalDropTable {
+    table_id: TableId,
+    if_exists: bool,
+}
+
+impl LogicalDropTable {
+    pub fn new(table_id: TableId, if_exists: bool) -> Self {
+        Self {
+            table_id,
+            if_exists,
+        }
+    }
+
+    pub fn table_id(&self) -> TableId {
+        self.table_id.clone()
+    }
+
+    pub fn if_exists(&self) -> bool {
+        self.if_exists
+    }
+}
+
+impl PlanNode for LogicalDropTable {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        vec![]
+    }
+}
+
+impl PlanTreeNode for LogicalDropTable {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert!(children.is_empty());
+        Arc::new(self.clone())
+    }
+}
+
+impl fmt::Display for LogicalDropTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalDropTable: table: #{}, if_exists: {}",
+            self.table_id, self.if_exists
+        )
+    }
+}
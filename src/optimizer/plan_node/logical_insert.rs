@@ -0,0 +1,70 @@
+use std::{fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::{
+    catalog::{ColumnCatalog, TableId},
+    optimizer::{affected_rows_schema, PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Append the rows of the input, whose columns are in the order of the table columns.
+#[derive(Debug, Clone)]
+pub struct LogicalInsert {
+    table_id: TableId,
+    columns: Vec<ColumnCatalog>,
+    input: PlanRef,
+}
+
+impl LogicalInsert {
+    pub fn new(table_id: TableId, columns: Vec<ColumnCatalog>, input: PlanRef) -> Self {
+        Self {
+            table_id,
+            columns,
+            input,
+        }
+    }
+
+    pub fn table_id(&self) -> TableId {
+        self.table_id.clone()
+    }
+
+    pub fn columns(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+
+    pub fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+}
+
+impl PlanNode for LogicalInsert {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        affected_rows_schema()
+    }
+}
+
+impl PlanTreeNode for LogicalInsert {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![self.input.clone()]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert_eq!(children.len(), 1);
+        Arc::new(Self::new(
+            self.table_id.clone(),
+            self.columns.clone(),
+            children[0].clone(),
+        ))
+    }
+}
+
+impl fmt::Display for LogicalInsert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalInsert: table: #{}, columns: [{}]",
+            self.table_id,
+            self.columns.iter().map(|c| c.id.clone()).join(", ")
+        )
+    }
+}
@@ -30,7 +30,8 @@ impl LogicalProject {
 
 impl PlanNode for LogicalProject {
     fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
-        self.input.schema()
+        let input = self.input.schema();
+        self.exprs.iter().map(|e| e.output_column(&input)).collect()
     }
 }
 
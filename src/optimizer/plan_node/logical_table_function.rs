@@ -0,0 +1,67 @@
+use std::{fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::{
+    catalog::ColumnCatalog,
+    function::FunctionTable,
+    optimizer::{PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// The rows of the table returned by a table function in FROM.
+#[derive(Debug, Clone)]
+pub struct LogicalTableFunction {
+    name: String,
+    columns: Vec<ColumnCatalog>,
+    function: FunctionTable,
+}
+
+impl LogicalTableFunction {
+    pub fn new(name: String, columns: Vec<ColumnCatalog>, function: FunctionTable) -> Self {
+        Self {
+            name,
+            columns,
+            function,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn columns(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+
+    pub fn function(&self) -> FunctionTable {
+        self.function.clone()
+    }
+}
+
+impl PlanNode for LogicalTableFunction {
+    fn schema(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+}
+
+impl PlanTreeNode for LogicalTableFunction {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert!(children.is_empty());
+        Arc::new(self.clone())
+    }
+}
+
+impl fmt::Display for LogicalTableFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalTableFunction: {}, columns: [{}]",
+            self.name,
+            self.columns.iter().map(|c| c.id.clone()).join(", ")
+        )
+    }
+}
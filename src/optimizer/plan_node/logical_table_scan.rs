@@ -3,19 +3,39 @@ use std::{fmt, sync::Arc};
 use itertools::Itertools;
 
 use crate::{
-    catalog::{ColumnCatalog, TableId},
-    optimizer::{plan_node::PlanNode, PlanTreeNode},
+    binder::expression::{BoundColumnRef, BoundExpr},
+    catalog::{ColumnCatalog, TableId, TableStatistics},
+    optimizer::{expr_rewriter::ExprRewriter, plan_node::PlanNode, PlanTreeNode},
 };
 
 #[derive(Debug, Clone)]
 pub struct LogicalTableScan {
     table_id: TableId,
     columns: Vec<ColumnCatalog>,
+    /// The filter on the scanned rows, used to skip the partitions of the table it rejects.
+    /// The rows scanned are still filtered afterwards.
+    filter: Option<BoundExpr>,
+    /// the statistics of the table collected by `ANALYZE`, used to estimate the number of
+    /// rows scanned
+    statistics: Option<Arc<TableStatistics>>,
 }
 
 impl LogicalTableScan {
     pub fn new(table_id: TableId, columns: Vec<ColumnCatalog>) -> Self {
-        Self { table_id, columns }
+        Self {
+            table_id,
+            columns,
+            filter: None,
+            statistics: None,
+        }
+    }
+
+    pub fn with_filter(self, filter: Option<BoundExpr>) -> Self {
+        Self { filter, ..self }
+    }
+
+    pub fn with_statistics(self, statistics: Option<Arc<TableStatistics>>) -> Self {
+        Self { statistics, ..self }
     }
 
     pub fn table_id(&self) -> TableId {
@@ -29,6 +49,45 @@ impl LogicalTableScan {
     pub fn columns(&self) -> Vec<ColumnCatalog> {
         self.columns.clone()
     }
+
+    pub fn filter(&self) -> Option<BoundExpr> {
+        self.filter.clone()
+    }
+
+    pub fn statistics(&self) -> Option<Arc<TableStatistics>> {
+        self.statistics.clone()
+    }
+
+    /// The estimated number of rows passing the filter, which is only known once the
+    /// table is analyzed.
+    pub fn estimated_rows(&self) -> Option<usize> {
+        let statistics = self.statistics.as_ref()?;
+        let mut filter = self.filter.clone();
+        if let Some(expr) = &mut filter {
+            ColumnRefResolver(&self.columns).rewrite_expr(expr);
+        }
+        Some(statistics.estimated_rows(filter.as_ref()))
+    }
+}
+
+/// Turns the input refs of the filter back into the scanned columns they refer to, as
+/// the statistics are looked up by column.
+struct ColumnRefResolver<'a>(&'a [ColumnCatalog]);
+
+impl ExprRewriter for ColumnRefResolver<'_> {
+    fn rewrite_input_ref(&self, expr: &mut BoundExpr) {
+        if let BoundExpr::InputRef(input_ref) = expr {
+            *expr = BoundExpr::ColumnRef(BoundColumnRef {
+                column_catalog: self.0[input_ref.index].clone(),
+            });
+        }
+    }
+
+    fn rewrite_type_cast(&self, expr: &mut BoundExpr) {
+        if let BoundExpr::TypeCast(cast) = expr {
+            self.rewrite_expr(&mut cast.expr);
+        }
+    }
 }
 
 impl PlanNode for LogicalTableScan {
@@ -50,11 +109,15 @@ impl PlanTreeNode for LogicalTableScan {
 
 impl fmt::Display for LogicalTableScan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(
+        write!(
             f,
             "LogicalTableScan: table: #{}, columns: [{}]",
             self.table_id,
             self.columns.iter().map(|c| c.id.clone()).join(", ")
-        )
+        )?;
+        if let Some(rows) = self.estimated_rows() {
+            write!(f, ", rows: {rows}")?;
+        }
+        writeln!(f)
     }
 }
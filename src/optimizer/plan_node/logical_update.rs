@@ -0,0 +1,82 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    binder::expression::BoundExpr,
+    catalog::TableId,
+    optimizer::{affected_rows_schema, PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Update the rows of the input, a scan over the whole table, that the predicate evaluates
+/// to true, or all rows without predicate. `assignments` holds the new value of every
+/// table column in order.
+#[derive(Debug, Clone)]
+pub struct LogicalUpdate {
+    table_id: TableId,
+    assignments: Vec<BoundExpr>,
+    predicate: Option<BoundExpr>,
+    input: PlanRef,
+}
+
+impl LogicalUpdate {
+    pub fn new(
+        table_id: TableId,
+        assignments: Vec<BoundExpr>,
+        predicate: Option<BoundExpr>,
+        input: PlanRef,
+    ) -> Self {
+        Self {
+            table_id,
+            assignments,
+            predicate,
+            input,
+        }
+    }
+
+    pub fn table_id(&self) -> TableId {
+        self.table_id.clone()
+    }
+
+    pub fn assignments(&self) -> Vec<BoundExpr> {
+        self.assignments.clone()
+    }
+
+    pub fn predicate(&self) -> Option<BoundExpr> {
+        self.predicate.clone()
+    }
+
+    pub fn input(&self) -> PlanRef {
+        self.input.clone()
+    }
+}
+
+impl PlanNode for LogicalUpdate {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        affected_rows_schema()
+    }
+}
+
+impl PlanTreeNode for LogicalUpdate {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![self.input.clone()]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert_eq!(children.len(), 1);
+        Arc::new(Self::new(
+            self.table_id.clone(),
+            self.assignments.clone(),
+            self.predicate.clone(),
+            children[0].clone(),
+        ))
+    }
+}
+
+impl fmt::Display for LogicalUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalUpdate: table: #{}, assignments: {:?}, predicate: {:?}",
+            self.table_id, self.assignments, self.predicate
+        )
+    }
+}
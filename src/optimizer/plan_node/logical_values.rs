@@ -0,0 +1,59 @@
+use std::{fmt, sync::Arc};
+
+use itertools::Itertools;
+
+use crate::{
+    binder::expression::BoundExpr,
+    catalog::ColumnCatalog,
+    optimizer::{PlanNode, PlanRef, PlanTreeNode},
+};
+
+/// Constant rows, from a `VALUES` list or a single row without any column for
+/// a select without FROM.
+#[derive(Debug, Clone)]
+pub struct LogicalValues {
+    columns: Vec<ColumnCatalog>,
+    rows: Vec<Vec<BoundExpr>>,
+}
+
+impl LogicalValues {
+    pub fn new(columns: Vec<ColumnCatalog>, rows: Vec<Vec<BoundExpr>>) -> Self {
+        Self { columns, rows }
+    }
+
+    pub fn columns(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+
+    pub fn rows(&self) -> Vec<Vec<BoundExpr>> {
+        self.rows.clone()
+    }
+}
+
+impl PlanNode for LogicalValues {
+    fn schema(&self) -> Vec<ColumnCatalog> {
+        self.columns.clone()
+    }
+}
+
+impl PlanTreeNode for LogicalValues {
+    fn children(&self) -> Vec<PlanRef> {
+        vec![]
+    }
+
+    fn clone_with_children(&self, children: Vec<PlanRef>) -> PlanRef {
+        assert!(children.is_empty());
+        Arc::new(self.clone())
+    }
+}
+
+impl fmt::Display for LogicalValues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "LogicalValues: columns: [{}], rows: {:?}",
+            self.columns.iter().map(|c| c.id.clone()).join(", "),
+            self.rows
+        )
+    }
+}
@@ -1,41 +1,97 @@
 pub mod dummy;
 pub mod logical_agg;
+pub mod logical_analyze;
+pub mod logical_copy_from_file;
+pub mod logical_copy_to_file;
+pub mod logical_create_table;
+pub mod logical_delete;
+pub mod logical_drop_table;
 pub mod logical_filter;
+pub mod logical_insert;
 pub mod logical_project;
+pub mod logical_table_function;
 pub mod logical_table_scan;
+pub mod logical_update;
+pub mod logical_values;
+pub mod physical_analyze;
+pub mod physical_copy_from_file;
+pub mod physical_copy_to_file;
+pub mod physical_create_table;
+pub mod physical_delete;
+pub mod physical_drop_table;
 pub mod physical_filter;
+pub mod physical_hash_agg;
+pub mod physical_insert;
 pub mod physical_project;
 pub mod physical_simple_agg;
 pub mod physical_table;
+pub mod physical_table_function;
+pub mod physical_update;
+pub mod physical_values;
 pub mod plan_node_traits;
 pub use crate::optimizer::logical_agg::*;
 use crate::optimizer::physical_filter::PhysicalFilter;
+pub use crate::optimizer::physical_hash_agg::*;
 use crate::optimizer::physical_project::PhysicalProject;
 pub use crate::optimizer::physical_simple_agg::*;
 use crate::optimizer::physical_table::PhysicalTableScan;
 use crate::optimizer::plan_node::dummy::Dummy;
+use crate::optimizer::plan_node::logical_analyze::LogicalAnalyze;
+use crate::optimizer::plan_node::logical_copy_from_file::LogicalCopyFromFile;
+use crate::optimizer::plan_node::logical_copy_to_file::LogicalCopyToFile;
+use crate::optimizer::plan_node::logical_create_table::LogicalCreateTable;
+use crate::optimizer::plan_node::logical_delete::LogicalDelete;
+use crate::optimizer::plan_node::logical_drop_table::LogicalDropTable;
 use crate::optimizer::plan_node::logical_filter::LogicalFilter;
+use crate::optimizer::plan_node::logical_insert::LogicalInsert;
 use crate::optimizer::plan_node::logical_project::LogicalProject;
+use crate::optimizer::plan_node::logical_table_function::LogicalTableFunction;
 use crate::optimizer::plan_node::logical_table_scan::LogicalTableScan;
+use crate::optimizer::plan_node::logical_update::LogicalUpdate;
+use crate::optimizer::plan_node::logical_values::LogicalValues;
+use crate::optimizer::plan_node::physical_analyze::PhysicalAnalyze;
+use crate::optimizer::plan_node::physical_copy_from_file::PhysicalCopyFromFile;
+use crate::optimizer::plan_node::physical_copy_to_file::PhysicalCopyToFile;
+use crate::optimizer::plan_node::physical_create_table::PhysicalCreateTable;
+use crate::optimizer::plan_node::physical_delete::PhysicalDelete;
+use crate::optimizer::plan_node::physical_drop_table::PhysicalDropTable;
+use crate::optimizer::plan_node::physical_insert::PhysicalInsert;
+use crate::optimizer::plan_node::physical_table_function::PhysicalTableFunction;
+use crate::optimizer::plan_node::physical_update::PhysicalUpdate;
+use crate::optimizer::plan_node::physical_values::PhysicalValues;
 use paste::paste;
 use std::fmt::Debug;
 
 use std::sync::Arc;
 
-use crate::catalog::ColumnCatalog;
+use crate::catalog::{ColumnCatalog, ColumnDesc};
+use arrow::datatypes::DataType;
 use downcast_rs::{impl_downcast, Downcast};
 pub use plan_node_traits::*;
 
 /// The common trait over all plan nodes. Used by optimizer framework which will treat all node as `dyn PlanNode`.
 /// Meanwhile, we split the trait into lots of sub-traits so that we can easily use macro to impl them.
 pub trait PlanNode: WithPlanNodeType + PlanTreeNode + Debug + Downcast + Send + Sync {
-    fn schema(&self) -> Vec<ColumnCatalog> {
-        vec![]
-    }
+    /// the columns output by the node, in order
+    fn schema(&self) -> Vec<ColumnCatalog>;
 }
 
 impl_downcast!(PlanNode);
 
+/// The schema of the statements outputting the number of rows they affect, like insert,
+/// update and delete.
+pub fn affected_rows_schema() -> Vec<ColumnCatalog> {
+    vec![ColumnCatalog {
+        id: "rows".to_string(),
+        desc: ColumnDesc {
+            name: "rows".to_string(),
+            data_type: DataType::Int64,
+            nullable: false,
+            default: None,
+        },
+    }]
+}
+
 /// The type of reference to a plan node.
 pub type PlanRef = Arc<dyn PlanNode>;
 
@@ -52,10 +108,31 @@ macro_rules! for_all_plan_nodes {
             LogicalProject,
             LogicalFilter,
             LogicalAgg,
+            LogicalValues,
+            LogicalCreateTable,
+            LogicalInsert,
+            LogicalDropTable,
+            LogicalUpdate,
+            LogicalDelete,
+            LogicalCopyToFile,
+            LogicalCopyFromFile,
+            LogicalTableFunction,
+            LogicalAnalyze,
             PhysicalFilter,
             PhysicalTableScan,
             PhysicalProject,
-            PhysicalSimpleAgg
+            PhysicalSimpleAgg,
+            PhysicalHashAgg,
+            PhysicalValues,
+            PhysicalCreateTable,
+            PhysicalInsert,
+            PhysicalDropTable,
+            PhysicalUpdate,
+            PhysicalDelete,
+            PhysicalCopyToFile,
+            PhysicalCopyFromFile,
+            PhysicalTableFunction,
+            PhysicalAnalyze
         }
     };
 }
@@ -65,6 +142,7 @@ macro_rules! impl_downcast_utility {
         impl dyn PlanNode {
             $(
                 paste! {
+                    #[allow(clippy::result_unit_err)]
                     pub fn [<as_$node_name:snake>] (&self) -> std::result::Result<&$node_name, ()> {
                         self.downcast_ref::<$node_name>().ok_or(())
                     }
@@ -0,0 +1,51 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::optimizer::{logical_analyze::LogicalAnalyze, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalAnalyze {
+    logical: LogicalAnalyze,
+}
+
+impl PhysicalAnalyze {
+    pub fn new(logical: LogicalAnalyze) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalAnalyze {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalAnalyze {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalAnalyze {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalAnalyze {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalAnalyze: table: #{}, columns: [{}]",
+            self.logical.table_id(),
+            self.logical
+                .columns()
+                .iter()
+                .map(|c| c.id.clone())
+                .join(", ")
+        )
+    }
+}
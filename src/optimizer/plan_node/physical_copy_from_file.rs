@@ -0,0 +1,52 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::optimizer::{logical_copy_from_file::LogicalCopyFromFile, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalCopyFromFile {
+    logical: LogicalCopyFromFile,
+}
+
+impl PhysicalCopyFromFile {
+    pub fn new(logical: LogicalCopyFromFile) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalCopyFromFile {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalCopyFromFile {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalCopyFromFile {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalCopyFromFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalCopyFromFile: table: #{}, columns: [{}], location: {}",
+            self.logical.table_catalog().id,
+            self.logical
+                .columns()
+                .iter()
+                .map(|c| c.id.clone())
+                .join(", "),
+            self.logical.file().location
+        )
+    }
+}
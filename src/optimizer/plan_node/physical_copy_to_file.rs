@@ -0,0 +1,51 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::optimizer::{logical_copy_to_file::LogicalCopyToFile, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalCopyToFile {
+    logical: LogicalCopyToFile,
+}
+
+impl PhysicalCopyToFile {
+    pub fn new(logical: LogicalCopyToFile) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalCopyToFile {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalCopyToFile {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalCopyToFile {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalCopyToFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalCopyToFile: location: {}, columns: [{}]",
+            self.logical.file().location,
+            self.logical
+                .columns()
+                .iter()
+                .map(|c| c.id.clone())
+                .join(", ")
+        )
+    }
+}
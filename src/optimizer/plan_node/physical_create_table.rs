@@ -0,0 +1,51 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::optimizer::{logical_create_table::LogicalCreateTable, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalCreateTable {
+    logical: LogicalCreateTable,
+}
+
+impl PhysicalCreateTable {
+    pub fn new(logical: LogicalCreateTable) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalCreateTable {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalCreateTable {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalCreateTable {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalCreateTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalCreateTable: table: #{}, columns: [{}]",
+            self.logical.table_id(),
+            self.logical
+                .columns()
+                .iter()
+                .map(|c| format!("{c:?}"))
+                .join(", ")
+        )
+    }
+}
@@ -0,0 +1,45 @@
+use std::fmt;
+
+use crate::optimizer::{logical_delete::LogicalDelete, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalDelete {
+    logical: LogicalDelete,
+}
+
+impl PhysicalDelete {
+    pub fn new(logical: LogicalDelete) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalDelete {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalDelete {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalDelete {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalDelete {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalDelete: table: #{}, predicate: {:?}",
+            self.logical.table_id(),
+            self.logical.predicate()
+        )
+    }
+}
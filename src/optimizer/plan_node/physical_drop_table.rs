@@ -0,0 +1,45 @@
+use std::fmt;
+
+use crate::optimizer::{logical_drop_table::LogicalDropTable, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalDropTable {
+    logical: LogicalDropTable,
+}
+
+impl PhysicalDropTable {
+    pub fn new(logical: LogicalDropTable) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalDropTable {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalDropTable {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalDropTable {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalDropTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalDropTable: table: #{}, if_exists: {}",
+            self.logical.table_id(),
+            self.logical.if_exists()
+        )
+    }
+}
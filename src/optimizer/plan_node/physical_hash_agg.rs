@@ -0,0 +1,46 @@
+use std::fmt;
+
+use crate::optimizer::{logical_agg::LogicalAgg, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalHashAgg {
+    logical: LogicalAgg,
+}
+
+impl PhysicalHashAgg {
+    pub fn new(logical: LogicalAgg) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalAgg {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalHashAgg {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical().schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalHashAgg {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical().children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical().clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalHashAgg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalHashAgg: agg_funcs {:?} group_by {:?} grouping_sets {:?}",
+            self.logical().agg_funcs(),
+            self.logical().group_by(),
+            self.logical().grouping_sets()
+        )
+    }
+}
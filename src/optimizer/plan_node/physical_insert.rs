@@ -0,0 +1,51 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::optimizer::{logical_insert::LogicalInsert, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalInsert {
+    logical: LogicalInsert,
+}
+
+impl PhysicalInsert {
+    pub fn new(logical: LogicalInsert) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalInsert {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalInsert {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalInsert {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalInsert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalInsert: table: #{}, columns: [{}]",
+            self.logical.table_id(),
+            self.logical
+                .columns()
+                .iter()
+                .map(|c| c.id.clone())
+                .join(", ")
+        )
+    }
+}
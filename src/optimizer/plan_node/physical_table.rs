@@ -35,11 +35,15 @@ impl PlanTreeNode for PhysicalTableScan {
 
 impl fmt::Display for PhysicalTableScan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(
+        write!(
             f,
             "PhysicalTableScan: table: #{}, columns: [{}]",
             self.logical.table_id(),
             self.logical.column_ids().join(", ")
-        )
+        )?;
+        if let Some(rows) = self.logical.estimated_rows() {
+            write!(f, ", rows: {rows}")?;
+        }
+        writeln!(f)
     }
 }
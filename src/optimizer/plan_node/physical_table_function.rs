@@ -0,0 +1,51 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::optimizer::{logical_table_function::LogicalTableFunction, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalTableFunction {
+    logical: LogicalTableFunction,
+}
+
+impl PhysicalTableFunction {
+    pub fn new(logical: LogicalTableFunction) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalTableFunction {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalTableFunction {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalTableFunction {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalTableFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalTableFunction: {}, columns: [{}]",
+            self.logical.name(),
+            self.logical
+                .columns()
+                .iter()
+                .map(|c| c.id.clone())
+                .join(", ")
+        )
+    }
+}
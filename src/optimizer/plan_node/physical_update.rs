@@ -0,0 +1,46 @@
+use std::fmt;
+
+use crate::optimizer::{logical_update::LogicalUpdate, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalUpdate {
+    logical: LogicalUpdate,
+}
+
+impl PhysicalUpdate {
+    pub fn new(logical: LogicalUpdate) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalUpdate {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalUpdate {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalUpdate {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalUpdate: table: #{}, assignments: {:?}, predicate: {:?}",
+            self.logical.table_id(),
+            self.logical.assignments(),
+            self.logical.predicate()
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::optimizer::{logical_values::LogicalValues, PlanNode, PlanTreeNode};
+
+#[derive(Debug, Clone)]
+pub struct PhysicalValues {
+    logical: LogicalValues,
+}
+
+impl PhysicalValues {
+    pub fn new(logical: LogicalValues) -> Self {
+        Self { logical }
+    }
+
+    pub fn logical(&self) -> &LogicalValues {
+        &self.logical
+    }
+}
+
+impl PlanNode for PhysicalValues {
+    fn schema(&self) -> Vec<crate::catalog::ColumnCatalog> {
+        self.logical.schema()
+    }
+}
+
+impl PlanTreeNode for PhysicalValues {
+    fn children(&self) -> Vec<super::PlanRef> {
+        self.logical.children()
+    }
+
+    fn clone_with_children(&self, children: Vec<super::PlanRef>) -> super::PlanRef {
+        self.logical.clone_with_children(children)
+    }
+}
+
+impl fmt::Display for PhysicalValues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "PhysicalValues: columns: [{}], rows: {:?}",
+            self.logical
+                .columns()
+                .iter()
+                .map(|c| c.id.clone())
+                .join(", "),
+            self.logical.rows()
+        )
+    }
+}
@@ -2,11 +2,32 @@ use crate::for_all_plan_nodes;
 use crate::optimizer::physical_project::PhysicalProject;
 use crate::optimizer::physical_table::PhysicalTableScan;
 use crate::optimizer::plan_node::dummy::Dummy;
+use crate::optimizer::plan_node::logical_analyze::LogicalAnalyze;
+use crate::optimizer::plan_node::logical_copy_from_file::LogicalCopyFromFile;
+use crate::optimizer::plan_node::logical_copy_to_file::LogicalCopyToFile;
+use crate::optimizer::plan_node::logical_create_table::LogicalCreateTable;
+use crate::optimizer::plan_node::logical_delete::LogicalDelete;
+use crate::optimizer::plan_node::logical_drop_table::LogicalDropTable;
 use crate::optimizer::plan_node::logical_filter::LogicalFilter;
+use crate::optimizer::plan_node::logical_insert::LogicalInsert;
 use crate::optimizer::plan_node::logical_project::LogicalProject;
+use crate::optimizer::plan_node::logical_table_function::LogicalTableFunction;
 use crate::optimizer::plan_node::logical_table_scan::LogicalTableScan;
+use crate::optimizer::plan_node::logical_update::LogicalUpdate;
+use crate::optimizer::plan_node::logical_values::LogicalValues;
+use crate::optimizer::plan_node::physical_analyze::PhysicalAnalyze;
+use crate::optimizer::plan_node::physical_copy_from_file::PhysicalCopyFromFile;
+use crate::optimizer::plan_node::physical_copy_to_file::PhysicalCopyToFile;
+use crate::optimizer::plan_node::physical_create_table::PhysicalCreateTable;
+use crate::optimizer::plan_node::physical_delete::PhysicalDelete;
+use crate::optimizer::plan_node::physical_drop_table::PhysicalDropTable;
+use crate::optimizer::plan_node::physical_insert::PhysicalInsert;
+use crate::optimizer::plan_node::physical_table_function::PhysicalTableFunction;
+use crate::optimizer::plan_node::physical_update::PhysicalUpdate;
+use crate::optimizer::plan_node::physical_values::PhysicalValues;
 use crate::optimizer::plan_node::PhysicalFilter;
 use crate::optimizer::LogicalAgg;
+use crate::optimizer::PhysicalHashAgg;
 use crate::optimizer::PhysicalSimpleAgg;
 use crate::optimizer::PlanRef;
 
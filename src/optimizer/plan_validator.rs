@@ -0,0 +1,179 @@
+use crate::{
+    binder::expression::{BoundExpr, BoundInputRef},
+    catalog::ColumnCatalog,
+    optimizer::{expr_visitor::ExprVisitor, PlanNodeType, PlanRef},
+    planner::LogicalPlanError,
+};
+
+/// Checks that every input ref of a resolved logical plan refers to a column of the schema
+/// of the node's input, with the type of that column, so a bug in the planner or in a plan
+/// read from elsewhere is reported as an error instead of failing in the executors.
+#[derive(Default)]
+pub struct PlanValidator {}
+
+impl PlanValidator {
+    pub fn validate(&self, plan: &PlanRef) -> Result<(), LogicalPlanError> {
+        for child in plan.children() {
+            self.validate(&child)?;
+        }
+        let node_type = plan.node_type();
+        // the exprs of the node with the columns they are evaluated over
+        let (exprs, input) = match node_type {
+            PlanNodeType::LogicalTableScan => {
+                let scan = plan.as_logical_table_scan().unwrap();
+                (scan.filter().into_iter().collect(), scan.columns())
+            }
+            PlanNodeType::LogicalValues => {
+                let values = plan.as_logical_values().unwrap();
+                (values.rows().concat(), vec![])
+            }
+            PlanNodeType::LogicalFilter => {
+                let filter = plan.as_logical_filter().unwrap();
+                (vec![filter.expr()], filter.input().schema())
+            }
+            PlanNodeType::LogicalProject => {
+                let project = plan.as_logical_project().unwrap();
+                (project.exprs(), project.input().schema())
+            }
+            PlanNodeType::LogicalAgg => {
+                let agg = plan.as_logical_agg().unwrap();
+                let exprs = [agg.agg_funcs(), agg.group_by()].concat();
+                (exprs, agg.input().schema())
+            }
+            PlanNodeType::LogicalUpdate => {
+                let update = plan.as_logical_update().unwrap();
+                let mut exprs = update.assignments();
+                exprs.extend(update.predicate());
+                (exprs, update.input().schema())
+            }
+            PlanNodeType::LogicalDelete => {
+                let delete = plan.as_logical_delete().unwrap();
+                let exprs = delete.predicate().into_iter().collect();
+                (exprs, delete.input().schema())
+            }
+            _ => return Ok(()),
+        };
+        let mut checker = InputRefChecker {
+            node_type,
+            input: &input,
+            error: None,
+        };
+        for expr in &exprs {
+            checker.visit_expr(expr);
+        }
+        match checker.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Keeps the first error found in the input refs of the visited exprs.
+struct InputRefChecker<'a> {
+    node_type: PlanNodeType,
+    input: &'a [ColumnCatalog],
+    error: Option<LogicalPlanError>,
+}
+
+impl ExprVisitor for InputRefChecker<'_> {
+    fn pre_visit(&mut self, expr: &BoundExpr) {
+        if self.error.is_some() {
+            return;
+        }
+        if let BoundExpr::InputRef(BoundInputRef { index, return_type }) = expr {
+            let node = format!("{:?}", self.node_type);
+            match self.input.get(*index) {
+                None => {
+                    self.error = Some(LogicalPlanError::InputRefOutOfRange {
+                        node,
+                        index: *index,
+                        len: self.input.len(),
+                    })
+                }
+                Some(column) if column.desc.data_type != *return_type => {
+                    self.error = Some(LogicalPlanError::InputRefTypeMismatch {
+                        node,
+                        index: *index,
+                        expected: column.desc.data_type.clone(),
+                        actual: return_type.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod plan_validator_test {
+    use std::sync::Arc;
+
+    use arrow::datatypes::DataType;
+
+    use super::*;
+    use crate::{
+        catalog::ColumnDesc,
+        optimizer::{
+            logical_filter::LogicalFilter, logical_project::LogicalProject,
+            logical_table_scan::LogicalTableScan,
+        },
+    };
+
+    fn scan() -> PlanRef {
+        let columns = [("a", DataType::Int32), ("b", DataType::Utf8)]
+            .into_iter()
+            .map(|(name, data_type)| ColumnCatalog {
+                id: name.to_string(),
+                desc: ColumnDesc {
+                    name: name.to_string(),
+                    data_type,
+                    nullable: true,
+                    default: None,
+                },
+            })
+            .collect();
+        Arc::new(LogicalTableScan::new("t".to_string(), columns))
+    }
+
+    fn input_ref(index: usize, return_type: DataType) -> BoundExpr {
+        BoundExpr::InputRef(BoundInputRef { index, return_type })
+    }
+
+    #[test]
+    fn test_validate_input_refs() {
+        let validator = PlanValidator::default();
+        let plan: PlanRef = Arc::new(LogicalProject::new(
+            vec![input_ref(1, DataType::Utf8), input_ref(0, DataType::Int32)],
+            scan(),
+        ));
+        assert_eq!(validator.validate(&plan), Ok(()));
+
+        let plan: PlanRef = Arc::new(LogicalProject::new(
+            vec![input_ref(2, DataType::Int32)],
+            scan(),
+        ));
+        assert_eq!(
+            validator.validate(&plan),
+            Err(LogicalPlanError::InputRefOutOfRange {
+                node: "LogicalProject".to_string(),
+                index: 2,
+                len: 2,
+            })
+        );
+
+        // the error of a node below is reported before the nodes above it are looked at
+        let plan: PlanRef = Arc::new(LogicalProject::new(
+            vec![input_ref(5, DataType::Int32)],
+            Arc::new(LogicalFilter::new(input_ref(1, DataType::Boolean), scan())),
+        ));
+        assert_eq!(
+            validator.validate(&plan),
+            Err(LogicalPlanError::InputRefTypeMismatch {
+                node: "LogicalFilter".to_string(),
+                index: 1,
+                expected: DataType::Utf8,
+                actual: DataType::Boolean,
+            })
+        );
+    }
+}
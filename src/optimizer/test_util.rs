@@ -0,0 +1,45 @@
+//! Builders of the exprs the tests of the optimizer rules are written with.
+
+use arrow::datatypes::DataType;
+use sqlparser::ast::BinaryOperator;
+
+use crate::{
+    binder::expression::{binary_op::BoundBinaryOp, BoundColumnRef, BoundExpr},
+    catalog::{ColumnCatalog, ColumnDesc},
+    types::ScalarValue,
+};
+
+/// a nullable int column of the name
+pub fn column(name: &str) -> BoundExpr {
+    BoundExpr::ColumnRef(BoundColumnRef {
+        column_catalog: ColumnCatalog {
+            id: name.to_string(),
+            desc: ColumnDesc {
+                name: name.to_string(),
+                data_type: DataType::Int32,
+                nullable: true,
+                default: None,
+            },
+        },
+    })
+}
+
+pub fn binary(op: BinaryOperator, left: BoundExpr, right: BoundExpr) -> BoundExpr {
+    let return_type = match op {
+        BinaryOperator::Plus
+        | BinaryOperator::Minus
+        | BinaryOperator::Multiply
+        | BinaryOperator::Divide => left.return_type(),
+        _ => Some(DataType::Boolean),
+    };
+    BoundExpr::BinaryOp(BoundBinaryOp {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+        return_type,
+    })
+}
+
+pub fn constant(value: impl Into<ScalarValue>) -> BoundExpr {
+    BoundExpr::Constant(value.into())
+}
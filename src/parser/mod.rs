@@ -1,10 +1,126 @@
 use sqlparser::{
-    ast::Statement,
+    ast::{
+        helpers::stmt_create_table::CreateTableBuilder, CreateTableOptions, Expr, Ident, SqlOption,
+        Statement, Value,
+    },
     dialect::PostgreSqlDialect,
+    keywords::Keyword,
     parser::{Parser, ParserError},
+    tokenizer::Token,
 };
 
 pub fn parse(sql: &str) -> Result<Vec<Statement>, ParserError> {
     let dialect = PostgreSqlDialect {};
-    Parser::parse_sql(&dialect, sql)
+    let mut parser = Parser::new(&dialect).try_with_sql(sql)?;
+    let mut stmts = Vec::new();
+    let mut expecting_statement_delimiter = false;
+    loop {
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+        if parser.peek_token().token == Token::EOF {
+            break;
+        }
+        if expecting_statement_delimiter {
+            return parser.expected("end of statement", parser.peek_token());
+        }
+        let stmt = if parser.parse_keywords(&[Keyword::CREATE, Keyword::EXTERNAL, Keyword::TABLE]) {
+            parse_create_external_table(&mut parser)?
+        } else {
+            parser.parse_statement()?
+        };
+        stmts.push(stmt);
+        expecting_statement_delimiter = true;
+    }
+    Ok(stmts)
+}
+
+/// Parse the rest of
+///
+/// ```sql
+/// CREATE EXTERNAL TABLE [IF NOT EXISTS] name [(column type, ...)]
+/// STORED AS format [WITH (option value, ...)] LOCATION 'path'
+/// ```
+///
+/// into an external `CREATE TABLE` with the `WITH` options, where the format becomes the
+/// `format` option.
+fn parse_create_external_table(parser: &mut Parser) -> Result<Statement, ParserError> {
+    let if_not_exists = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+    let name = parser.parse_object_name(false)?;
+    let (columns, constraints) = parser.parse_columns()?;
+    parser.expect_keywords(&[Keyword::STORED, Keyword::AS])?;
+    let format = parser.parse_identifier()?;
+    let mut options = vec![SqlOption::KeyValue {
+        key: Ident::new("format"),
+        value: Expr::value(Value::SingleQuotedString(format.value.to_lowercase())),
+    }];
+    let mut location = None;
+    loop {
+        if parser.parse_keyword(Keyword::WITH) {
+            parser.expect_token(&Token::LParen)?;
+            options.extend(parser.parse_comma_separated(parse_table_option)?);
+            parser.expect_token(&Token::RParen)?;
+        } else if location.is_none() && parser.parse_keyword(Keyword::LOCATION) {
+            location = Some(parser.parse_literal_string()?);
+        } else {
+            break;
+        }
+    }
+    if location.is_none() {
+        return parser.expected("LOCATION", parser.peek_token());
+    }
+    Ok(CreateTableBuilder::new(name)
+        .if_not_exists(if_not_exists)
+        .external(true)
+        .columns(columns)
+        .constraints(constraints)
+        .table_options(CreateTableOptions::With(options))
+        .location(location)
+        .build())
+}
+
+/// an option written as `key value` or `key = value`
+fn parse_table_option(parser: &mut Parser) -> Result<SqlOption, ParserError> {
+    let key = parser.parse_identifier()?;
+    let _ = parser.consume_token(&Token::Eq);
+    let value = parser.parse_expr()?;
+    Ok(SqlOption::KeyValue { key, value })
+}
+
+#[cfg(test)]
+mod parser_test {
+    use sqlparser::ast::CreateTable;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_create_external_table() -> Result<(), ParserError> {
+        let stmts = parse(
+            "create external table t (a int, b varchar) stored as CSV \
+             with (delimiter '|', header = false) location 'data.csv'; select 1",
+        )?;
+        assert_eq!(stmts.len(), 2);
+        let Statement::CreateTable(CreateTable {
+            external,
+            columns,
+            table_options: CreateTableOptions::With(options),
+            location,
+            ..
+        }) = &stmts[0]
+        else {
+            panic!("expected create table, got {:?}", stmts[0]);
+        };
+        assert!(external);
+        assert_eq!(columns.len(), 2);
+        assert_eq!(location.as_deref(), Some("data.csv"));
+        let options = options.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            options,
+            vec!["format = 'csv'", "delimiter = '|'", "header = false"]
+        );
+
+        assert!(parse("create external table t stored as csv").is_err());
+        assert!(parse("create external table t location 'data.csv'").is_err());
+        Ok(())
+    }
 }
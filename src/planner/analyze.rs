@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::statement::analyze::BoundAnalyze,
+    optimizer::{logical_analyze::LogicalAnalyze, logical_table_scan::LogicalTableScan, PlanRef},
+    planner::{LogicalPlanError, Planner},
+};
+
+impl Planner {
+    pub fn plan_analyze(&self, stmt: BoundAnalyze) -> Result<PlanRef, LogicalPlanError> {
+        let table_id = stmt.table_catalog.id;
+        let scan = LogicalTableScan::new(table_id.clone(), stmt.columns.clone());
+        Ok(Arc::new(LogicalAnalyze::new(
+            table_id,
+            stmt.columns,
+            Arc::new(scan),
+        )))
+    }
+}
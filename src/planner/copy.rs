@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::statement::copy::BoundCopy,
+    optimizer::{
+        logical_copy_from_file::LogicalCopyFromFile, logical_copy_to_file::LogicalCopyToFile,
+        PlanRef,
+    },
+    planner::{LogicalPlanError, Planner},
+};
+
+impl Planner {
+    pub fn plan_copy(&self, stmt: BoundCopy) -> Result<PlanRef, LogicalPlanError> {
+        match stmt {
+            BoundCopy::To {
+                query,
+                columns,
+                file,
+            } => {
+                let input = self.plan_select(*query)?;
+                Ok(Arc::new(LogicalCopyToFile::new(file, columns, input)))
+            }
+            BoundCopy::From {
+                table_catalog,
+                columns,
+                file,
+            } => Ok(Arc::new(LogicalCopyFromFile::new(
+                table_catalog,
+                columns,
+                file,
+            ))),
+        }
+    }
+}
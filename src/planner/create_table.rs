@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::statement::create_table::BoundCreateTable,
+    optimizer::{logical_create_table::LogicalCreateTable, PlanRef},
+    planner::{LogicalPlanError, Planner},
+};
+
+impl Planner {
+    pub fn plan_create_table(&self, stmt: BoundCreateTable) -> Result<PlanRef, LogicalPlanError> {
+        if let Some(external) = stmt.external {
+            return Ok(Arc::new(LogicalCreateTable::new_external(
+                stmt.table_name,
+                stmt.columns,
+                external,
+            )));
+        }
+        let input = stmt
+            .query
+            .map(|query| self.plan_select(query))
+            .transpose()?;
+        Ok(Arc::new(LogicalCreateTable::new(
+            stmt.table_name,
+            stmt.columns,
+            input,
+        )))
+    }
+}
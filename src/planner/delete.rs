@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::statement::delete::BoundDelete,
+    optimizer::{logical_delete::LogicalDelete, logical_table_scan::LogicalTableScan, PlanRef},
+    planner::{LogicalPlanError, Planner},
+};
+
+impl Planner {
+    pub fn plan_delete(&self, stmt: BoundDelete) -> Result<PlanRef, LogicalPlanError> {
+        let table_catalog = stmt.table_ref.table_catalog;
+        let scan = Arc::new(LogicalTableScan::new(
+            table_catalog.id.clone(),
+            table_catalog.get_all_columns(),
+        ));
+        Ok(Arc::new(LogicalDelete::new(
+            table_catalog.id,
+            stmt.where_clause,
+            scan,
+        )))
+    }
+}
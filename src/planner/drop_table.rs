@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::statement::drop_table::BoundDropTable,
+    optimizer::{logical_drop_table::LogicalDropTable, PlanRef},
+    planner::{LogicalPlanError, Planner},
+};
+
+impl Planner {
+    pub fn plan_drop_table(&self, stmt: BoundDropTable) -> Result<PlanRef, LogicalPlanError> {
+        Ok(Arc::new(LogicalDropTable::new(
+            stmt.table_name,
+            stmt.if_exists,
+        )))
+    }
+}
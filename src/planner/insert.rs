@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::statement::insert::BoundInsert,
+    optimizer::{logical_insert::LogicalInsert, PlanRef},
+    planner::{LogicalPlanError, Planner},
+};
+
+impl Planner {
+    pub fn plan_insert(&self, stmt: BoundInsert) -> Result<PlanRef, LogicalPlanError> {
+        let input = self.plan_select(stmt.source)?;
+        Ok(Arc::new(LogicalInsert::new(
+            stmt.table_catalog.id.clone(),
+            stmt.table_catalog.get_all_columns(),
+            input,
+        )))
+    }
+}
@@ -1,5 +1,14 @@
+use arrow::datatypes::DataType;
+
 use crate::{binder::statement::BoundStatement, optimizer::PlanRef};
+mod analyze;
+mod copy;
+mod create_table;
+mod delete;
+mod drop_table;
+mod insert;
 mod select;
+mod update;
 pub mod util;
 
 pub struct Planner {}
@@ -8,12 +17,35 @@ impl Planner {
     pub fn plan(&self, stmt: BoundStatement) -> Result<PlanRef, LogicalPlanError> {
         match stmt {
             BoundStatement::Select(stmt) => self.plan_select(stmt),
+            BoundStatement::CreateTable(stmt) => self.plan_create_table(stmt),
+            BoundStatement::Insert(stmt) => self.plan_insert(stmt),
+            BoundStatement::DropTable(stmt) => self.plan_drop_table(stmt),
+            BoundStatement::Update(stmt) => self.plan_update(stmt),
+            BoundStatement::Delete(stmt) => self.plan_delete(stmt),
+            BoundStatement::Copy(stmt) => self.plan_copy(stmt),
+            BoundStatement::Analyze(stmt) => self.plan_analyze(stmt),
         }
     }
 }
 
 #[derive(thiserror::Error, Debug, PartialEq)]
-pub enum LogicalPlanError {}
+pub enum LogicalPlanError {
+    #[error("input ref {index} of {node} is out of range of its {len} input columns")]
+    InputRefOutOfRange {
+        node: String,
+        index: usize,
+        len: usize,
+    },
+    #[error(
+        "input ref {index} of {node} has type {actual}, but its input column has type {expected}"
+    )]
+    InputRefTypeMismatch {
+        node: String,
+        index: usize,
+        expected: DataType,
+        actual: DataType,
+    },
+}
 
 #[cfg(test)]
 mod planner_test {
@@ -24,12 +56,16 @@ mod planner_test {
 
     use crate::{
         binder::{
-            expression::{binary_op::BoundBinaryOp, BoundColumnRef, BoundExpr},
+            expression::{
+                agg_func::{AggFunc, BoundAggFunc},
+                binary_op::BoundBinaryOp,
+                BoundColumnRef, BoundExpr,
+            },
             statement::{BoundSelect, BoundStatement},
             table::BoundTableRef,
         },
         catalog::{ColumnCatalog, ColumnDesc, TableCatalog},
-        optimizer::PlanNodeType,
+        optimizer::{PlanNodeType, PlanRef},
         planner::Planner,
         types::ScalarValue,
     };
@@ -41,6 +77,8 @@ mod planner_test {
                 desc: ColumnDesc {
                     name: column_name,
                     data_type: arrow::datatypes::DataType::Int32,
+                    nullable: true,
+                    default: None,
                 },
             },
         })
@@ -58,6 +96,8 @@ mod planner_test {
                     desc: ColumnDesc {
                         name: column,
                         data_type: arrow::datatypes::DataType::Int32,
+                        nullable: true,
+                        default: None,
                     },
                 },
             );
@@ -68,7 +108,10 @@ mod planner_test {
                 name: table_name,
                 columns: column_map,
                 column_ids,
+                statistics: None,
             },
+            values: None,
+            function: None,
         })
     }
 
@@ -87,6 +130,8 @@ mod planner_test {
             select_list: vec![c1],
             from_table: t,
             where_clause: Some(where_clause),
+            group_by: vec![],
+            grouping_sets: vec![],
         })
     }
 
@@ -98,7 +143,65 @@ mod planner_test {
         assert!(node.is_ok());
         let plan_ref = node.unwrap();
         assert_eq!(plan_ref.node_type(), PlanNodeType::LogicalProject);
-        assert_eq!(plan_ref.schema().len(), 2);
+        let schema = plan_ref.schema();
+        assert_eq!(schema.len(), 1);
+        assert_eq!(schema[0].desc.name, "c1");
         dbg!(plan_ref);
     }
+
+    #[test]
+    fn test_plan_select_without_from_works() {
+        let stmt = BoundStatement::Select(BoundSelect {
+            select_list: vec![BoundExpr::Constant(ScalarValue::Int32(Some(1)))],
+            from_table: None,
+            where_clause: None,
+            group_by: vec![],
+            grouping_sets: vec![],
+        });
+        let plan_ref = Planner {}.plan(stmt).unwrap();
+        assert_eq!(plan_ref.node_type(), PlanNodeType::LogicalProject);
+        let values = plan_ref.children()[0].clone();
+        assert_eq!(values.node_type(), PlanNodeType::LogicalValues);
+        assert_eq!(values.as_logical_values().unwrap().rows(), vec![vec![]]);
+    }
+
+    #[test]
+    fn test_plan_schema() {
+        let agg = |func, return_type| {
+            BoundExpr::AggFunc(BoundAggFunc {
+                func,
+                exprs: vec![build_test_column("c2".to_string())],
+                return_type,
+                filter: None,
+                order_by: vec![],
+            })
+        };
+        let count = agg(AggFunc::Count, DataType::Int64);
+        let sum = agg(AggFunc::Sum, DataType::Int32);
+        let c1 = build_test_column("c1".to_string());
+        let stmt = BoundStatement::Select(BoundSelect {
+            select_list: vec![c1.clone(), count, sum],
+            from_table: build_test_table("t".to_string(), vec!["c1".to_string(), "c2".to_string()]),
+            where_clause: None,
+            group_by: vec![c1],
+            grouping_sets: vec![],
+        });
+        let plan_ref = Planner {}.plan(stmt).unwrap();
+        let schema = |plan: &PlanRef| {
+            plan.schema()
+                .into_iter()
+                .map(|c| (c.desc.name, c.desc.data_type, c.desc.nullable))
+                .collect::<Vec<_>>()
+        };
+        // a count is never null, unlike a sum of no rows
+        let expected = vec![
+            ("c1".to_string(), DataType::Int32, true),
+            ("count".to_string(), DataType::Int64, false),
+            ("sum".to_string(), DataType::Int32, true),
+        ];
+        assert_eq!(schema(&plan_ref), expected);
+        let agg = plan_ref.children()[0].clone();
+        assert_eq!(agg.node_type(), PlanNodeType::LogicalAgg);
+        assert_eq!(schema(&agg), expected);
+    }
 }
@@ -4,7 +4,8 @@ use crate::{
     binder::statement::BoundSelect,
     optimizer::{
         logical_filter::LogicalFilter, logical_project::LogicalProject,
-        logical_table_scan::LogicalTableScan, LogicalAgg, PlanRef,
+        logical_table_function::LogicalTableFunction, logical_table_scan::LogicalTableScan,
+        logical_values::LogicalValues, LogicalAgg, PlanRef,
     },
     planner::{util::find_aggregate_exprs, LogicalPlanError, Planner},
 };
@@ -13,13 +14,34 @@ impl Planner {
     pub fn plan_select(&self, stmt: BoundSelect) -> Result<PlanRef, LogicalPlanError> {
         let mut plan: PlanRef;
 
-        if let Some(table_ref) = stmt.from_table {
-            plan = Arc::new(LogicalTableScan::new(
-                table_ref.table_catalog.id.clone(),
-                table_ref.table_catalog.get_all_columns(),
-            ))
-        } else {
-            todo!("need logical values")
+        match stmt.from_table {
+            Some(table_ref) => match (table_ref.values, table_ref.function) {
+                (Some(rows), _) => {
+                    plan = Arc::new(LogicalValues::new(
+                        table_ref.table_catalog.get_all_columns(),
+                        rows,
+                    ))
+                }
+                (None, Some(function)) => {
+                    plan = Arc::new(LogicalTableFunction::new(
+                        table_ref.table_catalog.name.clone(),
+                        table_ref.table_catalog.get_all_columns(),
+                        function,
+                    ))
+                }
+                (None, None) => {
+                    plan = Arc::new(
+                        LogicalTableScan::new(
+                            table_ref.table_catalog.id.clone(),
+                            table_ref.table_catalog.get_all_columns(),
+                        )
+                        .with_filter(stmt.where_clause.clone())
+                        .with_statistics(table_ref.table_catalog.statistics.clone()),
+                    )
+                }
+            },
+            // select without FROM is evaluated over a single row without any column
+            None => plan = Arc::new(LogicalValues::new(vec![], vec![vec![]])),
         }
 
         if let Some(expr) = stmt.where_clause {
@@ -28,8 +50,13 @@ impl Planner {
 
         let agg = find_aggregate_exprs(&stmt.select_list);
 
-        if !agg.is_empty() {
-            plan = Arc::new(LogicalAgg::new(agg, vec![], plan))
+        if !agg.is_empty() || !stmt.group_by.is_empty() || !stmt.grouping_sets.is_empty() {
+            plan = Arc::new(LogicalAgg::new_with_grouping_sets(
+                agg,
+                stmt.group_by,
+                stmt.grouping_sets,
+                plan,
+            ))
         }
 
         if !stmt.select_list.is_empty() {
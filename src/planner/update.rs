@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use crate::{
+    binder::statement::update::BoundUpdate,
+    optimizer::{logical_table_scan::LogicalTableScan, logical_update::LogicalUpdate, PlanRef},
+    planner::{LogicalPlanError, Planner},
+};
+
+impl Planner {
+    pub fn plan_update(&self, stmt: BoundUpdate) -> Result<PlanRef, LogicalPlanError> {
+        let table_catalog = stmt.table_ref.table_catalog;
+        let scan = Arc::new(LogicalTableScan::new(
+            table_catalog.id.clone(),
+            table_catalog.get_all_columns(),
+        ));
+        Ok(Arc::new(LogicalUpdate::new(
+            table_catalog.id,
+            stmt.assignments,
+            stmt.where_clause,
+            scan,
+        )))
+    }
+}
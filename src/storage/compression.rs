@@ -0,0 +1,103 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::storage::StorageError;
+
+/// The compression of the files of a table, decoded transparently while reading them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileCompression {
+    Uncompressed,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl FileCompression {
+    /// the compression told by the extension of the file, like `orders.csv.gz`
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("zst") => Self::Zstd,
+            Some("bz2") => Self::Bzip2,
+            _ => Self::Uncompressed,
+        }
+    }
+
+    /// open the file for reading its decompressed content
+    pub fn open(&self, path: &Path) -> Result<Box<dyn Read + Send>, StorageError> {
+        let file = File::open(path)?;
+        Ok(match self {
+            Self::Uncompressed => Box::new(file),
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(BufReader::new(file))),
+            Self::Zstd => Box::new(zstd::Decoder::new(file)?),
+            Self::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(BufReader::new(file))),
+        })
+    }
+}
+
+/// open a file of a table, whose compression is told by its extension unless configured
+pub(crate) fn open_file(
+    path: &Path,
+    compression: Option<FileCompression>,
+) -> Result<Box<dyn Read + Send>, StorageError> {
+    compression
+        .unwrap_or_else(|| FileCompression::from_path(path))
+        .open(path)
+}
+
+impl FromStr for FileCompression {
+    type Err = StorageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" | "uncompressed" => Ok(Self::Uncompressed),
+            "gzip" | "gz" => Ok(Self::Gzip),
+            "zstd" | "zst" => Ok(Self::Zstd),
+            "bzip2" | "bz2" => Ok(Self::Bzip2),
+            _ => Err(StorageError::Unsupported(format!("compression {s}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod compression_test {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_compressed_files_are_decoded() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let content = b"a,b\n1,x\n2,y\n";
+
+        let gzip = dir.path().join("t.csv.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&gzip)?, flate2::Compression::default());
+        encoder.write_all(content)?;
+        encoder.finish()?;
+
+        let zstd = dir.path().join("t.csv.zst");
+        std::fs::write(&zstd, zstd::encode_all(&content[..], 0)?)?;
+
+        let bzip2 = dir.path().join("t.csv.bz2");
+        let mut encoder =
+            bzip2::write::BzEncoder::new(File::create(&bzip2)?, bzip2::Compression::default());
+        encoder.write_all(content)?;
+        encoder.finish()?;
+
+        for path in [gzip, zstd, bzip2] {
+            let compression = FileCompression::from_path(&path);
+            assert_ne!(compression, FileCompression::Uncompressed);
+            let mut decoded = vec![];
+            compression.open(&path)?.read_to_end(&mut decoded)?;
+            assert_eq!(decoded, content);
+        }
+        assert_eq!("GZIP".parse::<FileCompression>()?, FileCompression::Gzip);
+        assert!("lz4".parse::<FileCompression>().is_err());
+        Ok(())
+    }
+}
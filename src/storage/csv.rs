@@ -1,23 +1,34 @@
 #![allow(warnings)]
 use std::{
-    collections::{BTreeMap, HashMap},
-    fs::File,
+    collections::HashMap,
+    io::{Read, Write},
+    path::Path,
     sync::{Arc, Mutex},
 };
 
 use arrow::{
-    csv::{reader, Reader, ReaderBuilder},
-    datatypes::{Schema, SchemaRef},
+    array::{ArrayRef, AsArray, Date32Array, RecordBatch, StringArray, TimestampMicrosecondArray},
+    compute::cast,
+    csv::{reader::Format, Reader, ReaderBuilder, Writer, WriterBuilder},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    error::ArrowError,
 };
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use regex::Regex;
 
 use crate::{
-    catalog::{ColumnCatalog, ColumnDesc, RootCatalog, TableCatalog, TableId},
-    storage::{Storage, StorageError, Table, Transaction},
+    catalog::{ColumnCatalog, RootCatalog, TableCatalog, TableId},
+    storage::{
+        files::{infer_catalog, TableFiles},
+        open_file, BatchStream, FileCompression, IpcConfig, IpcTable, JsonConfig, JsonTable,
+        PartitionPruner, Storage, StorageError, Table, TableReader, Transaction,
+    },
 };
 
+/// A read-only storage of tables over csv, json and Arrow IPC files, which are read on every scan.
 pub struct CsvStorage {
     catalog: Mutex<RootCatalog>,
-    tables: Mutex<HashMap<TableId, CsvTable>>,
+    tables: Mutex<HashMap<TableId, Arc<dyn Table>>>,
 }
 
 impl Default for CsvStorage {
@@ -36,16 +47,51 @@ impl CsvStorage {
 }
 
 impl Storage for CsvStorage {
-    type TableType = CsvTable;
-
-    fn create_csv_table(&self, id: String, filepath: String) -> Result<(), StorageError> {
-        let table = CsvTable::new(id.clone(), filepath, CsvConfig::default())?;
+    fn create_csv_table(
+        &self,
+        id: String,
+        filepath: String,
+        config: CsvConfig,
+    ) -> Result<(), StorageError> {
+        let table = CsvTable::new(id.clone(), filepath, config)?;
         self.catalog
             .lock()
             .unwrap()
             .tables
             .insert(id.clone(), table.catalog.clone());
-        self.tables.lock().unwrap().insert(id, table);
+        self.tables.lock().unwrap().insert(id, Arc::new(table));
+        Ok(())
+    }
+
+    fn create_json_table(
+        &self,
+        id: String,
+        location: String,
+        config: JsonConfig,
+    ) -> Result<(), StorageError> {
+        let table = JsonTable::new(id.clone(), location, config)?;
+        self.catalog
+            .lock()
+            .unwrap()
+            .tables
+            .insert(id.clone(), table.catalog().clone());
+        self.tables.lock().unwrap().insert(id, Arc::new(table));
+        Ok(())
+    }
+
+    fn create_ipc_table(
+        &self,
+        id: String,
+        location: String,
+        config: IpcConfig,
+    ) -> Result<(), StorageError> {
+        let table = IpcTable::new(id.clone(), location, config)?;
+        self.catalog
+            .lock()
+            .unwrap()
+            .tables
+            .insert(id.clone(), table.catalog().clone());
+        self.tables.lock().unwrap().insert(id, Arc::new(table));
         Ok(())
     }
 
@@ -57,21 +103,36 @@ impl Storage for CsvStorage {
         unreachable!("csv storage does not support create memory table")
     }
 
-    fn get_table(&self, id: String) -> Result<Self::TableType, StorageError> {
+    fn create_table(&self, id: String, columns: Vec<ColumnCatalog>) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!(
+            "create table {id} in csv storage"
+        )))
+    }
+
+    fn drop_table(&self, id: String) -> Result<(), StorageError> {
+        self.catalog.lock().unwrap().tables.remove(&id);
         self.tables
             .lock()
             .unwrap()
-            .get(&id)
-            .cloned()
+            .remove(&id)
+            .map(|_| ())
             .ok_or(StorageError::TableNotFound(id))
     }
 
+    fn begin(&self) -> Result<Arc<dyn Transaction>, StorageError> {
+        Ok(Arc::new(CsvTransaction {
+            tables: self.tables.lock().unwrap().clone(),
+        }))
+    }
+
     fn get_catalog(&self) -> RootCatalog {
         self.catalog.lock().unwrap().clone()
     }
 }
 
-#[derive(Clone)]
+/// How a csv file is read. Unless a schema is given, the columns are inferred from the
+/// first records of the file.
+#[derive(Debug, Clone)]
 pub struct CsvConfig {
     has_header: bool,
     delimiter: u8,
@@ -79,6 +140,10 @@ pub struct CsvConfig {
     batch_size: usize,
     projection: Option<Vec<usize>>,
     datetime_format: Option<String>,
+    null_marker: Option<String>,
+    schema: Option<SchemaRef>,
+    compression: Option<FileCompression>,
+    newlines_in_values: bool,
 }
 
 impl Default for CsvConfig {
@@ -90,115 +155,405 @@ impl Default for CsvConfig {
             batch_size: 1024,
             projection: None,
             datetime_format: None,
+            null_marker: None,
+            schema: None,
+            compression: None,
+            newlines_in_values: true,
+        }
+    }
+}
+
+impl CsvConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// whether the first line holds the column names, true by default
+    pub fn with_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// the number of records the schema is inferred from, 10 by default
+    pub fn with_infer_schema_max_records(mut self, records: usize) -> Self {
+        self.infer_schema_max_read_records = Some(records);
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// the chrono format of the date and timestamp columns, which are otherwise expected
+    /// in ISO 8601
+    pub fn with_datetime_format(mut self, format: impl Into<String>) -> Self {
+        self.datetime_format = Some(format.into());
+        self
+    }
+
+    /// the text of null fields, instead of empty fields
+    pub fn with_null_marker(mut self, marker: impl Into<String>) -> Self {
+        self.null_marker = Some(marker.into());
+        self
+    }
+
+    /// read the columns of the schema instead of inferring them
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// the compression of all files, which is otherwise told by the extension of each
+    pub fn with_compression(mut self, compression: FileCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// whether quoted values may hold newlines, true by default. Only the files without
+    /// them are split into byte ranges at line ends when scanned in parallel, as a line end
+    /// cannot tell whether it is quoted.
+    pub fn with_newlines_in_values(mut self, newlines_in_values: bool) -> Self {
+        self.newlines_in_values = newlines_in_values;
+        self
+    }
+
+    fn open(&self, path: &Path) -> Result<Box<dyn Read + Send>, StorageError> {
+        open_file(path, self.compression)
+    }
+
+    /// whether a file is split into byte ranges at line ends, which needs it uncompressed
+    fn splits_lines(&self, path: &Path) -> bool {
+        !self.newlines_in_values
+            && self
+                .compression
+                .unwrap_or_else(|| FileCompression::from_path(path))
+                == FileCompression::Uncompressed
+    }
+
+    /// a writer of csv files which the same config reads back
+    pub(crate) fn writer<W: Write>(&self, writer: W) -> Writer<W> {
+        let mut builder = WriterBuilder::new()
+            .with_header(self.has_header)
+            .with_delimiter(self.delimiter);
+        if let Some(format) = &self.datetime_format {
+            builder = builder
+                .with_date_format(format.clone())
+                .with_timestamp_format(format.clone());
         }
+        if let Some(null_marker) = &self.null_marker {
+            builder = builder.with_null(null_marker.clone());
+        }
+        builder.build(writer)
     }
 }
 
+/// A table over csv files, which may be partitioned, see [`TableFiles`].
 #[derive(Clone)]
 pub struct CsvTable {
     id: TableId,
+    /// the columns of the files
     arrow_schema: SchemaRef,
     arrow_csv_cfg: CsvConfig,
-    filepath: String,
+    files: TableFiles,
+    /// the columns of the files followed by the partition columns
+    schema: SchemaRef,
     catalog: TableCatalog,
 }
 
 impl CsvTable {
-    pub fn new(id: String, filepath: String, cfg: CsvConfig) -> Result<Self, StorageError> {
-        let schema = Self::infer_arrow_schema(filepath.clone(), &cfg)?;
-        let catalog = Self::infer_catalog(id.clone(), id.clone(), &schema);
+    pub fn new(id: String, location: String, cfg: CsvConfig) -> Result<Self, StorageError> {
+        let files = TableFiles::list(&location)?;
+        let arrow_schema = match &cfg.schema {
+            Some(schema) => schema.clone(),
+            None => Arc::new(Self::infer_arrow_schema(&files, &cfg)?),
+        };
+        let schema = files.table_schema(&arrow_schema)?;
+        let catalog = infer_catalog(id.clone(), &schema);
         Ok(Self {
             id,
-            arrow_schema: Arc::new(schema),
+            arrow_schema,
             arrow_csv_cfg: cfg,
-            filepath,
+            files,
+            schema,
             catalog,
         })
     }
 
-    fn infer_arrow_schema(filepath: String, cfg: &CsvConfig) -> Result<Schema, StorageError> {
-        let schema = reader::infer_schema_from_files(
-            &[filepath],
-            cfg.delimiter,
-            cfg.infer_schema_max_read_records,
-            cfg.has_header,
-        )?;
-        Ok(schema)
-    }
-
-    fn infer_catalog(id: String, name: String, schema: &Schema) -> TableCatalog {
-        let mut columns = BTreeMap::new();
-        let mut column_ids = Vec::new();
-        for f in schema.fields().iter() {
-            let field_name = f.name().to_string();
-            column_ids.push(field_name.clone());
-            columns.insert(
-                field_name.clone(),
-                ColumnCatalog {
-                    id: field_name.clone(),
-                    desc: ColumnDesc {
-                        name: field_name,
-                        data_type: f.data_type().clone(),
-                    },
-                },
-            );
+    pub fn catalog(&self) -> &TableCatalog {
+        &self.catalog
+    }
+
+    /// infer the columns from the first records of the files, like
+    /// `arrow::csv::infer_schema_from_files` does on decompressed files
+    fn infer_arrow_schema(files: &TableFiles, cfg: &CsvConfig) -> Result<Schema, StorageError> {
+        let mut format = Format::default()
+            .with_delimiter(cfg.delimiter)
+            .with_header(cfg.has_header);
+        if let Some(null_marker) = &cfg.null_marker {
+            format = format.with_null_regex(null_regex(null_marker)?);
         }
-        TableCatalog {
-            id,
-            name,
-            columns,
-            column_ids,
+        let mut schemas = vec![];
+        let mut records_to_read = cfg.infer_schema_max_read_records.unwrap_or(usize::MAX);
+        for file in &files.files {
+            let (schema, records_read) =
+                format.infer_schema(cfg.open(&file.path)?, Some(records_to_read))?;
+            schemas.push(schema);
+            records_to_read -= records_read;
+            if records_to_read == 0 {
+                break;
+            }
         }
+        Ok(Schema::try_merge(schemas)?)
+    }
+}
+
+/// The files are read only, so a transaction only needs the tables existing when it
+/// began.
+pub struct CsvTransaction {
+    tables: HashMap<TableId, Arc<dyn Table>>,
+}
+
+impl Transaction for CsvTransaction {
+    fn get_table(&self, id: String) -> Result<Arc<dyn Table>, StorageError> {
+        self.tables
+            .get(&id)
+            .cloned()
+            .ok_or(StorageError::TableNotFound(id))
+    }
+
+    fn commit(&self) -> Result<(), StorageError> {
+        Ok(())
     }
+
+    fn rollback(&self) {}
 }
 
 impl Table for CsvTable {
-    type TransactionType = CsvTransaction;
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+        let file_schema = self.arrow_schema.clone();
+        let config = self.arrow_csv_cfg.clone();
+        Ok(Box::new(self.files.read(
+            self.schema.clone(),
+            move |file| {
+                let has_header = config.has_header && file.is_read_from_start();
+                Ok(Box::new(CsvReader::new(
+                    file.open(config.compression)?,
+                    file_schema.clone(),
+                    &config,
+                    has_header,
+                )?))
+            },
+        )))
+    }
+
+    fn scan_pruned(self: Arc<Self>, pruner: &PartitionPruner<'_>) -> BatchStream {
+        if !self.files.is_partitioned() {
+            return self.scan();
+        }
+        Arc::new(CsvTable {
+            files: self.files.prune(&self.arrow_schema, pruner),
+            ..self.as_ref().clone()
+        })
+        .scan()
+    }
+
+    /// split the files, or the lines of uncompressed files when there are fewer files
+    /// than partitions
+    fn scan_partitions(
+        self: Arc<Self>,
+        partitions: usize,
+        pruner: &PartitionPruner<'_>,
+    ) -> Vec<BatchStream> {
+        let files = if self.files.is_partitioned() {
+            self.files.prune(&self.arrow_schema, pruner)
+        } else {
+            self.files.clone()
+        };
+        files
+            .split(partitions, |path| self.arrow_csv_cfg.splits_lines(path))
+            .into_iter()
+            .map(|files| {
+                Arc::new(CsvTable {
+                    files,
+                    ..self.as_ref().clone()
+                })
+                .scan()
+            })
+            .collect()
+    }
+
+    fn append(&self, _batch: arrow::array::RecordBatch) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!(
+            "insert into csv table {}",
+            self.id
+        )))
+    }
 
-    fn read(&self) -> Result<Self::TransactionType, StorageError> {
-        CsvTransaction::start(self)
+    fn overwrite(&self, _batches: Vec<arrow::array::RecordBatch>) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!(
+            "modify csv table {}",
+            self.id
+        )))
     }
 }
 
-pub struct CsvTransaction {
-    reader: Reader<File>,
+/// Reads a csv file of a table.
+pub struct CsvReader {
+    reader: Reader<Box<dyn Read + Send>>,
+    schema: SchemaRef,
+    datetime_format: Option<String>,
 }
 
-impl CsvTransaction {
-    pub fn start(table: &CsvTable) -> Result<Self, StorageError> {
+impl CsvReader {
+    pub fn open(path: &Path, schema: SchemaRef, cfg: &CsvConfig) -> Result<Self, StorageError> {
+        Self::new(cfg.open(path)?, schema, cfg, cfg.has_header)
+    }
+
+    /// read the records of a file opened by the caller, which starts with the header when
+    /// `has_header` is set
+    fn new(
+        file: Box<dyn Read + Send>,
+        schema: SchemaRef,
+        cfg: &CsvConfig,
+        has_header: bool,
+    ) -> Result<Self, StorageError> {
         Ok(Self {
-            reader: Self::create_reader(
-                table.filepath.clone(),
-                table.arrow_schema.clone(),
-                &table.arrow_csv_cfg,
-            )?,
+            reader: Self::create_reader(file, schema.clone(), cfg, has_header)?,
+            schema,
+            datetime_format: cfg.datetime_format.clone(),
         })
     }
 
     fn create_reader(
-        filepath: String,
+        file: Box<dyn Read + Send>,
         schema: SchemaRef,
         cfg: &CsvConfig,
-    ) -> Result<Reader<File>, StorageError> {
-        let file = File::open(filepath)?;
-        let reader = ReaderBuilder::new(schema)
+        has_header: bool,
+    ) -> Result<Reader<Box<dyn Read + Send>>, StorageError> {
+        // the temporal columns are parsed afterwards when they have their own format
+        let schema = match &cfg.datetime_format {
+            Some(_) => Arc::new(Schema::new(
+                schema
+                    .fields()
+                    .iter()
+                    .map(|f| match f.data_type() {
+                        DataType::Date32 | DataType::Timestamp(_, None) => {
+                            Field::new(f.name(), DataType::Utf8, f.is_nullable())
+                        }
+                        _ => f.as_ref().clone(),
+                    })
+                    .collect::<Vec<_>>(),
+            )),
+            None => schema,
+        };
+        let mut builder = ReaderBuilder::new(schema)
             .with_batch_size(cfg.batch_size)
             .with_delimiter(cfg.delimiter)
-            .with_header(cfg.has_header)
-            .build(file)?;
-        Ok(reader)
+            .with_header(has_header);
+        if let Some(marker) = &cfg.null_marker {
+            builder = builder.with_null_regex(null_regex(marker)?);
+        }
+        Ok(builder.build(file)?)
+    }
+
+    /// parse the temporal columns, read as strings, with the datetime format
+    fn parse_datetimes(
+        &self,
+        batch: RecordBatch,
+        format: &str,
+    ) -> Result<RecordBatch, StorageError> {
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(self.schema.fields())
+            .map(|(column, field)| match field.data_type() {
+                DataType::Date32 | DataType::Timestamp(_, None) => {
+                    parse_datetime_column(column.as_string::<i32>(), field.data_type(), format)
+                }
+                _ => Ok(column.clone()),
+            })
+            .collect::<Result<Vec<_>, ArrowError>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
     }
 }
 
-impl Transaction for CsvTransaction {
-    fn next_batch(&mut self) -> Result<Option<arrow::array::RecordBatch>, StorageError> {
-        let batch = self.reader.next().transpose()?;
-        Ok(batch)
+fn parse_datetime_column(
+    column: &StringArray,
+    data_type: &DataType,
+    format: &str,
+) -> Result<ArrayRef, ArrowError> {
+    let error = |value: &str| {
+        ArrowError::ParseError(format!(
+            "cannot parse '{value}' as {data_type} with format '{format}'"
+        ))
+    };
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    match data_type {
+        DataType::Date32 => {
+            let days = column
+                .iter()
+                .map(|value| {
+                    value
+                        .map(|v| {
+                            let date =
+                                NaiveDate::parse_from_str(v, format).map_err(|_| error(v))?;
+                            Ok((date - epoch).num_days() as i32)
+                        })
+                        .transpose()
+                })
+                .collect::<Result<Date32Array, ArrowError>>()?;
+            Ok(Arc::new(days))
+        }
+        _ => {
+            let micros = column
+                .iter()
+                .map(|value| {
+                    value
+                        .map(|v| {
+                            let datetime = NaiveDateTime::parse_from_str(v, format)
+                                .or_else(|_| {
+                                    NaiveDate::parse_from_str(v, format)
+                                        .map(|date| date.and_time(NaiveTime::MIN))
+                                })
+                                .map_err(|_| error(v))?;
+                            Ok(datetime.and_utc().timestamp_micros())
+                        })
+                        .transpose()
+                })
+                .collect::<Result<TimestampMicrosecondArray, ArrowError>>()?;
+            cast(&micros, data_type)
+        }
+    }
+}
+
+impl Iterator for CsvReader {
+    type Item = Result<RecordBatch, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.reader.next()?;
+        Some(match (batch, &self.datetime_format) {
+            (Ok(batch), Some(format)) => self.parse_datetimes(batch, format),
+            (batch, _) => batch.map_err(StorageError::from),
+        })
     }
 }
 
+/// the regex matching exactly the null marker
+fn null_regex(marker: &str) -> Result<Regex, StorageError> {
+    Regex::new(&format!("^{}$", regex::escape(marker)))
+        .map_err(|e| StorageError::Unsupported(format!("null marker {marker}: {e}")))
+}
+
 #[cfg(test)]
 mod tests {
+    use arrow::datatypes::{Date32Type, Int64Type, TimeUnit, TimestampMicrosecondType};
+
     use super::*;
 
     #[tokio::test]
@@ -206,12 +561,193 @@ mod tests {
         let id = "test".to_string();
         let filepath = "./tests/csv/sample.csv".to_string();
         let storage = CsvStorage::default();
-        storage.create_csv_table(id.clone(), filepath).unwrap();
-        let table = storage.get_table(id.clone()).unwrap();
+        storage
+            .create_csv_table(id.clone(), filepath, CsvConfig::default())
+            .unwrap();
+        let table = storage.begin().unwrap().get_table(id.clone()).unwrap();
         let mut tx = table.read().unwrap();
         let batch = tx.next_batch().unwrap();
         assert!(batch.is_some());
         let batch = batch.unwrap();
-        assert_eq!(batch.num_rows(), 1024);
+        assert_eq!(batch.num_rows(), 4);
+    }
+
+    #[test]
+    fn test_csv_config_reads_custom_files() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let filepath = dir.path().join("orders.csv");
+        std::fs::write(
+            &filepath,
+            "1|NA|05/01/2024 00:00:00|05/01/2024 10:30:00\n2|pen|NA|06/01/2024 08:00:00\n",
+        )?;
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("item", DataType::Utf8, true),
+            Field::new("shipped", DataType::Date32, true),
+            Field::new(
+                "ordered_at",
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+                true,
+            ),
+        ]);
+        let config = CsvConfig::new()
+            .with_header(false)
+            .with_delimiter(b'|')
+            .with_null_marker("NA")
+            .with_datetime_format("%d/%m/%Y %H:%M:%S")
+            .with_schema(Arc::new(schema));
+        let table = CsvTable::new(
+            "orders".to_string(),
+            filepath.to_string_lossy().into(),
+            config,
+        )?;
+        assert!(table.catalog().get_column_by_name("shipped").is_some());
+
+        let batch = table.read()?.next_batch()?.unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert!(batch.column(1).is_null(0));
+        assert_eq!(batch.column(1).as_string::<i32>().value(1), "pen");
+        assert!(batch.column(2).is_null(1));
+        let shipped = batch.column(2).as_primitive::<Date32Type>();
+        assert_eq!(
+            shipped.value_as_date(0),
+            NaiveDate::from_ymd_opt(2024, 1, 5)
+        );
+        let ordered_at = batch.column(3).as_primitive::<TimestampMicrosecondType>();
+        assert_eq!(
+            ordered_at.value_as_datetime(0),
+            NaiveDate::from_ymd_opt(2024, 1, 5).and_then(|d| d.and_hms_opt(10, 30, 0))
+        );
+        assert_eq!(
+            ordered_at.value_as_datetime(1),
+            NaiveDate::from_ymd_opt(2024, 1, 6).and_then(|d| d.and_hms_opt(8, 0, 0))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_csv_table_prunes_partitions() -> Result<(), StorageError> {
+        use futures::TryStreamExt;
+
+        let table = Arc::new(CsvTable::new(
+            "orders".to_string(),
+            "./tests/external/orders".to_string(),
+            CsvConfig::default(),
+        )?);
+        let columns = table.catalog().get_all_columns();
+        let columns = columns
+            .iter()
+            .map(|c| (c.id.as_str(), c.desc.data_type.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("id", DataType::Int64),
+                ("amount", DataType::Int64),
+                ("year", DataType::Int64),
+                ("month", DataType::Int64),
+            ]
+        );
+        let batches = table.clone().scan().try_collect::<Vec<_>>().await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 6);
+
+        // keep the partitions of 2026 only, seeing one row per file
+        let batches = table
+            .scan_pruned(&|partitions| {
+                assert_eq!(partitions.num_rows(), 4);
+                assert!(partitions.column(0).is_null(0));
+                let years = partitions.column(2).as_primitive::<Int64Type>();
+                years.iter().map(|year| year == Some(2026)).collect()
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+        let ids = batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int64Type>().values().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![3, 4, 5, 6]);
+
+        let table = CsvTable::new(
+            "orders".to_string(),
+            "./tests/external/orders/year=2026/*/*.csv".to_string(),
+            CsvConfig::default(),
+        )?;
+        let batch = table.read()?.next_batch()?.unwrap();
+        assert_eq!(batch.num_columns(), 3);
+        assert_eq!(batch.schema().field(2).name(), "month");
+
+        assert!(matches!(
+            CsvTable::new(
+                "orders".to_string(),
+                "./tests/external/orders/year=2027/*.csv".to_string(),
+                CsvConfig::default(),
+            ),
+            Err(StorageError::InvalidLocation(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_table_reads_compressed_files() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let zstd = dir.path().join("orders.csv.zst");
+        std::fs::write(&zstd, zstd::encode_all(&b"id,item\n1,pen\n2,ink\n"[..], 0)?)?;
+
+        // the compression is told by the extension, unless it is configured
+        for (location, config) in [
+            (zstd, CsvConfig::default()),
+            (
+                "./tests/external/sample.gzdata".into(),
+                CsvConfig::new().with_compression(FileCompression::Gzip),
+            ),
+        ] {
+            let table = CsvTable::new("t".to_string(), location.to_string_lossy().into(), config)?;
+            assert_eq!(
+                table.catalog().get_all_columns()[0].desc.data_type,
+                DataType::Int64
+            );
+            let batch = table.read()?.next_batch()?.unwrap();
+            assert!(batch.num_rows() >= 2);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_csv_table_scans_line_ranges() -> Result<(), StorageError> {
+        use futures::TryStreamExt;
+
+        let dir = tempfile::tempdir()?;
+        let filepath = dir.path().join("numbers.csv");
+        let lines = (0..100)
+            .map(|i| format!("{i},\"n {i}\"\n"))
+            .collect::<String>();
+        std::fs::write(&filepath, format!("id,name\n{lines}"))?;
+        let table = Arc::new(CsvTable::new(
+            "numbers".to_string(),
+            filepath.to_string_lossy().into(),
+            CsvConfig::new()
+                .with_batch_size(8)
+                .with_newlines_in_values(false),
+        )?);
+
+        let expected = table.clone().scan().try_collect::<Vec<_>>().await?;
+        let partitions = table.scan_partitions(4, &|p| vec![true; p.num_rows()]);
+        assert_eq!(partitions.len(), 4);
+        let mut ids = vec![];
+        for partition in partitions {
+            for batch in partition.try_collect::<Vec<_>>().await? {
+                assert_eq!(batch.schema(), expected[0].schema());
+                ids.extend(
+                    batch
+                        .column(0)
+                        .as_primitive::<Int64Type>()
+                        .values()
+                        .to_vec(),
+                );
+            }
+        }
+        // every line is read once, by the range it starts in
+        assert_eq!(ids, (0..100).collect::<Vec<_>>());
+        Ok(())
     }
 }
@@ -0,0 +1,1187 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fs::{self, File},
+    io::{BufReader, BufWriter, Cursor, ErrorKind, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use arrow::{
+    array::{RecordBatch, StringArray},
+    compute::cast,
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    error::ArrowError,
+    ipc::{
+        reader::{FileReader, StreamReader},
+        writer::{FileWriter, StreamWriter},
+    },
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog::{ColumnCatalog, ColumnDesc, RootCatalog, TableCatalog, TableId},
+    storage::{
+        mvcc::{TableWrite, TxnState, Versioned},
+        scan_reader, split_batches, split_evenly,
+        wal::WriteAheadLog,
+        BatchStream, CsvConfig, CsvTable, IpcConfig, IpcTable, JsonConfig, JsonTable,
+        PartitionPruner, Storage, StorageError, Table, TableReader, Transaction,
+    },
+    types::ScalarValue,
+};
+
+const MANIFEST_FILE: &str = "MANIFEST";
+const WAL_FILE: &str = "WAL";
+const SEGMENT_EXTENSION: &str = "arrow";
+/// checkpoint once the log grows beyond this size
+const CHECKPOINT_WAL_SIZE: u64 = 16 << 20;
+
+/// Durable storage in a data directory. The rows of every table are kept in immutable
+/// Arrow IPC segment files under `<dir>/tables/<n>/`, a directory numbered when the table
+/// is created rather than named after it, and the catalog together with the directory and
+/// the segments of each table is recorded in `<dir>/MANIFEST`.
+///
+/// Every change is first written to the write-ahead log `<dir>/WAL` and only then
+/// applied in memory, on top of the segments. A checkpoint writes the changed rows into
+/// new segments, atomically replaces the manifest and empties the log. Opening the
+/// directory replays the log records the manifest does not cover yet, so a crash at any
+/// point loses nothing but the record being written, and leaves at most unreferenced
+/// segment files behind, which are removed then.
+pub struct DiskStorage {
+    state: Arc<Mutex<DiskState>>,
+}
+
+struct DiskState {
+    dir: PathBuf,
+    catalog: RootCatalog,
+    tables: HashMap<TableId, Versioned<TableData>>,
+    /// the name of the directory of each table under `<dir>/tables`
+    table_dirs: HashMap<TableId, String>,
+    next_table_dir: u64,
+    next_segment_id: u64,
+    next_version: u64,
+    wal: WriteAheadLog,
+    /// the segments referenced by the saved manifest
+    checkpointed: Vec<Arc<Segment>>,
+}
+
+/// The rows of a table: the segment files followed by the batches committed since the
+/// last checkpoint.
+#[derive(Clone, Default)]
+struct TableData {
+    segments: Vec<Arc<Segment>>,
+    batches: Vec<RecordBatch>,
+}
+
+/// An immutable segment file. Once a checkpoint drops it from the manifest it is marked
+/// obsolete, and the file is removed when the last snapshot reading it is gone.
+struct Segment {
+    name: String,
+    path: PathBuf,
+    obsolete: AtomicBool,
+}
+
+impl Segment {
+    fn new(dir: &Path, table_dir_name: &str, name: String) -> Self {
+        Self {
+            path: table_dir(dir, table_dir_name).join(&name),
+            name,
+            obsolete: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Drop for Segment {
+    fn drop(&mut self) {
+        if self.obsolete.load(Ordering::Relaxed) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    next_table_dir: u64,
+    next_segment_id: u64,
+    /// log records below this lsn are already applied to the segments
+    next_lsn: u64,
+    tables: Vec<ManifestTable>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestTable {
+    name: String,
+    /// the directory of the table, which is the name of the table in the manifests written
+    /// before the directories were numbered
+    #[serde(default)]
+    dir: Option<String>,
+    columns: Vec<ManifestColumn>,
+    segments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestColumn {
+    name: String,
+    /// the arrow data type in its display form, e.g. `Int32`
+    data_type: String,
+    #[serde(default = "nullable_by_default")]
+    nullable: bool,
+    /// the default value in its display form, which is cast into the data type when read
+    #[serde(default)]
+    default: Option<String>,
+}
+
+fn nullable_by_default() -> bool {
+    true
+}
+
+/// A change to the storage, which is logged before it is applied.
+enum Change {
+    CreateTable {
+        name: String,
+        columns: Vec<ColumnCatalog>,
+    },
+    DropTable {
+        name: String,
+    },
+    /// the writes of a committed transaction
+    Commit(BTreeMap<TableId, TableWrite>),
+}
+
+/// The log record of a change, which is encoded as json with its length in front and
+/// followed by the batches of the change.
+#[derive(Serialize, Deserialize)]
+enum LogEntry {
+    CreateTable {
+        name: String,
+        columns: Vec<ManifestColumn>,
+    },
+    DropTable {
+        name: String,
+    },
+    /// the writes are followed by their batches, each as an Arrow IPC stream of `len` bytes
+    Commit {
+        writes: Vec<LoggedWrite>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct LoggedWrite {
+    name: String,
+    overwrite: bool,
+    len: usize,
+}
+
+#[derive(Clone)]
+pub struct DiskTransaction {
+    catalog: Arc<RootCatalog>,
+    state: Arc<Mutex<DiskState>>,
+    txn: Arc<Mutex<TxnState<TableData>>>,
+}
+
+pub struct DiskTable {
+    id: TableId,
+    catalog: TableCatalog,
+    txn: DiskTransaction,
+}
+
+/// Reads the rows of a table visible to a transaction. The segment files are opened
+/// upfront, so a concurrent checkpoint removing them does not affect the reader.
+pub struct DiskReader {
+    readers: VecDeque<FileReader<BufReader<File>>>,
+    batches: VecDeque<RecordBatch>,
+}
+
+impl DiskStorage {
+    /// open the storage on the data directory, creating the directory if it does not
+    /// exist and recovering the tables from its manifest and log otherwise.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let manifest = match fs::read(dir.join(MANIFEST_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| StorageError::Corrupted(format!("invalid manifest: {e}")))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => Manifest {
+                next_table_dir: 0,
+                next_segment_id: 0,
+                next_lsn: 0,
+                tables: vec![],
+            },
+            Err(e) => return Err(e.into()),
+        };
+        let (wal, records) = WriteAheadLog::open(&dir.join(WAL_FILE), manifest.next_lsn)?;
+        let mut state = DiskState::from_manifest(dir, manifest, wal)?;
+        remove_orphan_files(&state)?;
+
+        if !records.is_empty() {
+            for record in records {
+                state.apply(decode_change(&record.payload)?)?;
+            }
+            state.checkpoint()?;
+        }
+        Ok(Self {
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// write all changes logged so far into segment files and empty the log
+    pub fn checkpoint(&self) -> Result<(), StorageError> {
+        self.state.lock().unwrap().checkpoint()
+    }
+
+    fn insert_table(&self, catalog: &TableCatalog) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+        if state.catalog.tables.contains_key(&catalog.id) {
+            return Err(StorageError::TableExists(catalog.id.clone()));
+        }
+        state.write(Change::CreateTable {
+            name: catalog.id.clone(),
+            columns: catalog.get_all_columns(),
+        })
+    }
+
+    /// create a table holding all rows read from the source table
+    fn load_table(&self, catalog: &TableCatalog, source: &dyn Table) -> Result<(), StorageError> {
+        self.insert_table(catalog)?;
+        let txn = self.begin()?;
+        let table = txn.get_table(catalog.id.clone())?;
+        let mut reader = source.read()?;
+        while let Some(batch) = reader.next_batch()? {
+            table.append(batch)?;
+        }
+        txn.commit()
+    }
+}
+
+impl Storage for DiskStorage {
+    /// load all rows of the csv file into a new table
+    fn create_csv_table(
+        &self,
+        id: String,
+        filepath: String,
+        config: CsvConfig,
+    ) -> Result<(), StorageError> {
+        let csv_table = CsvTable::new(id.clone(), filepath, config)?;
+        self.load_table(csv_table.catalog(), &csv_table)
+    }
+
+    /// load all rows of the json files into a new table
+    fn create_json_table(
+        &self,
+        id: String,
+        location: String,
+        config: JsonConfig,
+    ) -> Result<(), StorageError> {
+        let json_table = JsonTable::new(id.clone(), location, config)?;
+        self.load_table(json_table.catalog(), &json_table)
+    }
+
+    /// load all rows of the Arrow IPC files into a new table
+    fn create_ipc_table(
+        &self,
+        id: String,
+        location: String,
+        config: IpcConfig,
+    ) -> Result<(), StorageError> {
+        let ipc_table = IpcTable::new(id.clone(), location, config)?;
+        self.load_table(ipc_table.catalog(), &ipc_table)
+    }
+
+    fn create_mem_table(&self, id: String, data: Vec<RecordBatch>) -> Result<(), StorageError> {
+        let columns = data
+            .first()
+            .map(|batch| {
+                batch
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|f| {
+                        let mut column = column_catalog(f.name(), f.data_type().clone());
+                        column.desc.nullable = f.is_nullable();
+                        column
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.insert_table(&table_catalog(id.clone(), columns))?;
+        let txn = self.begin()?;
+        txn.get_table(id)?.overwrite(data)?;
+        txn.commit()
+    }
+
+    fn create_table(&self, id: String, columns: Vec<ColumnCatalog>) -> Result<(), StorageError> {
+        self.insert_table(&table_catalog(id, columns))
+    }
+
+    fn drop_table(&self, id: String) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+        if !state.catalog.tables.contains_key(&id) {
+            return Err(StorageError::TableNotFound(id));
+        }
+        state.write(Change::DropTable { name: id })
+    }
+
+    fn begin(&self) -> Result<Arc<dyn Transaction>, StorageError> {
+        let state = self.state.lock().unwrap();
+        Ok(Arc::new(DiskTransaction {
+            catalog: Arc::new(state.catalog.clone()),
+            state: self.state.clone(),
+            txn: Arc::new(Mutex::new(TxnState::new(state.tables.clone()))),
+        }))
+    }
+
+    fn get_catalog(&self) -> RootCatalog {
+        self.state.lock().unwrap().catalog.clone()
+    }
+}
+
+impl Transaction for DiskTransaction {
+    fn get_table(&self, id: String) -> Result<Arc<dyn Table>, StorageError> {
+        let catalog = self
+            .catalog
+            .get_table_by_name(&id)
+            .ok_or(StorageError::TableNotFound(id.clone()))?;
+        Ok(Arc::new(DiskTable {
+            id,
+            catalog,
+            txn: self.clone(),
+        }))
+    }
+
+    fn commit(&self) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let writes = self.txn.lock().unwrap().commit(&state.tables)?;
+        if writes.is_empty() {
+            return Ok(());
+        }
+        state.write(Change::Commit(writes))
+    }
+
+    fn rollback(&self) {
+        self.txn.lock().unwrap().rollback();
+    }
+}
+
+impl DiskTable {
+    /// take the field names and types of the table, the columns are already in its order
+    fn conform_batches(&self, batches: Vec<RecordBatch>) -> Result<Vec<RecordBatch>, StorageError> {
+        let schema = arrow_schema(&self.catalog);
+        let batches = batches
+            .into_iter()
+            .filter(|b| b.num_rows() > 0)
+            .map(|b| RecordBatch::try_new(schema.clone(), b.columns().to_vec()))
+            .collect::<Result<_, _>>()?;
+        Ok(batches)
+    }
+}
+
+impl DiskTable {
+    /// the segments and then the batches holding the rows visible to the transaction
+    fn visible(&self) -> Result<(Vec<Arc<Segment>>, Vec<RecordBatch>), StorageError> {
+        let (data, written) = self.txn.txn.lock().unwrap().visible(&self.id)?;
+        let data = data.unwrap_or_default();
+        Ok((
+            data.segments,
+            data.batches.into_iter().chain(written).collect(),
+        ))
+    }
+}
+
+impl Table for DiskTable {
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+        let (segments, batches) = self.visible()?;
+        Ok(Box::new(DiskReader::open(&segments, batches)?))
+    }
+
+    /// split the segments between the first partitions and the rows of the batches
+    /// committed since the last checkpoint between the others
+    fn scan_partitions(
+        self: Arc<Self>,
+        partitions: usize,
+        _pruner: &PartitionPruner<'_>,
+    ) -> Vec<BatchStream> {
+        let (segments, batches) = match self.visible() {
+            Ok(visible) => visible,
+            Err(e) => return vec![futures::stream::iter([Err(e)]).boxed()],
+        };
+        let segment_partitions = if segments.is_empty() {
+            vec![]
+        } else {
+            split_evenly(segments, partitions)
+        };
+        let batch_partitions = if batches.is_empty() && !segment_partitions.is_empty() {
+            vec![]
+        } else {
+            let partitions = partitions.saturating_sub(segment_partitions.len()).max(1);
+            split_batches(batches, partitions)
+        };
+        segment_partitions
+            .into_iter()
+            .map(|segments| {
+                // the segments are kept until they are read, like the snapshot keeps them
+                scan_reader(move || Ok(Box::new(DiskReader::open(&segments, vec![])?)))
+            })
+            .chain(
+                batch_partitions
+                    .into_iter()
+                    .map(|batches| futures::stream::iter(batches.into_iter().map(Ok)).boxed()),
+            )
+            .collect()
+    }
+
+    fn append(&self, batch: RecordBatch) -> Result<(), StorageError> {
+        let batches = self.conform_batches(vec![batch])?;
+        self.txn.txn.lock().unwrap().append(&self.id, batches)
+    }
+
+    fn overwrite(&self, batches: Vec<RecordBatch>) -> Result<(), StorageError> {
+        let batches = self.conform_batches(batches)?;
+        self.txn.txn.lock().unwrap().overwrite(&self.id, batches)
+    }
+}
+
+impl DiskReader {
+    fn open(segments: &[Arc<Segment>], batches: Vec<RecordBatch>) -> Result<Self, StorageError> {
+        let readers = segments
+            .iter()
+            .map(|segment| {
+                let file = File::open(&segment.path)?;
+                Ok(FileReader::try_new(BufReader::new(file), None)?)
+            })
+            .collect::<Result<_, StorageError>>()?;
+        Ok(Self {
+            readers,
+            batches: batches.into(),
+        })
+    }
+}
+
+impl TableReader for DiskReader {
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError> {
+        while let Some(reader) = self.readers.front_mut() {
+            match reader.next() {
+                Some(batch) => return Ok(Some(batch?)),
+                None => {
+                    self.readers.pop_front();
+                }
+            }
+        }
+        Ok(self.batches.pop_front())
+    }
+}
+
+impl DiskState {
+    fn from_manifest(
+        dir: PathBuf,
+        manifest: Manifest,
+        wal: WriteAheadLog,
+    ) -> Result<Self, StorageError> {
+        let mut catalog = RootCatalog::new();
+        let mut tables = HashMap::new();
+        let mut table_dirs = HashMap::new();
+        let mut checkpointed = vec![];
+        for table in manifest.tables {
+            let columns = from_manifest_columns(&table.columns)?;
+            catalog.tables.insert(
+                table.name.clone(),
+                table_catalog(table.name.clone(), columns),
+            );
+            let table_dir_name = table.dir.unwrap_or_else(|| table.name.clone());
+            let segments = table
+                .segments
+                .into_iter()
+                .map(|name| Arc::new(Segment::new(&dir, &table_dir_name, name)))
+                .collect::<Vec<_>>();
+            table_dirs.insert(table.name.clone(), table_dir_name);
+            checkpointed.extend(segments.iter().cloned());
+            let data = TableData {
+                segments,
+                batches: vec![],
+            };
+            tables.insert(table.name, Versioned { version: 0, data });
+        }
+        Ok(Self {
+            dir,
+            catalog,
+            tables,
+            table_dirs,
+            next_table_dir: manifest.next_table_dir,
+            next_segment_id: manifest.next_segment_id,
+            next_version: 1,
+            wal,
+            checkpointed,
+        })
+    }
+
+    /// log the change, and apply it once the log record is durable
+    fn write(&mut self, change: Change) -> Result<(), StorageError> {
+        self.wal.append(&encode_change(&change)?)?;
+        self.apply(change)?;
+        if self.wal.size() >= CHECKPOINT_WAL_SIZE {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, change: Change) -> Result<(), StorageError> {
+        let version = self.next_version;
+        self.next_version += 1;
+        match change {
+            Change::CreateTable { name, columns } => {
+                self.catalog
+                    .tables
+                    .insert(name.clone(), table_catalog(name.clone(), columns));
+                let data = TableData::default();
+                self.tables
+                    .insert(name.clone(), Versioned { version, data });
+                let table_dir_name = self.new_table_dir_name();
+                self.table_dirs.insert(name, table_dir_name);
+            }
+            Change::DropTable { name } => {
+                self.catalog.tables.remove(&name);
+                self.tables.remove(&name);
+                self.table_dirs.remove(&name);
+            }
+            Change::Commit(writes) => {
+                for (name, write) in writes {
+                    let table = self
+                        .tables
+                        .get_mut(&name)
+                        .ok_or(StorageError::TableNotFound(name))?;
+                    table.version = version;
+                    match write {
+                        TableWrite::Append(batches) => table.data.batches.extend(batches),
+                        TableWrite::Overwrite(batches) => {
+                            table.data = TableData {
+                                segments: vec![],
+                                batches,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// the next number not naming the directory of a table yet, which is also replayed
+    /// from the log in the same order
+    fn new_table_dir_name(&mut self) -> String {
+        loop {
+            let name = format!("{:010}", self.next_table_dir);
+            self.next_table_dir += 1;
+            if !self.table_dirs.values().any(|dir| *dir == name) {
+                return name;
+            }
+        }
+    }
+
+    /// Write the batches of every table into new segment files and save the manifest
+    /// covering all logged records, after which the log is emptied. The segments no
+    /// longer referenced are removed as soon as no snapshot reads them.
+    fn checkpoint(&mut self) -> Result<(), StorageError> {
+        for (id, table) in self.tables.iter_mut() {
+            if table.data.batches.is_empty() {
+                continue;
+            }
+            let table_dir_name = &self.table_dirs[id];
+            fs::create_dir_all(table_dir(&self.dir, table_dir_name))?;
+            for batch in std::mem::take(&mut table.data.batches) {
+                let name = format!("{:010}.{SEGMENT_EXTENSION}", self.next_segment_id);
+                self.next_segment_id += 1;
+                let segment = Segment::new(&self.dir, table_dir_name, name);
+                write_segment(&segment.path, &batch)?;
+                table.data.segments.push(Arc::new(segment));
+            }
+        }
+        self.save()?;
+        self.wal.reset()?;
+
+        let live = self
+            .tables
+            .values()
+            .flat_map(|table| table.data.segments.iter().cloned())
+            .collect::<Vec<_>>();
+        let live_names: HashSet<_> = live.iter().map(|s| s.name.as_str()).collect();
+        for segment in &self.checkpointed {
+            if !live_names.contains(segment.name.as_str()) {
+                segment.obsolete.store(true, Ordering::Relaxed);
+            }
+        }
+        self.checkpointed = live;
+
+        // the directories of dropped tables are removed once emptied
+        let tables_dir = self.dir.join("tables");
+        if !tables_dir.is_dir() {
+            return Ok(());
+        }
+        let live_dirs: HashSet<_> = self.table_dirs.values().map(String::as_str).collect();
+        for entry in fs::read_dir(tables_dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if !live_dirs.contains(name) {
+                let _ = fs::remove_dir(&path);
+            }
+        }
+        Ok(())
+    }
+
+    fn to_manifest(&self) -> Manifest {
+        let mut tables = self
+            .catalog
+            .tables
+            .values()
+            .map(|table| ManifestTable {
+                name: table.id.clone(),
+                dir: self.table_dirs.get(&table.id).cloned(),
+                columns: to_manifest_columns(&table.get_all_columns()),
+                segments: self
+                    .tables
+                    .get(&table.id)
+                    .map(|t| t.data.segments.iter().map(|s| s.name.clone()).collect())
+                    .unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        Manifest {
+            next_table_dir: self.next_table_dir,
+            next_segment_id: self.next_segment_id,
+            next_lsn: self.wal.next_lsn(),
+            tables,
+        }
+    }
+
+    /// atomically replace the manifest by writing a temporary file and renaming it
+    fn save(&self) -> Result<(), StorageError> {
+        let bytes = serde_json::to_vec_pretty(&self.to_manifest())
+            .map_err(|e| StorageError::Corrupted(format!("invalid manifest: {e}")))?;
+        let tmp_path = self.dir.join(format!("{MANIFEST_FILE}.tmp"));
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(tmp_path, self.dir.join(MANIFEST_FILE))?;
+        Ok(())
+    }
+}
+
+fn write_segment(path: &Path, batch: &RecordBatch) -> Result<(), StorageError> {
+    let file = File::create(path)?;
+    let mut writer = FileWriter::try_new(BufWriter::new(file), &batch.schema())?;
+    writer.write(batch)?;
+    writer.finish()?;
+    let file = writer
+        .into_inner()?
+        .into_inner()
+        .map_err(|e| e.into_error())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn encode_change(change: &Change) -> Result<Vec<u8>, StorageError> {
+    let mut streams = vec![];
+    let entry = match change {
+        Change::CreateTable { name, columns } => LogEntry::CreateTable {
+            name: name.clone(),
+            columns: to_manifest_columns(columns),
+        },
+        Change::DropTable { name } => LogEntry::DropTable { name: name.clone() },
+        Change::Commit(writes) => {
+            let mut logged = vec![];
+            for (name, write) in writes {
+                let stream = encode_batches(write.batches())?;
+                logged.push(LoggedWrite {
+                    name: name.clone(),
+                    overwrite: matches!(write, TableWrite::Overwrite(_)),
+                    len: stream.len(),
+                });
+                streams.push(stream);
+            }
+            LogEntry::Commit { writes: logged }
+        }
+    };
+    let json = serde_json::to_vec(&entry)
+        .map_err(|e| StorageError::Corrupted(format!("invalid log entry: {e}")))?;
+    let mut payload = (json.len() as u32).to_le_bytes().to_vec();
+    payload.extend(json);
+    payload.extend(streams.concat());
+    Ok(payload)
+}
+
+fn decode_change(payload: &[u8]) -> Result<Change, StorageError> {
+    let corrupted = || StorageError::Corrupted("invalid log entry".to_string());
+    let len = u32::from_le_bytes(payload.get(..4).ok_or_else(corrupted)?.try_into().unwrap());
+    let json = payload.get(4..4 + len as usize).ok_or_else(corrupted)?;
+    let entry = serde_json::from_slice(json)
+        .map_err(|e| StorageError::Corrupted(format!("invalid log entry: {e}")))?;
+    let mut rest = &payload[4 + len as usize..];
+    Ok(match entry {
+        LogEntry::CreateTable { name, columns } => Change::CreateTable {
+            name,
+            columns: from_manifest_columns(&columns)?,
+        },
+        LogEntry::DropTable { name } => Change::DropTable { name },
+        LogEntry::Commit { writes } => {
+            let mut decoded = BTreeMap::new();
+            for write in writes {
+                let stream = rest.get(..write.len).ok_or_else(corrupted)?;
+                rest = &rest[write.len..];
+                let batches = decode_batches(stream)?;
+                let write_batches = if write.overwrite {
+                    TableWrite::Overwrite(batches)
+                } else {
+                    TableWrite::Append(batches)
+                };
+                decoded.insert(write.name, write_batches);
+            }
+            Change::Commit(decoded)
+        }
+    })
+}
+
+fn encode_batches(batches: &[RecordBatch]) -> Result<Vec<u8>, StorageError> {
+    let mut bytes = vec![];
+    if let Some(first) = batches.first() {
+        let mut writer = StreamWriter::try_new(&mut bytes, &first.schema())?;
+        for batch in batches {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+    }
+    Ok(bytes)
+}
+
+fn decode_batches(bytes: &[u8]) -> Result<Vec<RecordBatch>, StorageError> {
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+    let batches = StreamReader::try_new(Cursor::new(bytes), None)?.collect::<Result<_, _>>()?;
+    Ok(batches)
+}
+
+/// Remove the table directories and segment files not referenced by the manifest, which
+/// are left by dropped tables, replaced segments and checkpoints interrupted by a crash.
+fn remove_orphan_files(state: &DiskState) -> Result<(), StorageError> {
+    let tables_dir = state.dir.join("tables");
+    if !tables_dir.is_dir() {
+        return Ok(());
+    }
+    let tables: HashMap<_, _> = state
+        .table_dirs
+        .iter()
+        .map(|(id, dir)| (dir.as_str(), &state.tables[id]))
+        .collect();
+    for entry in fs::read_dir(tables_dir)? {
+        let table_dir = entry?.path();
+        let name = table_dir.file_name().and_then(|n| n.to_str());
+        let Some(table) = name.and_then(|name| tables.get(name)) else {
+            fs::remove_dir_all(&table_dir)?;
+            continue;
+        };
+        let live: HashSet<_> = table
+            .data
+            .segments
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        for entry in fs::read_dir(&table_dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if path.extension().is_some_and(|e| e == SEGMENT_EXTENSION) && !live.contains(name) {
+                fs::remove_file(&path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn table_dir(dir: &Path, table_dir_name: &str) -> PathBuf {
+    dir.join("tables").join(table_dir_name)
+}
+
+fn arrow_schema(catalog: &TableCatalog) -> SchemaRef {
+    let fields = catalog
+        .get_all_columns()
+        .into_iter()
+        .map(|c| Field::new(c.desc.name, c.desc.data_type, c.desc.nullable))
+        .collect::<Vec<_>>();
+    Arc::new(Schema::new(fields))
+}
+
+fn to_manifest_columns(columns: &[ColumnCatalog]) -> Vec<ManifestColumn> {
+    columns
+        .iter()
+        .map(|c| ManifestColumn {
+            name: c.desc.name.clone(),
+            data_type: c.desc.data_type.to_string(),
+            nullable: c.desc.nullable,
+            default: c.desc.default.as_ref().map(|v| v.to_string()),
+        })
+        .collect()
+}
+
+fn from_manifest_columns(columns: &[ManifestColumn]) -> Result<Vec<ColumnCatalog>, StorageError> {
+    let columns = columns
+        .iter()
+        .map(|c| {
+            let mut column = column_catalog(&c.name, DataType::from_str(&c.data_type)?);
+            column.desc.nullable = c.nullable;
+            column.desc.default = match &c.default {
+                Some(default) => {
+                    let value = cast(
+                        &StringArray::from(vec![default.clone()]),
+                        &column.desc.data_type,
+                    )?;
+                    Some(ScalarValue::try_from_array(&value, 0)?)
+                }
+                None => None,
+            };
+            Ok(column)
+        })
+        .collect::<Result<_, ArrowError>>()?;
+    Ok(columns)
+}
+
+fn column_catalog(name: &str, data_type: DataType) -> ColumnCatalog {
+    ColumnCatalog {
+        id: name.to_string(),
+        desc: ColumnDesc {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+            default: None,
+        },
+    }
+}
+
+fn table_catalog(id: TableId, columns: Vec<ColumnCatalog>) -> TableCatalog {
+    TableCatalog {
+        id: id.clone(),
+        name: id,
+        column_ids: columns.iter().map(|c| c.id.clone()).collect(),
+        columns: columns.into_iter().map(|c| (c.id.clone(), c)).collect(),
+        statistics: None,
+    }
+}
+
+#[cfg(test)]
+mod storage_test {
+    use arrow::array::{Int32Array, StringArray};
+
+    use super::*;
+
+    fn build_record_batch(a: Vec<i32>, b: Vec<&str>) -> Result<RecordBatch, StorageError> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(a)),
+                Arc::new(StringArray::from(b)),
+            ],
+        )?)
+    }
+
+    fn columns() -> Vec<ColumnCatalog> {
+        vec![
+            column_catalog("a", DataType::Int32),
+            column_catalog("b", DataType::Utf8),
+        ]
+    }
+
+    fn append(storage: &DiskStorage, id: &str, batch: RecordBatch) -> Result<(), StorageError> {
+        let txn = storage.begin()?;
+        txn.get_table(id.to_string())?.append(batch)?;
+        txn.commit()
+    }
+
+    fn overwrite(storage: &DiskStorage, id: &str, batch: RecordBatch) -> Result<(), StorageError> {
+        let txn = storage.begin()?;
+        txn.get_table(id.to_string())?.overwrite(vec![batch])?;
+        txn.commit()
+    }
+
+    fn count_rows(txn: &Arc<dyn Transaction>, id: &str) -> Result<usize, StorageError> {
+        let mut reader = txn.get_table(id.to_string())?.read()?;
+        let mut rows = 0;
+        while let Some(batch) = reader.next_batch()? {
+            rows += batch.num_rows();
+        }
+        Ok(rows)
+    }
+
+    fn num_rows(storage: &DiskStorage, id: &str) -> Result<usize, StorageError> {
+        count_rows(&storage.begin()?, id)
+    }
+
+    fn table_path(storage: &DiskStorage, id: &str) -> PathBuf {
+        let state = storage.state.lock().unwrap();
+        table_dir(&state.dir, &state.table_dirs[id])
+    }
+
+    /// copy the data directory as a crashed process would leave it
+    fn copy_dir(from: &Path, to: &Path) -> Result<(), StorageError> {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let path = entry?.path();
+            let target = to.join(path.file_name().unwrap());
+            if path.is_dir() {
+                copy_dir(&path, &target)?;
+            } else {
+                fs::copy(&path, &target)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_storage_reloads_tables() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let id = "test".to_string();
+        {
+            let storage = DiskStorage::open(dir.path())?;
+            storage.create_table(id.clone(), columns())?;
+            append(
+                &storage,
+                &id,
+                build_record_batch(vec![1, 2], vec!["x", "y"])?,
+            )?;
+            storage.checkpoint()?;
+            append(&storage, &id, build_record_batch(vec![3], vec!["z"])?)?;
+        }
+
+        let storage = DiskStorage::open(dir.path())?;
+        let catalog = storage.get_catalog().get_table_by_name(&id).unwrap();
+        let columns = catalog.get_all_columns();
+        assert_eq!(columns[0].desc.name, "a");
+        assert_eq!(columns[1].desc.data_type, DataType::Utf8);
+        assert_eq!(num_rows(&storage, &id)?, 3);
+        // the log is replayed into segments on open
+        assert_eq!(fs::metadata(dir.path().join(WAL_FILE))?.len(), 0);
+        assert_eq!(fs::read_dir(table_path(&storage, &id))?.count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_storage_overwrite_and_drop() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let id = "test".to_string();
+        let storage = DiskStorage::open(dir.path())?;
+        storage.create_mem_table(
+            id.clone(),
+            vec![build_record_batch(vec![1, 2, 3], vec!["x", "y", "z"])?],
+        )?;
+        assert!(matches!(
+            storage.create_table(id.clone(), vec![]),
+            Err(StorageError::TableExists(_))
+        ));
+        storage.checkpoint()?;
+        let path = table_path(&storage, &id);
+
+        let snapshot = storage.begin()?;
+        overwrite(&storage, &id, build_record_batch(vec![4], vec!["w"])?)?;
+        storage.checkpoint()?;
+        // the replaced segment is kept while a snapshot reads it
+        assert_eq!(fs::read_dir(&path)?.count(), 2);
+        assert_eq!(count_rows(&snapshot, &id)?, 3);
+        drop(snapshot);
+        assert_eq!(fs::read_dir(&path)?.count(), 1);
+        assert_eq!(num_rows(&DiskStorage::open(dir.path())?, &id)?, 1);
+
+        storage.drop_table(id.clone())?;
+        storage.checkpoint()?;
+        assert!(!path.exists());
+        let storage = DiskStorage::open(dir.path())?;
+        assert!(storage.get_catalog().get_table_by_name(&id).is_none());
+        assert!(matches!(
+            storage.drop_table(id),
+            Err(StorageError::TableNotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_storage_removes_orphan_segments() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let id = "test".to_string();
+        let storage = DiskStorage::open(dir.path())?;
+        storage.create_table(id.clone(), columns())?;
+        storage.checkpoint()?;
+        // a segment written by a checkpoint which crashed before saving the manifest
+        let path = table_path(&storage, &id);
+        fs::create_dir_all(&path)?;
+        let orphan = path.join("0000000099.arrow");
+        File::create(&orphan)?;
+
+        let storage = DiskStorage::open(dir.path())?;
+        assert!(!orphan.exists());
+        assert_eq!(num_rows(&storage, &id)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_storage_keeps_tables_in_numbered_dirs() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let data_dir = dir.path().join("data");
+        let names = ["../x", "a/b", "."];
+        {
+            let storage = DiskStorage::open(&data_dir)?;
+            for (i, name) in names.iter().enumerate() {
+                storage.create_table(name.to_string(), columns())?;
+                append(
+                    &storage,
+                    name,
+                    build_record_batch(vec![i as i32], vec!["x"])?,
+                )?;
+            }
+            storage.checkpoint()?;
+            storage.drop_table("a/b".to_string())?;
+            storage.create_table("a".to_string(), columns())?;
+            append(
+                &storage,
+                "a",
+                build_record_batch(vec![1, 2], vec!["x", "y"])?,
+            )?;
+        }
+
+        let storage = DiskStorage::open(&data_dir)?;
+        assert_eq!(num_rows(&storage, "../x")?, 1);
+        assert_eq!(num_rows(&storage, ".")?, 1);
+        assert_eq!(num_rows(&storage, "a")?, 2);
+        storage.checkpoint()?;
+        // nothing is written outside the data directory nor named after a table
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+        let mut table_dirs = fs::read_dir(data_dir.join("tables"))?
+            .map(|entry| Ok(entry?.file_name().into_string().unwrap()))
+            .collect::<Result<Vec<_>, StorageError>>()?;
+        table_dirs.sort();
+        assert_eq!(table_dirs, ["0000000000", "0000000002", "0000000003"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_storage_recovers_from_truncated_log() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let id = "test".to_string();
+        let wal_path = dir.path().join(WAL_FILE);
+        // the log size after creating the table and after each change
+        let mut ends = vec![];
+        {
+            let storage = DiskStorage::open(dir.path())?;
+            storage.create_table(id.clone(), columns())?;
+            ends.push(fs::metadata(&wal_path)?.len());
+            for i in 0..3 {
+                append(&storage, &id, build_record_batch(vec![i], vec!["x"])?)?;
+                ends.push(fs::metadata(&wal_path)?.len());
+            }
+            overwrite(
+                &storage,
+                &id,
+                build_record_batch(vec![7, 8], vec!["y", "z"])?,
+            )?;
+            ends.push(fs::metadata(&wal_path)?.len());
+        }
+        // rows of the table after each logged change
+        let expected_rows = [0, 1, 2, 3, 2];
+        let wal = fs::read(&wal_path)?;
+        assert_eq!(*ends.last().unwrap() as usize, wal.len());
+
+        for offset in 0..=wal.len() {
+            let crashed = tempfile::tempdir()?;
+            copy_dir(dir.path(), crashed.path())?;
+            fs::write(crashed.path().join(WAL_FILE), &wal[..offset])?;
+
+            let storage = DiskStorage::open(crashed.path())?;
+            let complete = ends.iter().filter(|end| **end as usize <= offset).count();
+            if complete == 0 {
+                assert!(num_rows(&storage, &id).is_err(), "offset {offset}");
+            } else {
+                let rows = num_rows(&storage, &id)?;
+                assert_eq!(rows, expected_rows[complete - 1], "offset {offset}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_storage_does_not_replay_checkpointed_log() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let id = "test".to_string();
+        let storage = DiskStorage::open(dir.path())?;
+        storage.create_table(id.clone(), columns())?;
+        append(
+            &storage,
+            &id,
+            build_record_batch(vec![1, 2], vec!["x", "y"])?,
+        )?;
+        let wal = fs::read(dir.path().join(WAL_FILE))?;
+        storage.checkpoint()?;
+        drop(storage);
+
+        // a crash after saving the manifest but before emptying the log
+        fs::write(dir.path().join(WAL_FILE), wal)?;
+        let storage = DiskStorage::open(dir.path())?;
+        assert_eq!(num_rows(&storage, &id)?, 2);
+        append(&storage, &id, build_record_batch(vec![3], vec!["z"])?)?;
+        drop(storage);
+        assert_eq!(num_rows(&DiskStorage::open(dir.path())?, &id)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_storage_commits_atomically() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let storage = DiskStorage::open(dir.path())?;
+        storage.create_table("t1".to_string(), columns())?;
+        storage.create_table("t2".to_string(), columns())?;
+        let txn = storage.begin()?;
+        txn.get_table("t1".to_string())?
+            .append(build_record_batch(vec![1], vec!["x"])?)?;
+        txn.get_table("t2".to_string())?
+            .append(build_record_batch(vec![2, 3], vec!["y", "z"])?)?;
+        // uncommitted writes are neither visible to others nor logged
+        assert_eq!(num_rows(&storage, "t1")?, 0);
+        assert_eq!(count_rows(&txn, "t2")?, 2);
+        let wal_len = fs::metadata(dir.path().join(WAL_FILE))?.len();
+
+        // a conflicting commit fails and leaves the log as it is
+        let other = storage.begin()?;
+        append(&storage, "t2", build_record_batch(vec![4], vec!["w"])?)?;
+        other
+            .get_table("t2".to_string())?
+            .append(build_record_batch(vec![5], vec!["v"])?)?;
+        assert!(matches!(
+            other.commit(),
+            Err(StorageError::WriteConflict(_))
+        ));
+        assert!(fs::metadata(dir.path().join(WAL_FILE))?.len() > wal_len);
+        let wal_len = fs::metadata(dir.path().join(WAL_FILE))?.len();
+        assert!(matches!(txn.commit(), Err(StorageError::WriteConflict(_))));
+        assert_eq!(fs::metadata(dir.path().join(WAL_FILE))?.len(), wal_len);
+
+        let txn = storage.begin()?;
+        txn.get_table("t1".to_string())?
+            .append(build_record_batch(vec![1], vec!["x"])?)?;
+        txn.get_table("t2".to_string())?
+            .append(build_record_batch(vec![2, 3], vec!["y", "z"])?)?;
+        txn.commit()?;
+        drop(storage);
+        let storage = DiskStorage::open(dir.path())?;
+        assert_eq!(num_rows(&storage, "t1")?, 1);
+        assert_eq!(num_rows(&storage, "t2")?, 3);
+        Ok(())
+    }
+}
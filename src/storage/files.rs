@@ -0,0 +1,399 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{new_null_array, ArrayRef, Int64Array, RecordBatch, StringArray},
+    datatypes::{DataType, Field, FieldRef, Schema, SchemaRef},
+};
+
+use crate::{
+    catalog::{ColumnCatalog, ColumnDesc, TableCatalog, TableId},
+    storage::{
+        open_file, split_evenly, FileCompression, PartitionPruner, StorageError, TableReader,
+    },
+};
+
+/// The batches read from a file of a table.
+pub(crate) type FileBatches = Box<dyn Iterator<Item = Result<RecordBatch, StorageError>> + Send>;
+
+/// Opens a file of a table for reading its batches.
+type OpenFile = dyn Fn(&DataFile) -> Result<FileBatches, StorageError> + Send;
+
+/// A file of a table, whose directories give the values of the partition columns.
+#[derive(Clone)]
+pub(crate) struct DataFile {
+    pub path: PathBuf,
+    partition_values: Vec<String>,
+    /// the byte range of a file split between partitions of a scan, which reads the lines
+    /// starting in the range
+    range: Option<Range<u64>>,
+}
+
+impl DataFile {
+    /// open the file, or the lines of its range, decoding it with the compression
+    pub fn open(
+        &self,
+        compression: Option<FileCompression>,
+    ) -> Result<Box<dyn Read + Send>, StorageError> {
+        match &self.range {
+            None => open_file(&self.path, compression),
+            Some(range) => open_lines(&self.path, range),
+        }
+    }
+
+    /// whether the file is read from its first line, which is the header of a csv file
+    pub fn is_read_from_start(&self) -> bool {
+        self.range.as_ref().is_none_or(|range| range.start == 0)
+    }
+}
+
+/// The files of a table at a location, which is a single file, a directory or a glob of
+/// files. The files of a directory may be laid out in Hive-style partitions such as
+/// `year=2026/month=10/`, whose keys become columns of the table after the columns of the
+/// files.
+#[derive(Clone)]
+pub(crate) struct TableFiles {
+    pub files: Vec<DataFile>,
+    partition_fields: Vec<FieldRef>,
+}
+
+impl TableFiles {
+    pub fn list(location: &str) -> Result<Self, StorageError> {
+        let (base, paths) = list_files(location)?;
+        let mut partition_keys = None;
+        let mut files = vec![];
+        for path in paths {
+            let (keys, partition_values) = partition_of(&base, &path);
+            if *partition_keys.get_or_insert_with(|| keys.clone()) != keys {
+                return Err(StorageError::InvalidLocation(format!(
+                    "{} is not partitioned by {:?} like the other files",
+                    path.display(),
+                    partition_keys.unwrap()
+                )));
+            }
+            files.push(DataFile {
+                path,
+                partition_values,
+                range: None,
+            });
+        }
+        let partition_fields = partition_keys
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| {
+                // a partition column is an integer when all of its values are
+                let data_type = if files
+                    .iter()
+                    .all(|f| f.partition_values[i].parse::<i64>().is_ok())
+                {
+                    DataType::Int64
+                } else {
+                    DataType::Utf8
+                };
+                Arc::new(Field::new(key, data_type, false))
+            })
+            .collect();
+        Ok(Self {
+            files,
+            partition_fields,
+        })
+    }
+
+    pub fn is_partitioned(&self) -> bool {
+        !self.partition_fields.is_empty()
+    }
+
+    /// the columns of the files followed by the partition columns
+    pub fn table_schema(&self, file_schema: &Schema) -> Result<SchemaRef, StorageError> {
+        let mut fields = file_schema.fields().to_vec();
+        for field in &self.partition_fields {
+            if file_schema.field_with_name(field.name()).is_ok() {
+                return Err(StorageError::InvalidLocation(format!(
+                    "partition column {} is also a column of the files",
+                    field.name()
+                )));
+            }
+            fields.push(field.clone());
+        }
+        Ok(Arc::new(Schema::new(fields)))
+    }
+
+    /// a row for each file, with its partition values and nulls for the other columns
+    fn partitions(&self, file_schema: &Schema) -> Result<RecordBatch, StorageError> {
+        let num_files = self.files.len();
+        let mut columns = file_schema
+            .fields()
+            .iter()
+            .map(|f| new_null_array(f.data_type(), num_files))
+            .collect::<Vec<_>>();
+        for (i, field) in self.partition_fields.iter().enumerate() {
+            let values = self.files.iter().map(|f| f.partition_values[i].as_str());
+            columns.push(partition_column(field.data_type(), values));
+        }
+        let fields = file_schema
+            .fields()
+            .iter()
+            .chain(&self.partition_fields)
+            .map(|f| f.as_ref().clone().with_nullable(true))
+            .collect::<Vec<_>>();
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+
+    /// the files of the partitions the pruner keeps, or all of them when the partitions
+    /// cannot be told
+    pub fn prune(&self, file_schema: &Schema, pruner: &PartitionPruner<'_>) -> Self {
+        let Ok(partitions) = self.partitions(file_schema) else {
+            return self.clone();
+        };
+        let files = self
+            .files
+            .iter()
+            .zip(pruner(&partitions))
+            .filter(|(_, keep)| *keep)
+            .map(|(file, _)| file.clone())
+            .collect();
+        Self {
+            files,
+            partition_fields: self.partition_fields.clone(),
+        }
+    }
+
+    /// Split the files into at most `n` parts to be read concurrently, which hold the files
+    /// in order. When there are fewer files than parts, the files of lines which
+    /// `splits_lines` tells are split into byte ranges of about the same size, each read
+    /// from the first line starting in it.
+    pub fn split(&self, n: usize, splits_lines: impl Fn(&Path) -> bool) -> Vec<Self> {
+        let mut files = self.files.clone();
+        if files.len() < n {
+            let ranges_per_file = n.div_ceil(files.len().max(1)) as u64;
+            files = files
+                .into_iter()
+                .flat_map(|file| {
+                    let len = match std::fs::metadata(&file.path) {
+                        Ok(metadata) if metadata.len() > 0 && splits_lines(&file.path) => {
+                            metadata.len()
+                        }
+                        _ => return vec![file],
+                    };
+                    let range_len = len.div_ceil(ranges_per_file).max(1);
+                    (0..len)
+                        .step_by(range_len as usize)
+                        .map(|start| DataFile {
+                            range: Some(start..(start + range_len).min(len)),
+                            ..file.clone()
+                        })
+                        .collect()
+                })
+                .collect();
+        }
+        split_evenly(files, n)
+            .into_iter()
+            .map(|files| Self {
+                files,
+                partition_fields: self.partition_fields.clone(),
+            })
+            .collect()
+    }
+
+    /// read the files one after another with `open`, appending the partition values of
+    /// each file to its batches of the table schema
+    pub fn read(
+        &self,
+        schema: SchemaRef,
+        open: impl Fn(&DataFile) -> Result<FileBatches, StorageError> + Send + 'static,
+    ) -> FilesReader {
+        FilesReader {
+            files: self.files.clone().into_iter(),
+            current: None,
+            open: Box::new(open),
+            schema,
+        }
+    }
+}
+
+pub(crate) struct FilesReader {
+    files: std::vec::IntoIter<DataFile>,
+    /// the batches of the current file and its partition values
+    current: Option<(FileBatches, Vec<String>)>,
+    open: Box<OpenFile>,
+    schema: SchemaRef,
+}
+
+impl FilesReader {
+    fn with_partitions(
+        &self,
+        batch: RecordBatch,
+        partition_values: &[String],
+    ) -> Result<RecordBatch, StorageError> {
+        if partition_values.is_empty() {
+            return Ok(batch);
+        }
+        let partition_fields = &self.schema.fields()[batch.num_columns()..];
+        let mut columns = batch.columns().to_vec();
+        for (field, value) in partition_fields.iter().zip(partition_values) {
+            let values = std::iter::repeat_n(value.as_str(), batch.num_rows());
+            columns.push(partition_column(field.data_type(), values));
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl TableReader for FilesReader {
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError> {
+        loop {
+            let Some((batches, partition_values)) = &mut self.current else {
+                let Some(file) = self.files.next() else {
+                    return Ok(None);
+                };
+                self.current = Some(((self.open)(&file)?, file.partition_values));
+                continue;
+            };
+            let Some(batch) = batches.next().transpose()? else {
+                self.current = None;
+                continue;
+            };
+            let partition_values = partition_values.clone();
+            return Ok(Some(self.with_partitions(batch, &partition_values)?));
+        }
+    }
+}
+
+/// the catalog of a table over files, with the columns of the schema
+pub(crate) fn infer_catalog(id: TableId, schema: &Schema) -> TableCatalog {
+    let mut columns = BTreeMap::new();
+    let mut column_ids = Vec::new();
+    for f in schema.fields().iter() {
+        let field_name = f.name().to_string();
+        column_ids.push(field_name.clone());
+        columns.insert(
+            field_name.clone(),
+            ColumnCatalog {
+                id: field_name.clone(),
+                desc: ColumnDesc {
+                    name: field_name,
+                    data_type: f.data_type().clone(),
+                    nullable: f.is_nullable(),
+                    default: None,
+                },
+            },
+        );
+    }
+    TableCatalog {
+        id: id.clone(),
+        name: id,
+        columns,
+        column_ids,
+        statistics: None,
+    }
+}
+
+/// The files at the location, sorted by path, together with the directory their
+/// partitions are relative to. A location with wildcards is a glob pattern, a directory
+/// holds all files below it but the hidden ones, and anything else is a single file.
+fn list_files(location: &str) -> Result<(PathBuf, Vec<PathBuf>), StorageError> {
+    let path = Path::new(location);
+    let (base, mut files) = if location.contains(['*', '?', '[']) {
+        let base = path
+            .components()
+            .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+            .collect::<PathBuf>();
+        let pattern = glob::glob(location)
+            .map_err(|e| StorageError::InvalidLocation(format!("{location}: {e}")))?;
+        let mut files = vec![];
+        for entry in pattern {
+            let path = entry.map_err(|e| StorageError::IoError(e.into_error()))?;
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+        (base, files)
+    } else if path.is_dir() {
+        let mut files = vec![];
+        visit_dir(path, &mut files)?;
+        (path.to_path_buf(), files)
+    } else {
+        let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        (base, vec![path.to_path_buf()])
+    };
+    if files.is_empty() {
+        return Err(StorageError::InvalidLocation(format!(
+            "no files found at {location}"
+        )));
+    }
+    files.sort();
+    Ok((base, files))
+}
+
+/// collect the files below the directory, skipping hidden entries like `.crc` or
+/// `_SUCCESS` files
+fn visit_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), StorageError> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with('.') || name.starts_with('_') {
+            continue;
+        }
+        if path.is_dir() {
+            visit_dir(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// the partition keys and values of a file, from its `key=value` directories below the
+/// base directory
+fn partition_of(base: &Path, path: &Path) -> (Vec<String>, Vec<String>) {
+    // glob results drop the leading `./` of the pattern
+    let without_cur_dir =
+        |p: &Path| -> PathBuf { p.components().filter(|c| c != &Component::CurDir).collect() };
+    let dir = without_cur_dir(path.parent().unwrap_or(Path::new("")));
+    let dirs = dir
+        .strip_prefix(without_cur_dir(base))
+        .unwrap_or(Path::new(""));
+    dirs.components()
+        .filter_map(|c| {
+            let (key, value) = c.as_os_str().to_str()?.split_once('=')?;
+            Some((key.to_lowercase(), value.to_string()))
+        })
+        .unzip()
+}
+
+/// the lines of an uncompressed file starting within the byte range
+fn open_lines(path: &Path, range: &Range<u64>) -> Result<Box<dyn Read + Send>, StorageError> {
+    let mut file = BufReader::new(File::open(path)?);
+    let end = line_start(&mut file, range.end)?;
+    let start = line_start(&mut file, range.start)?;
+    file.seek(SeekFrom::Start(start))?;
+    Ok(Box::new(file.take(end.saturating_sub(start))))
+}
+
+/// the offset of the first line starting at or after the offset
+fn line_start(file: &mut BufReader<File>, offset: u64) -> Result<u64, StorageError> {
+    if offset == 0 {
+        return Ok(0);
+    }
+    file.seek(SeekFrom::Start(offset - 1))?;
+    let skipped = file.read_until(b'\n', &mut vec![])?;
+    Ok(offset - 1 + skipped as u64)
+}
+
+fn partition_column<'a>(data_type: &DataType, values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    match data_type {
+        DataType::Int64 => Arc::new(Int64Array::from_iter_values(
+            values.map(|v| v.parse::<i64>().unwrap()),
+        )),
+        _ => Arc::new(StringArray::from_iter_values(values)),
+    }
+}
@@ -0,0 +1,329 @@
+use std::{fs::File, path::Path, sync::Arc};
+
+use arrow::{
+    array::RecordBatch,
+    buffer::Buffer,
+    datatypes::SchemaRef,
+    error::ArrowError,
+    ipc::{
+        convert::fb_to_schema,
+        reader::{read_footer_length, FileDecoder, StreamDecoder},
+        root_as_footer, Block,
+    },
+};
+use memmap2::Mmap;
+
+use crate::{
+    catalog::{TableCatalog, TableId},
+    storage::{
+        files::{infer_catalog, FileBatches, TableFiles},
+        BatchStream, PartitionPruner, StorageError, Table, TableReader,
+    },
+};
+
+/// the magic bytes an Arrow IPC file starts with, unlike an IPC stream
+const IPC_FILE_MAGIC: &[u8] = b"ARROW1";
+
+/// How Arrow IPC files are read.
+#[derive(Debug, Clone, Default)]
+pub struct IpcConfig {
+    projection: Option<Vec<usize>>,
+}
+
+impl IpcConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// read only the columns at the indices of the file schema
+    pub fn with_projection(mut self, projection: Vec<usize>) -> Self {
+        self.projection = Some(projection);
+        self
+    }
+}
+
+/// A table over Arrow IPC files or streams, which may be partitioned, see [`TableFiles`].
+/// The columns are those of the schema embedded in the first file. The files are mapped
+/// into memory, so the batches read share the pages of the files instead of copying them.
+#[derive(Clone)]
+pub struct IpcTable {
+    id: TableId,
+    /// the columns of the files, after the projection
+    arrow_schema: SchemaRef,
+    config: IpcConfig,
+    files: TableFiles,
+    /// the columns of the files followed by the partition columns
+    schema: SchemaRef,
+    catalog: TableCatalog,
+}
+
+impl IpcTable {
+    pub fn new(id: String, location: String, config: IpcConfig) -> Result<Self, StorageError> {
+        let files = TableFiles::list(&location)?;
+        let file_schema = read_schema(&map_file(&files.files[0].path)?)?;
+        let arrow_schema = match &config.projection {
+            Some(projection) => Arc::new(file_schema.project(projection)?),
+            None => file_schema,
+        };
+        let schema = files.table_schema(&arrow_schema)?;
+        let catalog = infer_catalog(id.clone(), &schema);
+        Ok(Self {
+            id,
+            arrow_schema,
+            config,
+            files,
+            schema,
+            catalog,
+        })
+    }
+
+    pub fn catalog(&self) -> &TableCatalog {
+        &self.catalog
+    }
+}
+
+impl Table for IpcTable {
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+        let projection = self.config.projection.clone();
+        Ok(Box::new(
+            self.files.read(self.schema.clone(), move |file| {
+                read_batches(map_file(&file.path)?, projection.clone())
+            }),
+        ))
+    }
+
+    fn scan_pruned(self: Arc<Self>, pruner: &PartitionPruner<'_>) -> BatchStream {
+        if !self.files.is_partitioned() {
+            return self.scan();
+        }
+        Arc::new(IpcTable {
+            files: self.files.prune(&self.arrow_schema, pruner),
+            ..self.as_ref().clone()
+        })
+        .scan()
+    }
+
+    /// split the files, which are read whole
+    fn scan_partitions(
+        self: Arc<Self>,
+        partitions: usize,
+        pruner: &PartitionPruner<'_>,
+    ) -> Vec<BatchStream> {
+        let files = if self.files.is_partitioned() {
+            self.files.prune(&self.arrow_schema, pruner)
+        } else {
+            self.files.clone()
+        };
+        files
+            .split(partitions, |_| false)
+            .into_iter()
+            .map(|files| {
+                Arc::new(IpcTable {
+                    files,
+                    ..self.as_ref().clone()
+                })
+                .scan()
+            })
+            .collect()
+    }
+
+    fn append(&self, _batch: RecordBatch) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!(
+            "insert into arrow table {}",
+            self.id
+        )))
+    }
+
+    fn overwrite(&self, _batches: Vec<RecordBatch>) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!(
+            "modify arrow table {}",
+            self.id
+        )))
+    }
+}
+
+/// map the file into memory as a buffer, which the arrays decoded from it point into
+fn map_file(path: &Path) -> Result<Buffer, StorageError> {
+    let file = File::open(path)?;
+    // SAFETY: the files of a table are read only, they must not be modified while mapped
+    let mmap = unsafe { Mmap::map(&file)? };
+    Ok(Buffer::from(bytes::Bytes::from_owner(mmap)))
+}
+
+fn read_schema(buffer: &Buffer) -> Result<SchemaRef, StorageError> {
+    if buffer.starts_with(IPC_FILE_MAGIC) {
+        let footer = IpcFooter::read(buffer)?;
+        return Ok(footer.schema);
+    }
+    let mut decoder = StreamDecoder::new();
+    let mut buffer = buffer.clone();
+    while decoder.schema().is_none() && !buffer.is_empty() {
+        decoder.decode(&mut buffer)?;
+    }
+    decoder.schema().ok_or_else(|| {
+        ArrowError::IpcError("missing schema of the arrow stream".to_string()).into()
+    })
+}
+
+/// decode the batches of an IPC file or stream lazily
+fn read_batches(
+    buffer: Buffer,
+    projection: Option<Vec<usize>>,
+) -> Result<FileBatches, StorageError> {
+    if buffer.starts_with(IPC_FILE_MAGIC) {
+        let footer = IpcFooter::read(&buffer)?;
+        let mut decoder = FileDecoder::new(footer.schema, footer.version);
+        if let Some(projection) = projection {
+            decoder = decoder.with_projection(projection);
+        }
+        for block in &footer.dictionaries {
+            decoder.read_dictionary(block, &block_data(&buffer, block))?;
+        }
+        let batches = footer.batches.into_iter().filter_map(move |block| {
+            decoder
+                .read_record_batch(&block, &block_data(&buffer, &block))
+                .map_err(StorageError::from)
+                .transpose()
+        });
+        return Ok(Box::new(batches));
+    }
+
+    let mut buffer = buffer;
+    let mut decoder = StreamDecoder::new();
+    let batches = std::iter::from_fn(move || loop {
+        if buffer.is_empty() {
+            return decoder.finish().err().map(|e| Err(e.into()));
+        }
+        match decoder.decode(&mut buffer) {
+            Ok(Some(batch)) => {
+                return Some(match &projection {
+                    Some(projection) => batch.project(projection).map_err(StorageError::from),
+                    None => Ok(batch),
+                })
+            }
+            Ok(None) => continue,
+            Err(e) => {
+                buffer = Buffer::from_vec(Vec::<u8>::new());
+                return Some(Err(e.into()));
+            }
+        }
+    });
+    Ok(Box::new(batches))
+}
+
+fn block_data(buffer: &Buffer, block: &Block) -> Buffer {
+    let len = block.bodyLength() as usize + block.metaDataLength() as usize;
+    buffer.slice_with_length(block.offset() as usize, len)
+}
+
+/// the footer of an IPC file, locating its dictionaries and batches
+struct IpcFooter {
+    schema: SchemaRef,
+    version: arrow::ipc::MetadataVersion,
+    dictionaries: Vec<Block>,
+    batches: Vec<Block>,
+}
+
+impl IpcFooter {
+    fn read(buffer: &Buffer) -> Result<Self, StorageError> {
+        let corrupted = |e: String| ArrowError::IpcError(format!("invalid arrow file: {e}"));
+        let trailer_start = buffer
+            .len()
+            .checked_sub(10)
+            .ok_or_else(|| corrupted("too short".to_string()))?;
+        let footer_len = read_footer_length(buffer[trailer_start..].try_into().unwrap())?;
+        let footer_start = trailer_start
+            .checked_sub(footer_len)
+            .ok_or_else(|| corrupted("footer out of range".to_string()))?;
+        let footer = root_as_footer(&buffer[footer_start..trailer_start])
+            .map_err(|e| corrupted(e.to_string()))?;
+        let schema = footer
+            .schema()
+            .ok_or_else(|| corrupted("missing schema".to_string()))?;
+        Ok(Self {
+            schema: Arc::new(fb_to_schema(schema)),
+            version: footer.version(),
+            dictionaries: footer
+                .dictionaries()
+                .map(|blocks| blocks.iter().copied().collect())
+                .unwrap_or_default(),
+            batches: footer
+                .recordBatches()
+                .map(|blocks| blocks.iter().copied().collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod ipc_test {
+    use arrow::{
+        array::{AsArray, DictionaryArray, Int32Array},
+        datatypes::{DataType, Int32Type},
+        ipc::writer::{FileWriter, StreamWriter},
+    };
+
+    use super::*;
+
+    fn build_record_batch(offset: i32) -> RecordBatch {
+        RecordBatch::try_from_iter([
+            (
+                "a",
+                Arc::new(Int32Array::from(vec![offset, offset + 1])) as _,
+            ),
+            (
+                "b",
+                Arc::new(DictionaryArray::<Int32Type>::from_iter(["x", "y"])) as _,
+            ),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ipc_table_reads_files_and_streams() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let schema = build_record_batch(0).schema();
+        let mut writer = FileWriter::try_new(File::create(dir.path().join("0.arrow"))?, &schema)?;
+        writer.write(&build_record_batch(0))?;
+        writer.write(&build_record_batch(2))?;
+        writer.finish()?;
+        let mut writer =
+            StreamWriter::try_new(File::create(dir.path().join("1.arrows"))?, &schema)?;
+        writer.write(&build_record_batch(4))?;
+        writer.finish()?;
+
+        let table = IpcTable::new(
+            "t".to_string(),
+            dir.path().to_string_lossy().into(),
+            IpcConfig::default(),
+        )?;
+        assert_eq!(table.catalog().column_ids, vec!["a", "b"]);
+        let mut reader = table.read()?;
+        let mut values = vec![];
+        while let Some(batch) = reader.next_batch()? {
+            assert_eq!(batch.schema(), schema);
+            values.extend(
+                batch
+                    .column(0)
+                    .as_primitive::<Int32Type>()
+                    .values()
+                    .to_vec(),
+            );
+        }
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+
+        let table = IpcTable::new(
+            "t".to_string(),
+            dir.path().join("1.arrows").to_string_lossy().into(),
+            IpcConfig::new().with_projection(vec![1]),
+        )?;
+        assert_eq!(table.catalog().column_ids, vec!["b"]);
+        let batch = table.read()?.next_batch()?.unwrap();
+        assert_eq!(batch.num_columns(), 1);
+        assert!(matches!(
+            batch.column(0).data_type(),
+            DataType::Dictionary(..)
+        ));
+        Ok(())
+    }
+}
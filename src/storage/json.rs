@@ -0,0 +1,264 @@
+use std::{
+    io::{BufReader, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use arrow::{
+    array::RecordBatch,
+    datatypes::{Schema, SchemaRef},
+    json::{reader::infer_json_schema, Reader, ReaderBuilder},
+};
+
+use crate::{
+    catalog::{TableCatalog, TableId},
+    storage::{
+        files::{infer_catalog, TableFiles},
+        open_file, BatchStream, FileCompression, PartitionPruner, StorageError, Table, TableReader,
+    },
+};
+
+/// How a newline-delimited json file is read. Unless a schema is given, the columns are
+/// inferred from the first records of the files, where nested objects become structs and
+/// arrays become lists.
+#[derive(Debug, Clone)]
+pub struct JsonConfig {
+    infer_schema_max_read_records: Option<usize>,
+    batch_size: usize,
+    schema: Option<SchemaRef>,
+    compression: Option<FileCompression>,
+}
+
+impl Default for JsonConfig {
+    fn default() -> Self {
+        Self {
+            infer_schema_max_read_records: Some(100),
+            batch_size: 1024,
+            schema: None,
+            compression: None,
+        }
+    }
+}
+
+impl JsonConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the number of records the schema is inferred from, 100 by default
+    pub fn with_infer_schema_max_records(mut self, records: usize) -> Self {
+        self.infer_schema_max_read_records = Some(records);
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// read the columns of the schema instead of inferring them
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// the compression of all files, which is otherwise told by the extension of each
+    pub fn with_compression(mut self, compression: FileCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    fn open(&self, path: &Path) -> Result<BufReader<Box<dyn Read + Send>>, StorageError> {
+        Ok(BufReader::new(open_file(path, self.compression)?))
+    }
+}
+
+/// A table over newline-delimited json files, which may be partitioned, see
+/// [`TableFiles`].
+#[derive(Clone)]
+pub struct JsonTable {
+    id: TableId,
+    /// the columns of the files
+    arrow_schema: SchemaRef,
+    config: JsonConfig,
+    files: TableFiles,
+    /// the columns of the files followed by the partition columns
+    schema: SchemaRef,
+    catalog: TableCatalog,
+}
+
+impl JsonTable {
+    pub fn new(id: String, location: String, config: JsonConfig) -> Result<Self, StorageError> {
+        let files = TableFiles::list(&location)?;
+        let arrow_schema = match &config.schema {
+            Some(schema) => schema.clone(),
+            None => Arc::new(Self::infer_arrow_schema(&files, &config)?),
+        };
+        let schema = files.table_schema(&arrow_schema)?;
+        let catalog = infer_catalog(id.clone(), &schema);
+        Ok(Self {
+            id,
+            arrow_schema,
+            config,
+            files,
+            schema,
+            catalog,
+        })
+    }
+
+    pub fn catalog(&self) -> &TableCatalog {
+        &self.catalog
+    }
+
+    fn infer_arrow_schema(files: &TableFiles, config: &JsonConfig) -> Result<Schema, StorageError> {
+        let mut schemas = vec![];
+        let mut records_to_read = config.infer_schema_max_read_records.unwrap_or(usize::MAX);
+        for file in &files.files {
+            let (schema, records_read) =
+                infer_json_schema(config.open(&file.path)?, Some(records_to_read))?;
+            schemas.push(schema);
+            records_to_read -= records_read;
+            if records_to_read == 0 {
+                break;
+            }
+        }
+        Ok(Schema::try_merge(schemas)?)
+    }
+}
+
+impl Table for JsonTable {
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+        let file_schema = self.arrow_schema.clone();
+        let config = self.config.clone();
+        Ok(Box::new(self.files.read(
+            self.schema.clone(),
+            move |file| {
+                let reader: Reader<_> = ReaderBuilder::new(file_schema.clone())
+                    .with_batch_size(config.batch_size)
+                    .build(BufReader::new(file.open(config.compression)?))?;
+                Ok(Box::new(reader.map(|batch| Ok(batch?))))
+            },
+        )))
+    }
+
+    fn scan_pruned(self: Arc<Self>, pruner: &PartitionPruner<'_>) -> BatchStream {
+        if !self.files.is_partitioned() {
+            return self.scan();
+        }
+        Arc::new(JsonTable {
+            files: self.files.prune(&self.arrow_schema, pruner),
+            ..self.as_ref().clone()
+        })
+        .scan()
+    }
+
+    /// split the files, or the lines of uncompressed files when there are fewer files
+    /// than partitions
+    fn scan_partitions(
+        self: Arc<Self>,
+        partitions: usize,
+        pruner: &PartitionPruner<'_>,
+    ) -> Vec<BatchStream> {
+        let files = if self.files.is_partitioned() {
+            self.files.prune(&self.arrow_schema, pruner)
+        } else {
+            self.files.clone()
+        };
+        let compression = self.config.compression;
+        files
+            .split(partitions, |path| {
+                compression.unwrap_or_else(|| FileCompression::from_path(path))
+                    == FileCompression::Uncompressed
+            })
+            .into_iter()
+            .map(|files| {
+                Arc::new(JsonTable {
+                    files,
+                    ..self.as_ref().clone()
+                })
+                .scan()
+            })
+            .collect()
+    }
+
+    fn append(&self, _batch: RecordBatch) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!(
+            "insert into json table {}",
+            self.id
+        )))
+    }
+
+    fn overwrite(&self, _batches: Vec<RecordBatch>) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!(
+            "modify json table {}",
+            self.id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod json_test {
+    use std::io::Write;
+
+    use arrow::{
+        array::AsArray,
+        datatypes::{DataType, Field, Fields, Int64Type},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_json_table_infers_nested_columns() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let partition = dir.path().join("day=1");
+        std::fs::create_dir(&partition)?;
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(partition.join("events.json.gz"))?,
+            flate2::Compression::default(),
+        );
+        encoder.write_all(
+            b"{\"id\": 1, \"actor\": {\"name\": \"ann\"}, \"tags\": [\"a\"]}\n\
+              {\"id\": 2, \"actor\": {\"name\": \"bob\"}, \"tags\": []}\n",
+        )?;
+        encoder.finish()?;
+
+        let table = JsonTable::new(
+            "events".to_string(),
+            dir.path().to_string_lossy().into(),
+            JsonConfig::default(),
+        )?;
+        let columns = table
+            .catalog()
+            .get_all_columns()
+            .into_iter()
+            .map(|c| (c.id, c.desc.data_type))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                (
+                    "actor".to_string(),
+                    DataType::Struct(Fields::from(vec![Field::new("name", DataType::Utf8, true)]))
+                ),
+                ("id".to_string(), DataType::Int64),
+                (
+                    "tags".to_string(),
+                    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)))
+                ),
+                ("day".to_string(), DataType::Int64),
+            ]
+        );
+
+        let batch = table.read()?.next_batch()?.unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(
+            batch.column(1).as_primitive::<Int64Type>().values(),
+            &[1, 2]
+        );
+        assert_eq!(
+            batch.column(3).as_primitive::<Int64Type>().values(),
+            &[1, 1]
+        );
+        Ok(())
+    }
+}
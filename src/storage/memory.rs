@@ -1,137 +1,324 @@
 use std::{
-    collections::{BTreeMap, HashMap},
-    sync::Mutex,
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex},
 };
 
-use arrow::array::RecordBatch;
+use arrow::{
+    array::RecordBatch,
+    datatypes::{Field, Schema, SchemaRef},
+};
+use futures::StreamExt;
 
 use crate::{
     catalog::{ColumnCatalog, ColumnDesc, RootCatalog, TableCatalog, TableId},
-    storage::{Storage, StorageError, Table, Transaction},
+    storage::{
+        mvcc::{TableWrite, TxnState, Versioned},
+        split_batches, BatchStream, CsvConfig, CsvTable, IpcConfig, IpcTable, JsonConfig,
+        JsonTable, PartitionPruner, Storage, StorageError, Table, TableReader, Transaction,
+    },
 };
 
-#[derive(Clone)]
-pub struct InMemoryTable {
-    _id: TableId,
-    catalog: TableCatalog,
-    data: Vec<RecordBatch>,
+/// The committed batches of a table, shared by the snapshots reading them.
+type TableData = Arc<Vec<RecordBatch>>;
+
+pub struct InMemoryStorage {
+    catalog: Mutex<RootCatalog>,
+    state: Arc<Mutex<InMemoryState>>,
+}
+
+struct InMemoryState {
+    tables: HashMap<TableId, Versioned<TableData>>,
+    next_version: u64,
 }
 
+#[derive(Clone)]
 pub struct InMemoryTransaction {
-    cursor: usize,
-    data: Vec<RecordBatch>,
+    catalog: Arc<RootCatalog>,
+    state: Arc<Mutex<InMemoryState>>,
+    txn: Arc<Mutex<TxnState<TableData>>>,
 }
 
-pub struct InMemoryStorage {
-    catalog: Mutex<RootCatalog>,
-    tables: Mutex<HashMap<TableId, InMemoryTable>>,
+pub struct InMemoryTable {
+    id: TableId,
+    catalog: TableCatalog,
+    txn: InMemoryTransaction,
 }
 
-impl InMemoryTransaction {
-    pub fn start(table: &InMemoryTable) -> Result<Self, StorageError> {
-        Ok(Self {
-            cursor: 0,
-            data: table.data.clone(),
-        })
-    }
+pub struct InMemoryReader {
+    batches: VecDeque<RecordBatch>,
 }
 
-impl Transaction for InMemoryTransaction {
+impl TableReader for InMemoryReader {
     fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError> {
-        self.data
-            .get(self.cursor)
-            .map(|batch| {
-                self.cursor += 1;
-                Ok(batch.clone())
-            })
-            .transpose()
+        Ok(self.batches.pop_front())
     }
 }
 
 impl Table for InMemoryTable {
-    type TransactionType = InMemoryTransaction;
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+        let batches = self.visible_batches()?;
+        Ok(Box::new(InMemoryReader { batches }))
+    }
+
+    /// the batches are already in memory, so they are streamed without a blocking reader
+    fn scan(self: Arc<Self>) -> BatchStream {
+        let batches = match self.visible_batches() {
+            Ok(batches) => batches.into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+        futures::stream::iter(batches).boxed()
+    }
+
+    /// split the rows into ranges, slicing the batches without copying them
+    fn scan_partitions(
+        self: Arc<Self>,
+        partitions: usize,
+        _pruner: &PartitionPruner<'_>,
+    ) -> Vec<BatchStream> {
+        match self.visible_batches() {
+            Ok(batches) => split_batches(batches.into(), partitions)
+                .into_iter()
+                .map(|batches| futures::stream::iter(batches.into_iter().map(Ok)).boxed())
+                .collect(),
+            Err(e) => vec![futures::stream::iter([Err(e)]).boxed()],
+        }
+    }
+
+    fn append(&self, batch: RecordBatch) -> Result<(), StorageError> {
+        let batches = self.conform_batches(vec![batch])?;
+        self.txn.txn.lock().unwrap().append(&self.id, batches)
+    }
 
-    fn read(&self) -> Result<Self::TransactionType, StorageError> {
-        InMemoryTransaction::start(self)
+    fn overwrite(&self, batches: Vec<RecordBatch>) -> Result<(), StorageError> {
+        let batches = self.conform_batches(batches)?;
+        self.txn.txn.lock().unwrap().overwrite(&self.id, batches)
     }
 }
 
 impl InMemoryTable {
-    pub fn new(id: TableId, data: Vec<RecordBatch>) -> Result<Self, StorageError> {
-        let catalog = Self::infer_catalog(id.clone(), data.first().cloned());
-        Ok(Self {
-            _id: id,
+    fn visible_batches(&self) -> Result<VecDeque<RecordBatch>, StorageError> {
+        let (data, written) = self.txn.txn.lock().unwrap().visible(&self.id)?;
+        let mut batches: VecDeque<_> = data.iter().flat_map(|d| d.iter().cloned()).collect();
+        batches.extend(written);
+        Ok(batches)
+    }
+
+    fn arrow_schema(&self) -> SchemaRef {
+        let fields = self
+            .catalog
+            .get_all_columns()
+            .into_iter()
+            .map(|c| Field::new(c.desc.name, c.desc.data_type, c.desc.nullable))
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    }
+
+    /// take the field names and types of the table, the columns are already in its order
+    fn conform_batches(&self, batches: Vec<RecordBatch>) -> Result<Vec<RecordBatch>, StorageError> {
+        let schema = self.arrow_schema();
+        let batches = batches
+            .into_iter()
+            .map(|batch| RecordBatch::try_new(schema.clone(), batch.columns().to_vec()))
+            .collect::<Result<_, _>>()?;
+        Ok(batches)
+    }
+}
+
+impl Transaction for InMemoryTransaction {
+    fn get_table(&self, id: String) -> Result<Arc<dyn Table>, StorageError> {
+        let catalog = self
+            .catalog
+            .get_table_by_name(&id)
+            .ok_or(StorageError::TableNotFound(id.clone()))?;
+        Ok(Arc::new(InMemoryTable {
+            id,
             catalog,
-            data,
-        })
-    }
-
-    fn infer_catalog(id: String, batch: Option<RecordBatch>) -> TableCatalog {
-        let mut columns = BTreeMap::new();
-        let mut column_ids = Vec::new();
-        if let Some(batch) = batch {
-            for f in batch.schema().fields().iter() {
-                let field_name = f.name().to_string();
-                column_ids.push(field_name.clone());
-                columns.insert(
-                    field_name.clone(),
-                    ColumnCatalog {
-                        id: field_name.clone(),
-                        desc: ColumnDesc {
-                            name: field_name,
-                            data_type: f.data_type().clone(),
-                        },
-                    },
-                );
-            }
+            txn: self.clone(),
+        }))
+    }
+
+    fn commit(&self) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let writes = self.txn.lock().unwrap().commit(&state.tables)?;
+        if writes.is_empty() {
+            return Ok(());
         }
-        TableCatalog {
-            id: id.clone(),
-            name: id,
-            column_ids,
-            columns,
+        let version = state.next_version;
+        state.next_version += 1;
+        for (id, write) in writes {
+            let table = state.tables.get_mut(&id).unwrap();
+            let data = match write {
+                TableWrite::Append(batches) => table.data.iter().cloned().chain(batches).collect(),
+                TableWrite::Overwrite(batches) => batches,
+            };
+            *table = Versioned {
+                version,
+                data: Arc::new(data),
+            };
         }
+        Ok(())
+    }
+
+    fn rollback(&self) {
+        self.txn.lock().unwrap().rollback();
     }
 }
 
 impl Storage for InMemoryStorage {
-    type TableType = InMemoryTable;
+    /// load all rows of the csv file into a memory table
+    fn create_csv_table(
+        &self,
+        id: String,
+        filepath: String,
+        config: CsvConfig,
+    ) -> Result<(), StorageError> {
+        let csv_table = CsvTable::new(id.clone(), filepath, config)?;
+        self.load_table(csv_table.catalog().clone(), &csv_table)
+    }
 
-    fn create_csv_table(&self, _id: String, _filepath: String) -> Result<(), StorageError> {
-        unreachable!("memory storage does not support create csv table")
+    /// load all rows of the json files into a memory table
+    fn create_json_table(
+        &self,
+        id: String,
+        location: String,
+        config: JsonConfig,
+    ) -> Result<(), StorageError> {
+        let json_table = JsonTable::new(id.clone(), location, config)?;
+        self.load_table(json_table.catalog().clone(), &json_table)
+    }
+
+    /// load all rows of the Arrow IPC files into a memory table
+    fn create_ipc_table(
+        &self,
+        id: String,
+        location: String,
+        config: IpcConfig,
+    ) -> Result<(), StorageError> {
+        let ipc_table = IpcTable::new(id.clone(), location, config)?;
+        self.load_table(ipc_table.catalog().clone(), &ipc_table)
     }
 
     fn create_mem_table(&self, id: String, data: Vec<RecordBatch>) -> Result<(), StorageError> {
-        let table = InMemoryTable::new(id.clone(), data)?;
-        self.catalog
-            .lock()
-            .unwrap()
-            .tables
-            .insert(id.clone(), table.catalog.clone());
-        self.tables.lock().unwrap().insert(id, table);
-        Ok(())
+        let catalog = infer_catalog(id, data.first());
+        self.insert_table(catalog, data)
     }
 
-    fn get_table(&self, id: String) -> Result<Self::TableType, StorageError> {
-        self.tables
+    fn create_table(&self, id: String, columns: Vec<ColumnCatalog>) -> Result<(), StorageError> {
+        let catalog = TableCatalog {
+            id: id.clone(),
+            name: id,
+            column_ids: columns.iter().map(|c| c.id.clone()).collect(),
+            columns: columns.into_iter().map(|c| (c.id.clone(), c)).collect(),
+            statistics: None,
+        };
+        self.insert_table(catalog, vec![])
+    }
+
+    fn drop_table(&self, id: String) -> Result<(), StorageError> {
+        self.catalog.lock().unwrap().tables.remove(&id);
+        self.state
             .lock()
             .unwrap()
-            .get(&id)
-            .cloned()
+            .tables
+            .remove(&id)
+            .map(|_| ())
             .ok_or(StorageError::TableNotFound(id))
     }
 
+    fn begin(&self) -> Result<Arc<dyn Transaction>, StorageError> {
+        // take the catalog first, so that every table in it is also in the snapshot
+        let catalog = Arc::new(self.catalog.lock().unwrap().clone());
+        let snapshot = self.state.lock().unwrap().tables.clone();
+        Ok(Arc::new(InMemoryTransaction {
+            catalog,
+            state: self.state.clone(),
+            txn: Arc::new(Mutex::new(TxnState::new(snapshot))),
+        }))
+    }
+
     fn get_catalog(&self) -> RootCatalog {
         self.catalog.lock().unwrap().clone()
     }
 }
 
+impl Default for InMemoryStorage {
+    fn default() -> Self {
+        InMemoryStorage::new()
+    }
+}
+
 impl InMemoryStorage {
     pub fn new() -> Self {
         InMemoryStorage {
             catalog: Mutex::new(RootCatalog::new()),
-            tables: Mutex::new(HashMap::new()),
+            state: Arc::new(Mutex::new(InMemoryState {
+                tables: HashMap::new(),
+                next_version: 0,
+            })),
+        }
+    }
+
+    fn insert_table(
+        &self,
+        catalog: TableCatalog,
+        data: Vec<RecordBatch>,
+    ) -> Result<(), StorageError> {
+        let mut tables = self.catalog.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
+        if state.tables.contains_key(&catalog.id) {
+            return Err(StorageError::TableExists(catalog.id));
+        }
+        let version = state.next_version;
+        state.next_version += 1;
+        state.tables.insert(
+            catalog.id.clone(),
+            Versioned {
+                version,
+                data: Arc::new(data),
+            },
+        );
+        tables.tables.insert(catalog.id.clone(), catalog);
+        Ok(())
+    }
+
+    /// create a table holding all rows read from the source table
+    fn load_table(&self, catalog: TableCatalog, source: &dyn Table) -> Result<(), StorageError> {
+        let mut batches = vec![];
+        let mut reader = source.read()?;
+        while let Some(batch) = reader.next_batch()? {
+            batches.push(batch);
         }
+        self.insert_table(catalog, batches)
+    }
+}
+
+fn infer_catalog(id: String, batch: Option<&RecordBatch>) -> TableCatalog {
+    let mut columns = BTreeMap::new();
+    let mut column_ids = Vec::new();
+    if let Some(batch) = batch {
+        for f in batch.schema().fields().iter() {
+            let field_name = f.name().to_string();
+            column_ids.push(field_name.clone());
+            columns.insert(
+                field_name.clone(),
+                ColumnCatalog {
+                    id: field_name.clone(),
+                    desc: ColumnDesc {
+                        name: field_name,
+                        data_type: f.data_type().clone(),
+                        nullable: true,
+                        default: None,
+                    },
+                },
+            );
+        }
+    }
+    TableCatalog {
+        id: id.clone(),
+        name: id,
+        column_ids,
+        columns,
+        statistics: None,
     }
 }
 
@@ -157,9 +344,9 @@ mod storage_test {
         assert!(table_catalog.is_some());
         assert!(table_catalog.unwrap().get_all_columns().is_empty());
 
-        let table = storage.get_table(id)?;
-        let mut tx = table.read()?;
-        let batch = tx.next_batch()?;
+        let table = storage.begin()?.get_table(id)?;
+        let mut reader = table.read()?;
+        let batch = reader.next_batch()?;
         assert!(batch.is_none());
         Ok(())
     }
@@ -180,6 +367,15 @@ mod storage_test {
         Ok(vec![batch])
     }
 
+    fn num_rows(txn: &Arc<dyn Transaction>, id: &str) -> Result<usize, StorageError> {
+        let mut reader = txn.get_table(id.to_string())?.read()?;
+        let mut rows = 0;
+        while let Some(batch) = reader.next_batch()? {
+            rows += batch.num_rows();
+        }
+        Ok(rows)
+    }
+
     #[test]
     fn test_in_memory_storage_works_with_data() -> Result<(), StorageError> {
         let id = "test".to_string();
@@ -191,11 +387,91 @@ mod storage_test {
         assert!(table_catalog.is_some());
         assert!(table_catalog.unwrap().get_column_by_name("a").is_some());
 
-        let table = storage.get_table(id)?;
-        let mut tx = table.read()?;
-        let batch = tx.next_batch()?;
+        let table = storage.begin()?.get_table(id)?;
+        let mut reader = table.read()?;
+        let batch = reader.next_batch()?;
         assert!(batch.is_some());
         assert_eq!(batch.unwrap().num_rows(), 3);
         Ok(())
     }
+
+    #[test]
+    fn test_in_memory_storage_create_append_and_drop() -> Result<(), StorageError> {
+        let id = "test".to_string();
+        let storage = InMemoryStorage::new();
+        let columns = ["a", "b"]
+            .iter()
+            .map(|name| ColumnCatalog {
+                id: name.to_string(),
+                desc: ColumnDesc {
+                    name: name.to_string(),
+                    data_type: DataType::Int32,
+                    nullable: true,
+                    default: None,
+                },
+            })
+            .collect();
+        storage.create_table(id.clone(), columns)?;
+        assert!(matches!(
+            storage.create_table(id.clone(), vec![]),
+            Err(StorageError::TableExists(_))
+        ));
+
+        let txn = storage.begin()?;
+        let table = txn.get_table(id.clone())?;
+        assert!(table.read()?.next_batch()?.is_none());
+        for batch in build_record_batch()? {
+            table.append(batch)?;
+        }
+        // the appended batches are visible to a table fetched later in the transaction
+        assert_eq!(num_rows(&txn, &id)?, 3);
+        txn.commit()?;
+        assert_eq!(num_rows(&storage.begin()?, &id)?, 3);
+
+        storage.drop_table(id.clone())?;
+        assert!(storage.get_catalog().get_table_by_name(&id).is_none());
+        assert!(matches!(
+            storage.drop_table(id),
+            Err(StorageError::TableNotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_memory_storage_snapshot_isolation() -> Result<(), StorageError> {
+        let id = "test".to_string();
+        let storage = InMemoryStorage::new();
+        storage.create_mem_table(id.clone(), build_record_batch()?)?;
+
+        let writer = storage.begin()?;
+        let reader = storage.begin()?;
+        writer
+            .get_table(id.clone())?
+            .append(build_record_batch()?.remove(0))?;
+        // uncommitted writes are only visible to the writer
+        assert_eq!(num_rows(&writer, &id)?, 6);
+        assert_eq!(num_rows(&reader, &id)?, 3);
+        writer.commit()?;
+        // committed writes are only visible to transactions beginning afterwards
+        assert_eq!(num_rows(&reader, &id)?, 3);
+        assert_eq!(num_rows(&storage.begin()?, &id)?, 6);
+
+        // the reader also wrote the table, so it conflicts with the earlier commit
+        reader.get_table(id.clone())?.overwrite(vec![])?;
+        assert!(matches!(
+            reader.commit(),
+            Err(StorageError::WriteConflict(_))
+        ));
+        assert_eq!(num_rows(&storage.begin()?, &id)?, 6);
+
+        let txn = storage.begin()?;
+        txn.get_table(id.clone())?.overwrite(vec![])?;
+        txn.rollback();
+        assert_eq!(num_rows(&storage.begin()?, &id)?, 6);
+        assert!(matches!(
+            txn.commit(),
+            Err(StorageError::TransactionFinished)
+        ));
+        Ok(())
+    }
 }
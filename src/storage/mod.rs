@@ -1,42 +1,252 @@
 use std::sync::Arc;
 
 use arrow::{error::ArrowError, record_batch::RecordBatch};
+use futures::{stream::BoxStream, StreamExt};
+use tokio::sync::mpsc;
 
+mod compression;
 mod csv;
+mod disk;
+mod files;
+mod ipc;
+mod json;
 mod memory;
+pub mod mvcc;
+mod registry;
+mod system;
+pub mod wal;
+mod writer;
+pub use compression::*;
 pub use csv::*;
+pub use disk::*;
+pub use ipc::*;
+pub use json::*;
+pub use memory::*;
+pub use registry::*;
+pub use system::COLUMN_STATISTICS_TABLE_NAME;
+pub use writer::*;
 
-use crate::{catalog::RootCatalog, storage::memory::InMemoryStorage};
+use crate::catalog::{ColumnCatalog, RootCatalog, TableStatistics};
 
-#[derive(Clone)]
-pub enum StorageImpl {
-    CsvStorage(Arc<CsvStorage>),
-    InMemoryStorage(Arc<InMemoryStorage>),
-}
+pub type BatchStream = BoxStream<'static, Result<RecordBatch, StorageError>>;
+
+/// Tells which partitions of a table may have rows passing a filter. Each partition is a
+/// row of the batch, having the values of its partition columns and nulls in the other
+/// columns of the table.
+pub type PartitionPruner<'a> = dyn Fn(&RecordBatch) -> Vec<bool> + 'a;
+
+/// the number of batches a blocking reader reads ahead of the consumer of its scan
+const SCAN_BUFFER_SIZE: usize = 2;
 
+/// A source of tables mounted into a database. Tables are created and dropped by the
+/// storage right away, while their rows are read and written through transactions.
+///
+/// Storages are used as trait objects, so that sources of different kinds can be mounted
+/// side by side in a [`StorageRegistry`]. A read-only source only needs to provide its
+/// catalog and transactions, the rest is unsupported by default.
 pub trait Storage: Sync + Send + 'static {
-    type TableType: Table;
+    /// create a table on the csv file
+    fn create_csv_table(
+        &self,
+        id: String,
+        _filepath: String,
+        _config: CsvConfig,
+    ) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!("create table {id}")))
+    }
+
+    /// create a table on the newline-delimited json files at the location
+    fn create_json_table(
+        &self,
+        id: String,
+        _location: String,
+        _config: JsonConfig,
+    ) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!("create table {id}")))
+    }
+
+    /// create a table on the Arrow IPC files at the location
+    fn create_ipc_table(
+        &self,
+        id: String,
+        _location: String,
+        _config: IpcConfig,
+    ) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!("create table {id}")))
+    }
+
+    fn create_mem_table(&self, id: String, _data: Vec<RecordBatch>) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!("create table {id}")))
+    }
 
-    // currently only support create table by file
-    fn create_csv_table(&self, id: String, filepath: String) -> Result<(), StorageError>;
+    /// create an empty table with the declared columns
+    fn create_table(&self, id: String, _columns: Vec<ColumnCatalog>) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!("create table {id}")))
+    }
 
-    fn create_mem_table(&self, id: String, data: Vec<RecordBatch>) -> Result<(), StorageError>;
+    fn drop_table(&self, id: String) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!("drop table {id}")))
+    }
 
-    fn get_table(&self, id: String) -> Result<Self::TableType, StorageError>;
+    /// keep the statistics collected by `ANALYZE`, which are attached to the catalog of the
+    /// table from then on
+    fn set_statistics(&self, id: String, _statistics: TableStatistics) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(format!("analyze table {id}")))
+    }
+
+    /// start a transaction on a snapshot of the tables committed so far
+    fn begin(&self) -> Result<Arc<dyn Transaction>, StorageError>;
 
     fn get_catalog(&self) -> RootCatalog;
 }
 
-pub trait Table: Sync + Send + Clone + 'static {
-    type TransactionType: Transaction;
+/// A transaction with snapshot isolation. It reads the tables as they were committed when
+/// it began together with its own writes, which are buffered and become visible to other
+/// transactions all at once on commit. It is shared by the executors of a statement and
+/// the statements of a transaction block.
+pub trait Transaction: Sync + Send + 'static {
+    fn get_table(&self, id: String) -> Result<Arc<dyn Table>, StorageError>;
+
+    /// make the writes visible to transactions beginning afterwards, failing with
+    /// `WriteConflict` if a written table was committed by another transaction since
+    /// this one began, in which case the writes are discarded
+    fn commit(&self) -> Result<(), StorageError>;
 
-    fn read(&self) -> Result<Self::TransactionType, StorageError>;
+    /// discard the writes
+    fn rollback(&self);
 }
 
-pub trait Transaction: Sync + Send + 'static {
+/// A table accessed within a transaction.
+pub trait Table: Sync + Send + 'static {
+    /// read the rows of the table visible to the transaction, which blocks on io
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError>;
+
+    /// Stream the rows of the table visible to the transaction without blocking the async
+    /// runtime. By default the reader runs on a blocking thread of the tokio runtime and
+    /// sends its batches over a bounded channel, so it waits while the consumer lags
+    /// behind, and stops once the stream is dropped.
+    fn scan(self: Arc<Self>) -> BatchStream {
+        scan_blocking(self)
+    }
+
+    /// Stream the rows of the partitions which the pruner keeps. A table without
+    /// partitions scans all of its rows, so the rows still need to be filtered.
+    fn scan_pruned(self: Arc<Self>, _pruner: &PartitionPruner<'_>) -> BatchStream {
+        self.scan()
+    }
+
+    /// Stream the rows of `scan_pruned` split into at most `partitions` streams, which may
+    /// be read concurrently and hold the rows in the same order when read one after
+    /// another. A table which cannot split its rows scans them in a single stream.
+    fn scan_partitions(
+        self: Arc<Self>,
+        _partitions: usize,
+        pruner: &PartitionPruner<'_>,
+    ) -> Vec<BatchStream> {
+        vec![self.scan_pruned(pruner)]
+    }
+
+    /// append a batch whose columns are in the order of the table columns
+    fn append(&self, _batch: RecordBatch) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(
+            "insert into a read-only table".to_string(),
+        ))
+    }
+
+    /// replace all rows of the table with the batches, used by UPDATE and DELETE which
+    /// rewrite the batches of the table
+    fn overwrite(&self, _batches: Vec<RecordBatch>) -> Result<(), StorageError> {
+        Err(StorageError::Unsupported(
+            "modify a read-only table".to_string(),
+        ))
+    }
+}
+
+pub trait TableReader: Send + 'static {
     fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError>;
 }
 
+fn scan_blocking<T: Table + ?Sized>(table: Arc<T>) -> BatchStream {
+    scan_reader(move || table.read())
+}
+
+/// stream the batches of the reader which `read` opens, on a blocking thread of the
+/// runtime like [`Table::scan`]
+fn scan_reader(
+    read: impl FnOnce() -> Result<Box<dyn TableReader>, StorageError> + Send + 'static,
+) -> BatchStream {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        // outside of a runtime there are no tasks to stall, so the reader runs inline
+        let mut reader = Some(read());
+        let batches = std::iter::from_fn(move || match reader.take()? {
+            Ok(mut r) => {
+                let batch = r.next_batch().transpose();
+                if let Some(Ok(_)) = batch {
+                    reader = Some(Ok(r));
+                }
+                batch
+            }
+            Err(e) => Some(Err(e)),
+        });
+        return futures::stream::iter(batches).boxed();
+    };
+    let (sender, receiver) = mpsc::channel(SCAN_BUFFER_SIZE);
+    runtime.spawn_blocking(move || {
+        let mut reader = match read() {
+            Ok(reader) => reader,
+            Err(e) => {
+                let _ = sender.blocking_send(Err(e));
+                return;
+            }
+        };
+        while let Some(batch) = reader.next_batch().transpose() {
+            let failed = batch.is_err();
+            if sender.blocking_send(batch).is_err() || failed {
+                break;
+            }
+        }
+    });
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        let batch = receiver.recv().await?;
+        Some((batch, receiver))
+    })
+    .boxed()
+}
+
+/// split the items into at most `n` contiguous chunks whose lengths differ by one at
+/// most, keeping a single empty chunk when there are no items
+fn split_evenly<T>(items: Vec<T>, n: usize) -> Vec<Vec<T>> {
+    let n = n.min(items.len()).max(1);
+    let (len, rest) = (items.len() / n, items.len() % n);
+    let mut items = items.into_iter();
+    (0..n)
+        .map(|i| items.by_ref().take(len + usize::from(i < rest)).collect())
+        .collect()
+}
+
+/// split the rows of the batches into at most `n` contiguous ranges of about as many rows,
+/// slicing the batches which cross the end of a range
+fn split_batches(batches: Vec<RecordBatch>, n: usize) -> Vec<Vec<RecordBatch>> {
+    let num_rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+    let rows_per_range = num_rows.div_ceil(n.max(1)).max(1);
+    let mut ranges = vec![vec![]];
+    let mut rows_in_range = 0;
+    for batch in batches {
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            if rows_in_range == rows_per_range {
+                ranges.push(vec![]);
+                rows_in_range = 0;
+            }
+            let len = (batch.num_rows() - offset).min(rows_per_range - rows_in_range);
+            ranges.last_mut().unwrap().push(batch.slice(offset, len));
+            offset += len;
+            rows_in_range += len;
+        }
+    }
+    ranges
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum StorageError {
     #[error("arrow error")]
@@ -47,4 +257,135 @@ pub enum StorageError {
 
     #[error("table not found: {0}")]
     TableNotFound(String),
+
+    #[error("table already exists: {0}")]
+    TableExists(String),
+
+    #[error("unsupported operation: {0}")]
+    Unsupported(String),
+
+    #[error("could not serialize access due to concurrent update of table {0}")]
+    WriteConflict(String),
+
+    #[error("transaction is already committed or rolled back")]
+    TransactionFinished,
+
+    #[error("corrupted storage: {0}")]
+    Corrupted(String),
+
+    #[error("invalid location: {0}")]
+    InvalidLocation(String),
+}
+
+#[cfg(test)]
+mod storage_test {
+    use std::sync::mpsc::{channel, Receiver};
+
+    use arrow::{
+        array::Int32Array,
+        datatypes::{DataType, Field, Schema},
+    };
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    /// A table whose reader blocks until the test hands it the next batch.
+    #[derive(Clone)]
+    struct BlockingTable {
+        batches: Arc<std::sync::Mutex<Receiver<Option<RecordBatch>>>>,
+    }
+
+    struct BlockingReader(BlockingTable);
+
+    impl TableReader for BlockingReader {
+        fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError> {
+            Ok(self.0.batches.lock().unwrap().recv().unwrap())
+        }
+    }
+
+    impl Table for BlockingTable {
+        fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+            Ok(Box::new(BlockingReader(self.clone())))
+        }
+    }
+
+    fn batch(value: i32) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int32Array::from(vec![value]))],
+        )
+        .unwrap()
+    }
+
+    // runs on a single threaded runtime, which a reader blocking it would deadlock
+    #[tokio::test]
+    async fn test_scan_reads_off_the_runtime() -> Result<(), StorageError> {
+        let (sender, receiver) = channel();
+        let table = BlockingTable {
+            batches: Arc::new(std::sync::Mutex::new(receiver)),
+        };
+        let scan =
+            tokio::spawn(async move { Arc::new(table).scan().try_collect::<Vec<_>>().await });
+        // the scan waits for its reader, while other tasks keep running
+        tokio::task::yield_now().await;
+        assert_eq!(tokio::spawn(async { 1 }).await.unwrap(), 1);
+
+        for i in 0..3 {
+            sender.send(Some(batch(i))).unwrap();
+        }
+        sender.send(None).unwrap();
+        assert_eq!(scan.await.unwrap()?, vec![batch(0), batch(1), batch(2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_reads_inline_without_runtime() -> Result<(), StorageError> {
+        let (sender, receiver) = channel();
+        let table = BlockingTable {
+            batches: Arc::new(std::sync::Mutex::new(receiver)),
+        };
+        sender.send(Some(batch(1))).unwrap();
+        sender.send(None).unwrap();
+        let batches = futures::executor::block_on(Arc::new(table).scan().try_collect::<Vec<_>>())?;
+        assert_eq!(batches, vec![batch(1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_batches_into_row_ranges() {
+        let batches = vec![
+            RecordBatch::try_new(
+                Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)])),
+                vec![Arc::new(Int32Array::from_iter_values(0..5))],
+            )
+            .unwrap(),
+            batch(5),
+            batch(6),
+        ];
+        let ranges = split_batches(batches, 3)
+            .into_iter()
+            .map(|range| {
+                range
+                    .iter()
+                    .flat_map(|b| {
+                        b.column(0)
+                            .as_any()
+                            .downcast_ref::<Int32Array>()
+                            .unwrap()
+                            .values()
+                            .to_vec()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+
+        assert_eq!(split_batches(vec![], 3).len(), 1);
+        assert_eq!(
+            split_evenly(vec![1, 2, 3, 4, 5], 3),
+            vec![vec![1, 2], vec![3, 4], vec![5]]
+        );
+        assert_eq!(split_evenly(vec![1], 3), vec![vec![1]]);
+    }
 }
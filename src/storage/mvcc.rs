@@ -0,0 +1,186 @@
+use std::collections::{BTreeMap, HashMap};
+
+use arrow::array::RecordBatch;
+
+use crate::{catalog::TableId, storage::StorageError};
+
+/// A committed table, holding its data and the version of the commit that wrote it last.
+#[derive(Clone)]
+pub struct Versioned<D> {
+    pub version: u64,
+    pub data: D,
+}
+
+/// The writes of a transaction to a table, applied on top of the table on commit.
+pub enum TableWrite {
+    /// rows appended to the table
+    Append(Vec<RecordBatch>),
+    /// rows replacing all rows of the table
+    Overwrite(Vec<RecordBatch>),
+}
+
+impl TableWrite {
+    pub fn batches(&self) -> &[RecordBatch] {
+        match self {
+            TableWrite::Append(batches) | TableWrite::Overwrite(batches) => batches,
+        }
+    }
+}
+
+/// The state of a transaction under snapshot isolation: the tables committed when it
+/// began, which it reads, and the writes it buffers until it commits. Its own writes are
+/// visible to its reads, while the writes of others committed later are not.
+///
+/// Write-write conflicts are detected per table when committing: the first transaction
+/// to commit a write to a table wins, and a later one writing the same table fails if the
+/// version it read is no longer the committed one.
+pub struct TxnState<D> {
+    snapshot: HashMap<TableId, Versioned<D>>,
+    writes: BTreeMap<TableId, TableWrite>,
+    finished: bool,
+}
+
+impl<D: Clone> TxnState<D> {
+    pub fn new(snapshot: HashMap<TableId, Versioned<D>>) -> Self {
+        Self {
+            snapshot,
+            writes: BTreeMap::new(),
+            finished: false,
+        }
+    }
+
+    /// The rows of the table visible to the transaction: the snapshot data, unless the
+    /// transaction overwrote the table, followed by the batches it wrote.
+    pub fn visible(&self, id: &TableId) -> Result<(Option<D>, Vec<RecordBatch>), StorageError> {
+        let snapshot = self.snapshot_of(id)?;
+        Ok(match self.writes.get(id) {
+            None => (Some(snapshot.data.clone()), vec![]),
+            Some(TableWrite::Append(batches)) => (Some(snapshot.data.clone()), batches.clone()),
+            Some(TableWrite::Overwrite(batches)) => (None, batches.clone()),
+        })
+    }
+
+    pub fn append(&mut self, id: &TableId, batches: Vec<RecordBatch>) -> Result<(), StorageError> {
+        self.snapshot_of(id)?;
+        if batches.is_empty() {
+            return Ok(());
+        }
+        match self
+            .writes
+            .entry(id.clone())
+            .or_insert_with(|| TableWrite::Append(vec![]))
+        {
+            TableWrite::Append(pending) | TableWrite::Overwrite(pending) => pending.extend(batches),
+        }
+        Ok(())
+    }
+
+    pub fn overwrite(
+        &mut self,
+        id: &TableId,
+        batches: Vec<RecordBatch>,
+    ) -> Result<(), StorageError> {
+        self.snapshot_of(id)?;
+        self.writes
+            .insert(id.clone(), TableWrite::Overwrite(batches));
+        Ok(())
+    }
+
+    /// Finish the transaction and take its writes after checking that none of the written
+    /// tables was changed by another commit since the snapshot. The writes are discarded
+    /// on a conflict.
+    pub fn commit(
+        &mut self,
+        committed: &HashMap<TableId, Versioned<D>>,
+    ) -> Result<BTreeMap<TableId, TableWrite>, StorageError> {
+        self.check_active()?;
+        self.finished = true;
+        let writes = std::mem::take(&mut self.writes);
+        for id in writes.keys() {
+            let current = committed
+                .get(id)
+                .ok_or(StorageError::TableNotFound(id.clone()))?;
+            if current.version != self.snapshot[id].version {
+                return Err(StorageError::WriteConflict(id.clone()));
+            }
+        }
+        Ok(writes)
+    }
+
+    /// finish the transaction discarding its writes
+    pub fn rollback(&mut self) {
+        self.finished = true;
+        self.writes.clear();
+    }
+
+    fn snapshot_of(&self, id: &TableId) -> Result<&Versioned<D>, StorageError> {
+        self.check_active()?;
+        self.snapshot
+            .get(id)
+            .ok_or(StorageError::TableNotFound(id.clone()))
+    }
+
+    fn check_active(&self) -> Result<(), StorageError> {
+        if self.finished {
+            return Err(StorageError::TransactionFinished);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod mvcc_test {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::Int32Array,
+        datatypes::{DataType, Field, Schema},
+    };
+
+    use super::*;
+
+    fn batch(values: Vec<i32>) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Int32Array::from(values))]).unwrap()
+    }
+
+    fn committed(version: u64) -> HashMap<TableId, Versioned<usize>> {
+        HashMap::from([("t".to_string(), Versioned { version, data: 3 })])
+    }
+
+    #[test]
+    fn test_txn_reads_own_writes_and_detects_conflicts() -> Result<(), StorageError> {
+        let id = "t".to_string();
+        let mut txn = TxnState::new(committed(1));
+        assert_eq!(txn.visible(&id)?, (Some(3), vec![]));
+        txn.append(&id, vec![batch(vec![1])])?;
+        assert_eq!(txn.visible(&id)?.1.len(), 1);
+        txn.overwrite(&id, vec![batch(vec![2])])?;
+        txn.append(&id, vec![batch(vec![3])])?;
+        assert_eq!(
+            txn.visible(&id)?,
+            (None, vec![batch(vec![2]), batch(vec![3])])
+        );
+
+        // another transaction committed to the table after the snapshot
+        assert!(matches!(
+            txn.commit(&committed(2)),
+            Err(StorageError::WriteConflict(_))
+        ));
+        assert!(matches!(
+            txn.visible(&id),
+            Err(StorageError::TransactionFinished)
+        ));
+
+        let mut txn = TxnState::new(committed(1));
+        txn.append(&id, vec![batch(vec![1])])?;
+        let writes = txn.commit(&committed(1))?;
+        assert!(matches!(writes.get(&id), Some(TableWrite::Append(b)) if b.len() == 1));
+
+        // reads do not conflict
+        let mut txn = TxnState::new(committed(1));
+        txn.visible(&id)?;
+        assert!(txn.commit(&committed(2))?.is_empty());
+        Ok(())
+    }
+}
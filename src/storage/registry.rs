@@ -0,0 +1,264 @@
+use std::sync::{Arc, RwLock};
+
+use arrow::array::RecordBatch;
+
+use crate::{
+    catalog::{ColumnCatalog, RootCatalog, TableStatistics},
+    storage::{
+        system::{StatisticsMap, SystemStorage},
+        CsvConfig, IpcConfig, JsonConfig, Storage, StorageError, Table, Transaction,
+    },
+};
+
+/// Mounts storages of any kind into one namespace of tables, so that a single statement
+/// can read and write tables of different sources. A table belongs to the storage having
+/// it, and new tables are created in the first storage mounted, the default one.
+///
+/// The registry also keeps the statistics of its tables, which are not persisted, and
+/// mounts the system tables describing them.
+pub struct StorageRegistry {
+    storages: RwLock<Vec<Arc<dyn Storage>>>,
+    statistics: StatisticsMap,
+}
+
+/// A transaction on every mounted storage. They are committed one after another, so a
+/// commit is only atomic within each storage: a failing commit rolls back the storages
+/// not committed yet, while those already committed stay so.
+struct RegistryTransaction {
+    txns: Vec<Arc<dyn Transaction>>,
+}
+
+impl StorageRegistry {
+    pub fn new(default: Arc<dyn Storage>) -> Self {
+        let statistics = StatisticsMap::default();
+        let system = Arc::new(SystemStorage::new(statistics.clone()));
+        Self {
+            storages: RwLock::new(vec![default, system]),
+            statistics,
+        }
+    }
+
+    /// mount another storage, whose tables must not clash with the tables mounted already
+    pub fn mount(&self, storage: Arc<dyn Storage>) -> Result<(), StorageError> {
+        let mut storages = self.storages.write().unwrap();
+        let catalog = storage.get_catalog();
+        for mounted in storages.iter() {
+            if let Some(id) = mounted
+                .get_catalog()
+                .tables
+                .into_keys()
+                .find(|id| catalog.tables.contains_key(id))
+            {
+                return Err(StorageError::TableExists(id));
+            }
+        }
+        storages.push(storage);
+        Ok(())
+    }
+
+    fn default_storage(&self) -> Arc<dyn Storage> {
+        self.storages.read().unwrap()[0].clone()
+    }
+
+    fn owner(&self, id: &str) -> Option<Arc<dyn Storage>> {
+        self.storages
+            .read()
+            .unwrap()
+            .iter()
+            .find(|storage| storage.get_catalog().tables.contains_key(id))
+            .cloned()
+    }
+
+    /// the storage to create the table in, which is the default one unless any storage
+    /// has the table already
+    fn storage_for_new_table(&self, id: &str) -> Result<Arc<dyn Storage>, StorageError> {
+        if self.owner(id).is_some() {
+            return Err(StorageError::TableExists(id.to_string()));
+        }
+        Ok(self.default_storage())
+    }
+}
+
+impl Storage for StorageRegistry {
+    fn create_csv_table(
+        &self,
+        id: String,
+        filepath: String,
+        config: CsvConfig,
+    ) -> Result<(), StorageError> {
+        self.storage_for_new_table(&id)?
+            .create_csv_table(id, filepath, config)
+    }
+
+    fn create_json_table(
+        &self,
+        id: String,
+        location: String,
+        config: JsonConfig,
+    ) -> Result<(), StorageError> {
+        self.storage_for_new_table(&id)?
+            .create_json_table(id, location, config)
+    }
+
+    fn create_ipc_table(
+        &self,
+        id: String,
+        location: String,
+        config: IpcConfig,
+    ) -> Result<(), StorageError> {
+        self.storage_for_new_table(&id)?
+            .create_ipc_table(id, location, config)
+    }
+
+    fn create_mem_table(&self, id: String, data: Vec<RecordBatch>) -> Result<(), StorageError> {
+        self.storage_for_new_table(&id)?.create_mem_table(id, data)
+    }
+
+    fn create_table(&self, id: String, columns: Vec<ColumnCatalog>) -> Result<(), StorageError> {
+        self.storage_for_new_table(&id)?.create_table(id, columns)
+    }
+
+    fn drop_table(&self, id: String) -> Result<(), StorageError> {
+        self.owner(&id)
+            .ok_or(StorageError::TableNotFound(id.clone()))?
+            .drop_table(id.clone())?;
+        self.statistics.write().unwrap().remove(&id);
+        Ok(())
+    }
+
+    fn set_statistics(&self, id: String, statistics: TableStatistics) -> Result<(), StorageError> {
+        if self.owner(&id).is_none() {
+            return Err(StorageError::TableNotFound(id));
+        }
+        self.statistics
+            .write()
+            .unwrap()
+            .insert(id, Arc::new(statistics));
+        Ok(())
+    }
+
+    fn begin(&self) -> Result<Arc<dyn Transaction>, StorageError> {
+        let txns = self
+            .storages
+            .read()
+            .unwrap()
+            .iter()
+            .map(|storage| storage.begin())
+            .collect::<Result<_, _>>()?;
+        Ok(Arc::new(RegistryTransaction { txns }))
+    }
+
+    fn get_catalog(&self) -> RootCatalog {
+        let mut catalog = RootCatalog::new();
+        for storage in self.storages.read().unwrap().iter() {
+            for (id, table) in storage.get_catalog().tables {
+                catalog.tables.entry(id).or_insert(table);
+            }
+        }
+        let statistics = self.statistics.read().unwrap();
+        for (id, table) in catalog.tables.iter_mut() {
+            table.statistics = statistics.get(id).cloned();
+        }
+        catalog
+    }
+}
+
+impl Transaction for RegistryTransaction {
+    fn get_table(&self, id: String) -> Result<Arc<dyn Table>, StorageError> {
+        for txn in &self.txns {
+            match txn.get_table(id.clone()) {
+                Err(StorageError::TableNotFound(_)) => continue,
+                result => return result,
+            }
+        }
+        Err(StorageError::TableNotFound(id))
+    }
+
+    fn commit(&self) -> Result<(), StorageError> {
+        let mut txns = self.txns.iter();
+        while let Some(txn) = txns.next() {
+            if let Err(e) = txn.commit() {
+                txns.for_each(|txn| txn.rollback());
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn rollback(&self) {
+        for txn in &self.txns {
+            txn.rollback();
+        }
+    }
+}
+
+#[cfg(test)]
+mod registry_test {
+    use arrow::{
+        array::Int32Array,
+        datatypes::{DataType, Field, Schema},
+    };
+
+    use super::*;
+    use crate::storage::{CsvStorage, InMemoryStorage};
+
+    fn build_record_batch() -> Result<RecordBatch, StorageError> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        Ok(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?)
+    }
+
+    #[test]
+    fn test_registry_routes_tables_to_their_storage() -> Result<(), StorageError> {
+        let memory = Arc::new(InMemoryStorage::new());
+        let csv = Arc::new(CsvStorage::new());
+        csv.create_csv_table(
+            "employee".to_string(),
+            "./tests/csv/sample.csv".to_string(),
+            CsvConfig::default(),
+        )?;
+        let registry = StorageRegistry::new(memory.clone());
+        registry.mount(csv.clone())?;
+
+        // new tables are created in the default storage
+        registry.create_mem_table("t".to_string(), vec![build_record_batch()?])?;
+        assert!(memory.get_catalog().get_table_by_name("t").is_some());
+        assert!(matches!(
+            registry.create_mem_table("employee".to_string(), vec![]),
+            Err(StorageError::TableExists(_))
+        ));
+        let catalog = registry.get_catalog();
+        assert!(catalog.get_table_by_name("t").is_some());
+        assert!(catalog.get_table_by_name("employee").is_some());
+
+        let txn = registry.begin()?;
+        let mut reader = txn.get_table("employee".to_string())?.read()?;
+        assert_eq!(reader.next_batch()?.unwrap().num_rows(), 4);
+        let table = txn.get_table("t".to_string())?;
+        table.append(build_record_batch()?)?;
+        txn.commit()?;
+        let mut reader = memory.begin()?.get_table("t".to_string())?.read()?;
+        let rows: usize = std::iter::from_fn(|| reader.next_batch().transpose())
+            .map(|batch| batch.map(|b| b.num_rows()))
+            .sum::<Result<_, _>>()?;
+        assert_eq!(rows, 6);
+
+        // a storage whose tables clash with the mounted ones is rejected
+        assert!(matches!(
+            registry.mount(csv),
+            Err(StorageError::TableExists(_))
+        ));
+        registry.drop_table("employee".to_string())?;
+        assert!(registry
+            .get_catalog()
+            .get_table_by_name("employee")
+            .is_none());
+        assert!(matches!(
+            registry.begin()?.get_table("employee".to_string()),
+            Err(StorageError::TableNotFound(_))
+        ));
+        Ok(())
+    }
+}
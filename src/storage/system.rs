@@ -0,0 +1,164 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use arrow::{
+    array::{ArrayRef, Int64Array, RecordBatch, StringArray},
+    datatypes::{DataType, Field, Schema},
+};
+use itertools::Itertools;
+
+use crate::{
+    catalog::{ColumnCatalog, ColumnDesc, RootCatalog, TableCatalog, TableId, TableStatistics},
+    storage::{Storage, StorageError, Table, TableReader, Transaction},
+};
+
+/// The system table of the statistics collected by `ANALYZE`, a row per analyzed column.
+pub static COLUMN_STATISTICS_TABLE_NAME: &str = "column_statistics";
+
+/// The statistics of the tables by table id, shared by a registry and its system tables.
+pub(crate) type StatisticsMap = Arc<RwLock<HashMap<TableId, Arc<TableStatistics>>>>;
+
+/// The read-only system tables, which describe the other tables of a registry.
+pub(crate) struct SystemStorage {
+    statistics: StatisticsMap,
+}
+
+/// The system tables as of the beginning of a transaction.
+struct SystemTransaction {
+    statistics: HashMap<TableId, Arc<TableStatistics>>,
+}
+
+struct SystemTable {
+    batch: RecordBatch,
+}
+
+struct SystemTableReader {
+    batch: Option<RecordBatch>,
+}
+
+impl SystemStorage {
+    pub(crate) fn new(statistics: StatisticsMap) -> Self {
+        Self { statistics }
+    }
+}
+
+impl Storage for SystemStorage {
+    fn begin(&self) -> Result<Arc<dyn Transaction>, StorageError> {
+        Ok(Arc::new(SystemTransaction {
+            statistics: self.statistics.read().unwrap().clone(),
+        }))
+    }
+
+    fn get_catalog(&self) -> RootCatalog {
+        let columns = column_statistics_schema()
+            .fields()
+            .iter()
+            .map(|f| ColumnCatalog {
+                id: f.name().clone(),
+                desc: ColumnDesc {
+                    name: f.name().clone(),
+                    data_type: f.data_type().clone(),
+                    nullable: f.is_nullable(),
+                    default: None,
+                },
+            })
+            .collect::<Vec<_>>();
+        let id = COLUMN_STATISTICS_TABLE_NAME.to_string();
+        let table = TableCatalog {
+            id: id.clone(),
+            name: id.clone(),
+            column_ids: columns.iter().map(|c| c.id.clone()).collect(),
+            columns: columns.into_iter().map(|c| (c.id.clone(), c)).collect(),
+            statistics: None,
+        };
+        let mut catalog = RootCatalog::new();
+        catalog.tables.insert(id, table);
+        catalog
+    }
+}
+
+impl Transaction for SystemTransaction {
+    fn get_table(&self, id: String) -> Result<Arc<dyn Table>, StorageError> {
+        if id != COLUMN_STATISTICS_TABLE_NAME {
+            return Err(StorageError::TableNotFound(id));
+        }
+        Ok(Arc::new(SystemTable {
+            batch: self.column_statistics()?,
+        }))
+    }
+
+    fn commit(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    fn rollback(&self) {}
+}
+
+impl SystemTransaction {
+    /// the rows of `column_statistics`, ordered by table and column
+    fn column_statistics(&self) -> Result<RecordBatch, StorageError> {
+        let mut table_names = vec![];
+        let mut column_names = vec![];
+        let mut row_counts = vec![];
+        let mut null_counts = vec![];
+        let mut distinct_counts = vec![];
+        let mut mins = vec![];
+        let mut maxs = vec![];
+        let mut histograms = vec![];
+        for (table, statistics) in self.statistics.iter().sorted_by_key(|(id, _)| *id) {
+            for (column, stats) in &statistics.columns {
+                table_names.push(table.clone());
+                column_names.push(column.clone());
+                row_counts.push(statistics.row_count as i64);
+                null_counts.push(stats.null_count as i64);
+                distinct_counts.push(stats.distinct_count as i64);
+                mins.push((!stats.min.is_null()).then(|| stats.min.to_string()));
+                maxs.push((!stats.max.is_null()).then(|| stats.max.to_string()));
+                histograms.push(format!("[{}]", stats.histogram.iter().join(", ")));
+            }
+        }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(table_names)),
+            Arc::new(StringArray::from(column_names)),
+            Arc::new(Int64Array::from(row_counts)),
+            Arc::new(Int64Array::from(null_counts)),
+            Arc::new(Int64Array::from(distinct_counts)),
+            Arc::new(StringArray::from(mins)),
+            Arc::new(StringArray::from(maxs)),
+            Arc::new(StringArray::from(histograms)),
+        ];
+        Ok(RecordBatch::try_new(
+            Arc::new(column_statistics_schema()),
+            columns,
+        )?)
+    }
+}
+
+impl Table for SystemTable {
+    fn read(&self) -> Result<Box<dyn TableReader>, StorageError> {
+        Ok(Box::new(SystemTableReader {
+            batch: Some(self.batch.clone()),
+        }))
+    }
+}
+
+impl TableReader for SystemTableReader {
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>, StorageError> {
+        Ok(self.batch.take())
+    }
+}
+
+fn column_statistics_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("row_count", DataType::Int64, false),
+        Field::new("null_count", DataType::Int64, false),
+        Field::new("distinct_count", DataType::Int64, false),
+        Field::new("min", DataType::Utf8, true),
+        Field::new("max", DataType::Utf8, true),
+        Field::new("histogram", DataType::Utf8, false),
+    ])
+}
@@ -0,0 +1,182 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::storage::StorageError;
+
+/// payload length (u32), checksum (u32) and lsn (u64) in front of every payload
+const RECORD_HEADER_SIZE: usize = 16;
+
+/// An append-only log of opaque records, each one identified by a log sequence number.
+///
+/// Every record is framed as `[payload length: u32][crc32: u32][lsn: u64][payload]` in
+/// little endian, where the checksum covers the lsn and the payload, and is synced to
+/// disk before `append` returns. A record cut short by a crash or failing its checksum
+/// ends the log, so it and everything after it are discarded when the log is opened.
+pub struct WriteAheadLog {
+    file: File,
+    size: u64,
+    next_lsn: u64,
+}
+
+pub struct WalRecord {
+    pub lsn: u64,
+    pub payload: Vec<u8>,
+}
+
+impl WriteAheadLog {
+    /// open the log, returning the intact records from `min_lsn` on. Records below it
+    /// are already checkpointed, and the lsn of new records never goes below it.
+    pub fn open(path: &Path, min_lsn: u64) -> Result<(Self, Vec<WalRecord>), StorageError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        let mut records = vec![];
+        let mut offset = 0;
+        while let Some((record, len)) = decode_record(&bytes[offset..]) {
+            records.push(record);
+            offset += len;
+        }
+        // cut the torn tail, so that new records are not appended after garbage
+        if offset < bytes.len() {
+            file.set_len(offset as u64)?;
+            file.sync_all()?;
+        }
+        file.seek(SeekFrom::Start(offset as u64))?;
+
+        let next_lsn = records.last().map_or(0, |r| r.lsn + 1).max(min_lsn);
+        records.retain(|r| r.lsn >= min_lsn);
+        let log = Self {
+            file,
+            size: offset as u64,
+            next_lsn,
+        };
+        Ok((log, records))
+    }
+
+    pub fn next_lsn(&self) -> u64 {
+        self.next_lsn
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// durably append the record, returning its lsn
+    pub fn append(&mut self, payload: &[u8]) -> Result<u64, StorageError> {
+        let lsn = self.next_lsn;
+        let mut record = Vec::with_capacity(RECORD_HEADER_SIZE + payload.len());
+        record.extend((payload.len() as u32).to_le_bytes());
+        record.extend(checksum(lsn, payload).to_le_bytes());
+        record.extend(lsn.to_le_bytes());
+        record.extend(payload);
+
+        let written = self
+            .file
+            .write_all(&record)
+            .and_then(|_| self.file.sync_data());
+        if let Err(e) = written {
+            // drop what was partially written, later records must follow the last intact one
+            let _ = self.file.set_len(self.size);
+            let _ = self.file.seek(SeekFrom::Start(self.size));
+            return Err(e.into());
+        }
+        self.size += record.len() as u64;
+        self.next_lsn += 1;
+        Ok(lsn)
+    }
+
+    /// discard all records once they are checkpointed
+    pub fn reset(&mut self) -> Result<(), StorageError> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.sync_all()?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn checksum(lsn: u64, payload: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&lsn.to_le_bytes());
+    hasher.update(payload);
+    hasher.finalize()
+}
+
+/// decode the record at the start of the bytes with its encoded length, or None if the
+/// record is incomplete or corrupted
+fn decode_record(bytes: &[u8]) -> Option<(WalRecord, usize)> {
+    let header = bytes.get(..RECORD_HEADER_SIZE)?;
+    let len = u32::from_le_bytes(header[0..4].try_into().unwrap()) as usize;
+    let crc = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    let lsn = u64::from_le_bytes(header[8..16].try_into().unwrap());
+    let payload = bytes.get(RECORD_HEADER_SIZE..RECORD_HEADER_SIZE.checked_add(len)?)?;
+    if checksum(lsn, payload) != crc {
+        return None;
+    }
+    let record = WalRecord {
+        lsn,
+        payload: payload.to_vec(),
+    };
+    Some((record, RECORD_HEADER_SIZE + len))
+}
+
+#[cfg(test)]
+mod wal_test {
+    use super::*;
+
+    #[test]
+    fn test_wal_discards_torn_records() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("WAL");
+        let payloads: Vec<Vec<u8>> = vec![b"a".to_vec(), vec![], b"ccc".to_vec()];
+        let mut ends = vec![];
+        {
+            let (mut log, records) = WriteAheadLog::open(&path, 0)?;
+            assert!(records.is_empty());
+            for payload in &payloads {
+                log.append(payload)?;
+                ends.push(log.size());
+            }
+        }
+        let bytes = std::fs::read(&path)?;
+
+        for offset in 0..=bytes.len() {
+            std::fs::write(&path, &bytes[..offset])?;
+            let complete = ends.iter().filter(|end| **end as usize <= offset).count();
+            let (mut log, records) = WriteAheadLog::open(&path, 0)?;
+            assert_eq!(records.len(), complete, "truncated at {offset}");
+            for (i, record) in records.iter().enumerate() {
+                assert_eq!(record.lsn, i as u64);
+                assert_eq!(record.payload, payloads[i]);
+            }
+            // a record appended after recovery follows the intact ones
+            assert_eq!(log.append(b"d")?, complete as u64);
+            drop(log);
+            let (_, records) = WriteAheadLog::open(&path, 0)?;
+            assert_eq!(records.len(), complete + 1);
+        }
+
+        // a flipped byte fails the checksum and ends the log
+        let mut corrupted = bytes.clone();
+        corrupted[ends[0] as usize + RECORD_HEADER_SIZE - 1] ^= 0xff;
+        std::fs::write(&path, &corrupted)?;
+        let (_, records) = WriteAheadLog::open(&path, 0)?;
+        assert_eq!(records.len(), 1);
+
+        // checkpointed records are skipped
+        std::fs::write(&path, &bytes)?;
+        let (log, records) = WriteAheadLog::open(&path, 2)?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(log.next_lsn(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use arrow::{array::RecordBatch, datatypes::Schema, ipc, json::LineDelimitedWriter};
+
+use crate::storage::{CsvConfig, StorageError};
+
+/// Writes batches one after another into a file, which is created or truncated, in a
+/// format the tables over files read back. The file is only complete once finished.
+pub struct FileWriter {
+    writer: BatchWriter,
+}
+
+enum BatchWriter {
+    Csv(Box<arrow::csv::Writer<BufWriter<File>>>),
+    Json(LineDelimitedWriter<BufWriter<File>>),
+    Ipc(ipc::writer::FileWriter<BufWriter<File>>),
+}
+
+impl FileWriter {
+    /// write a csv file with the header, delimiter, datetime format and null marker of
+    /// the config
+    pub fn csv(path: &Path, config: &CsvConfig) -> Result<Self, StorageError> {
+        Ok(Self {
+            writer: BatchWriter::Csv(Box::new(config.writer(create_file(path)?))),
+        })
+    }
+
+    /// write a newline-delimited json file
+    pub fn json(path: &Path) -> Result<Self, StorageError> {
+        Ok(Self {
+            writer: BatchWriter::Json(LineDelimitedWriter::new(create_file(path)?)),
+        })
+    }
+
+    /// write an Arrow IPC file of the batches of the schema
+    pub fn ipc(path: &Path, schema: &Schema) -> Result<Self, StorageError> {
+        Ok(Self {
+            writer: BatchWriter::Ipc(ipc::writer::FileWriter::try_new(
+                create_file(path)?,
+                schema,
+            )?),
+        })
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), StorageError> {
+        match &mut self.writer {
+            BatchWriter::Csv(writer) => writer.write(batch)?,
+            BatchWriter::Json(writer) => writer.write(batch)?,
+            BatchWriter::Ipc(writer) => writer.write(batch)?,
+        }
+        Ok(())
+    }
+
+    /// write the end of the file, like the footer of an IPC file, and flush it
+    pub fn finish(self) -> Result<(), StorageError> {
+        let mut file = match self.writer {
+            BatchWriter::Csv(writer) => writer.into_inner(),
+            BatchWriter::Json(mut writer) => {
+                writer.finish()?;
+                writer.into_inner()
+            }
+            BatchWriter::Ipc(mut writer) => {
+                writer.finish()?;
+                writer.into_inner()?
+            }
+        };
+        file.flush()?;
+        Ok(())
+    }
+}
+
+fn create_file(path: &Path) -> Result<BufWriter<File>, StorageError> {
+    Ok(BufWriter::new(File::create(path)?))
+}
+
+#[cfg(test)]
+mod writer_test {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+
+    use super::*;
+    use crate::storage::{CsvTable, IpcConfig, IpcTable, JsonConfig, JsonTable, Table};
+
+    #[test]
+    fn test_written_files_are_read_back() -> Result<(), StorageError> {
+        let dir = tempfile::tempdir()?;
+        let batch = RecordBatch::try_from_iter([
+            ("a", Arc::new(Int32Array::from(vec![1, 2])) as _),
+            ("b", Arc::new(StringArray::from(vec![Some("x"), None])) as _),
+        ])?;
+
+        let config = CsvConfig::new()
+            .with_delimiter(b'|')
+            .with_null_marker("NULL")
+            .with_schema(batch.schema());
+        let csv = dir.path().join("t.csv");
+        let mut writer = FileWriter::csv(&csv, &config)?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        assert_eq!(std::fs::read_to_string(&csv)?, "a|b\n1|x\n2|NULL\n");
+
+        let json = dir.path().join("t.json");
+        let mut writer = FileWriter::json(&json)?;
+        writer.write(&batch)?;
+        writer.finish()?;
+
+        let ipc = dir.path().join("t.arrow");
+        let mut writer = FileWriter::ipc(&ipc, &batch.schema())?;
+        writer.write(&batch)?;
+        writer.write(&batch)?;
+        writer.finish()?;
+
+        let path = |p: &Path| p.to_string_lossy().to_string();
+        let tables: Vec<Box<dyn Table>> = vec![
+            Box::new(CsvTable::new("t".to_string(), path(&csv), config)?),
+            Box::new(JsonTable::new(
+                "t".to_string(),
+                path(&json),
+                JsonConfig::new().with_schema(batch.schema()),
+            )?),
+            Box::new(IpcTable::new(
+                "t".to_string(),
+                path(&ipc),
+                IpcConfig::new(),
+            )?),
+        ];
+        for table in tables {
+            let read = table.read()?.next_batch()?.unwrap();
+            assert_eq!(read.columns(), batch.columns());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::datatypes::DataType;
+
+use crate::{
+    binder::expression::{
+        agg_func::BoundAggFunc, binary_op::BoundBinaryOp, BoundExpr, BoundInputRef, BoundIsNull,
+        BoundOrderBy, BoundTypeCast,
+    },
+    catalog::{ColumnCatalog, ColumnDesc, RootCatalogRef},
+    optimizer::{
+        logical_filter::LogicalFilter, logical_project::LogicalProject,
+        logical_table_scan::LogicalTableScan, logical_values::LogicalValues, LogicalAgg, PlanRef,
+    },
+    substrait::{
+        from_substrait_type, proto, SubstraitError, AGG_FUNCTIONS, BINARY_OP_FUNCTIONS,
+        IS_NULL_FUNCTIONS,
+    },
+    types::ScalarValue,
+};
+
+/// Translates a Substrait plan into a logical plan on the tables of the catalog. The
+/// exprs of the plan refer to the fields of their inputs by input refs, as if resolved by
+/// the `InputRefRewriter`, so the plan is ready for the `PhysicalRewriter`.
+pub struct SubstraitConsumer {
+    catalog: RootCatalogRef,
+    /// the names of the functions declared by the plan, by anchor
+    functions: HashMap<u32, String>,
+}
+
+impl SubstraitConsumer {
+    pub fn new(catalog: RootCatalogRef) -> Self {
+        Self {
+            catalog,
+            functions: HashMap::new(),
+        }
+    }
+
+    pub fn consume(&mut self, plan: &proto::Plan) -> Result<PlanRef, SubstraitError> {
+        self.functions = plan
+            .extensions
+            .iter()
+            .filter_map(|e| e.extension_function.as_ref())
+            // the name may be followed by the signature of the function, like `add:i32_i32`
+            .map(|f| {
+                let name = f.name.split(':').next().unwrap_or_default();
+                (f.function_anchor, name.to_string())
+            })
+            .collect();
+        let rel = match plan.relations.as_slice() {
+            [proto::PlanRel::Root(root)] => &root.input,
+            [proto::PlanRel::Rel(rel)] => rel,
+            relations => {
+                return Err(SubstraitError::InvalidPlan(format!(
+                    "expected a single relation, got {}",
+                    relations.len()
+                )))
+            }
+        };
+        Ok(self.consume_rel(rel)?.0)
+    }
+
+    /// the plan of the relation, with the types of its output fields
+    fn consume_rel(&self, rel: &proto::Rel) -> Result<(PlanRef, Vec<DataType>), SubstraitError> {
+        match rel {
+            proto::Rel::Read(read) => {
+                let types = read
+                    .base_schema
+                    .struct_type
+                    .types
+                    .iter()
+                    .map(from_substrait_type)
+                    .collect::<Vec<_>>();
+                if types.len() != read.base_schema.names.len() {
+                    return Err(SubstraitError::InvalidPlan(
+                        "the base schema must have as many names as types".to_string(),
+                    ));
+                }
+                let plan: PlanRef = match &read.read_type {
+                    proto::ReadType::NamedTable(table) => {
+                        let name = table.names.last().cloned().unwrap_or_default();
+                        let Some(table) = self.catalog.get_table_by_name(&name) else {
+                            return Err(SubstraitError::TableNotFound(name));
+                        };
+                        let mut columns = vec![];
+                        for (column, data_type) in read.base_schema.names.iter().zip(&types) {
+                            match table.get_column_by_name(column) {
+                                Some(c) if c.desc.data_type == *data_type => columns.push(c),
+                                Some(c) => {
+                                    return Err(SubstraitError::InvalidPlan(format!(
+                                        "column {column} is {} rather than {data_type}",
+                                        c.desc.data_type
+                                    )))
+                                }
+                                None => {
+                                    return Err(SubstraitError::ColumnNotFound(
+                                        column.clone(),
+                                        name,
+                                    ))
+                                }
+                            }
+                        }
+                        let filter = read
+                            .filter
+                            .as_ref()
+                            .map(|f| self.consume_expr(f, &types))
+                            .transpose()?;
+                        Arc::new(
+                            LogicalTableScan::new(table.id.clone(), columns)
+                                .with_filter(filter)
+                                .with_statistics(table.statistics.clone()),
+                        )
+                    }
+                    proto::ReadType::VirtualTable(table) => {
+                        let columns = read
+                            .base_schema
+                            .names
+                            .iter()
+                            .zip(&types)
+                            .map(|(name, data_type)| ColumnCatalog {
+                                id: name.clone(),
+                                desc: ColumnDesc {
+                                    name: name.clone(),
+                                    data_type: data_type.clone(),
+                                    nullable: true,
+                                    default: None,
+                                },
+                            })
+                            .collect();
+                        let rows = table
+                            .expressions
+                            .iter()
+                            .map(|row| {
+                                row.fields
+                                    .iter()
+                                    .map(|e| self.consume_expr(e, &[]))
+                                    .collect::<Result<Vec<_>, _>>()
+                            })
+                            .collect::<Result<_, _>>()?;
+                        let plan: PlanRef = Arc::new(LogicalValues::new(columns, rows));
+                        match &read.filter {
+                            Some(filter) => Arc::new(LogicalFilter::new(
+                                self.consume_expr(filter, &types)?,
+                                plan,
+                            )),
+                            None => plan,
+                        }
+                    }
+                };
+                self.emit(read.common.as_ref(), plan, types)
+            }
+            proto::Rel::Filter(filter) => {
+                let (input, types) = self.consume_rel(&filter.input)?;
+                let condition = self.consume_expr(&filter.condition, &types)?;
+                let plan = Arc::new(LogicalFilter::new(condition, input));
+                self.emit(filter.common.as_ref(), plan, types)
+            }
+            proto::Rel::Project(project) => {
+                let (input, input_types) = self.consume_rel(&project.input)?;
+                // the output of a projection starts with the fields of its input
+                let mut exprs = input_refs(&input_types);
+                for expr in &project.expressions {
+                    exprs.push(self.consume_expr(expr, &input_types)?);
+                }
+                let exprs = match project.common.as_ref().and_then(|c| c.emit.as_ref()) {
+                    Some(emit) => select(&exprs, &emit.output_mapping)?,
+                    None => exprs,
+                };
+                let types = exprs.iter().map(|e| e.return_type().unwrap()).collect();
+                Ok((Arc::new(LogicalProject::new(exprs, input)), types))
+            }
+            proto::Rel::Aggregate(aggregate) => {
+                let (input, input_types) = self.consume_rel(&aggregate.input)?;
+                let group_by = match aggregate.groupings.as_slice() {
+                    [] => vec![],
+                    [grouping] => grouping
+                        .grouping_expressions
+                        .iter()
+                        .map(|e| self.consume_expr(e, &input_types))
+                        .collect::<Result<_, _>>()?,
+                    _ => {
+                        return Err(SubstraitError::Unsupported(
+                            "more than one grouping set".to_string(),
+                        ))
+                    }
+                };
+                let agg_funcs = aggregate
+                    .measures
+                    .iter()
+                    .map(|measure| self.consume_measure(measure, &input_types))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // an aggregation outputs the grouping exprs followed by the measures
+                let types = group_by
+                    .iter()
+                    .chain(&agg_funcs)
+                    .map(|e| e.return_type().unwrap())
+                    .collect();
+                let plan = Arc::new(LogicalAgg::new(agg_funcs, group_by, input));
+                self.emit(aggregate.common.as_ref(), plan, types)
+            }
+        }
+    }
+
+    /// project the fields emitted by a relation, unless it emits all its fields
+    fn emit(
+        &self,
+        common: Option<&proto::RelCommon>,
+        plan: PlanRef,
+        types: Vec<DataType>,
+    ) -> Result<(PlanRef, Vec<DataType>), SubstraitError> {
+        let Some(emit) = common.and_then(|c| c.emit.as_ref()) else {
+            return Ok((plan, types));
+        };
+        let exprs = select(&input_refs(&types), &emit.output_mapping)?;
+        let types = emit
+            .output_mapping
+            .iter()
+            .map(|i| types[*i].clone())
+            .collect();
+        Ok((Arc::new(LogicalProject::new(exprs, plan)), types))
+    }
+
+    fn consume_measure(
+        &self,
+        measure: &proto::Measure,
+        input_types: &[DataType],
+    ) -> Result<BoundExpr, SubstraitError> {
+        let function = &measure.measure;
+        if function.invocation == proto::AggregationInvocation::Distinct {
+            return Err(SubstraitError::Unsupported(
+                "aggregations of distinct values".to_string(),
+            ));
+        }
+        let name = self.function_name(function.function_reference)?;
+        let Some((func, _, _)) = AGG_FUNCTIONS.iter().find(|(_, n, _)| *n == name) else {
+            return Err(SubstraitError::UnknownFunction(name.to_string()));
+        };
+        let exprs = function
+            .arguments
+            .iter()
+            .map(|arg| self.consume_expr(&arg.value, input_types))
+            .collect::<Result<_, _>>()?;
+        let order_by = function
+            .sorts
+            .iter()
+            .map(|sort| {
+                use proto::SortDirection::*;
+                Ok(BoundOrderBy {
+                    expr: self.consume_expr(&sort.expr, input_types)?,
+                    asc: matches!(sort.direction, AscNullsFirst | AscNullsLast),
+                    nulls_first: matches!(sort.direction, AscNullsFirst | DescNullsFirst),
+                })
+            })
+            .collect::<Result<_, SubstraitError>>()?;
+        let filter = measure
+            .filter
+            .as_ref()
+            .map(|f| self.consume_expr(f, input_types).map(Box::new))
+            .transpose()?;
+        Ok(BoundExpr::AggFunc(BoundAggFunc {
+            func: func.clone(),
+            exprs,
+            return_type: from_substrait_type(&function.output_type),
+            filter,
+            order_by,
+        }))
+    }
+
+    fn consume_expr(
+        &self,
+        expr: &proto::Expression,
+        input_types: &[DataType],
+    ) -> Result<BoundExpr, SubstraitError> {
+        match expr {
+            proto::Expression::Literal(literal) => {
+                Ok(BoundExpr::Constant(match &literal.literal_type {
+                    proto::LiteralType::Boolean(v) => ScalarValue::Boolean(Some(*v)),
+                    proto::LiteralType::I32(v) => ScalarValue::Int32(Some(*v)),
+                    proto::LiteralType::I64(v) => ScalarValue::Int64(Some(*v)),
+                    proto::LiteralType::Fp64(v) => ScalarValue::Float64(Some(*v)),
+                    proto::LiteralType::String(v) => ScalarValue::String(Some(v.clone())),
+                    proto::LiteralType::Null(data_type) => {
+                        ScalarValue::new_null(&from_substrait_type(data_type))
+                    }
+                }))
+            }
+            proto::Expression::Selection(reference) => {
+                let index = reference.direct_reference.struct_field.field;
+                let Some(return_type) = input_types.get(index) else {
+                    return Err(SubstraitError::InvalidPlan(format!(
+                        "field {index} is out of the {} input fields",
+                        input_types.len()
+                    )));
+                };
+                Ok(BoundExpr::InputRef(BoundInputRef {
+                    index,
+                    return_type: return_type.clone(),
+                }))
+            }
+            proto::Expression::ScalarFunction(function) => {
+                let name = self.function_name(function.function_reference)?;
+                if let Some((negated, _, _)) = IS_NULL_FUNCTIONS.iter().find(|(_, n, _)| *n == name)
+                {
+                    let [argument] = function.arguments.as_slice() else {
+                        return Err(SubstraitError::InvalidPlan(format!(
+                            "{name} takes 1 argument, got {}",
+                            function.arguments.len()
+                        )));
+                    };
+                    return Ok(BoundExpr::IsNull(BoundIsNull {
+                        expr: Box::new(self.consume_expr(&argument.value, input_types)?),
+                        negated: *negated,
+                    }));
+                }
+                let Some((op, _, _)) = BINARY_OP_FUNCTIONS.iter().find(|(_, n, _)| *n == name)
+                else {
+                    return Err(SubstraitError::UnknownFunction(name.to_string()));
+                };
+                let [left, right] = function.arguments.as_slice() else {
+                    return Err(SubstraitError::InvalidPlan(format!(
+                        "{name} takes 2 arguments, got {}",
+                        function.arguments.len()
+                    )));
+                };
+                Ok(BoundExpr::BinaryOp(BoundBinaryOp {
+                    op: op.clone(),
+                    left: Box::new(self.consume_expr(&left.value, input_types)?),
+                    right: Box::new(self.consume_expr(&right.value, input_types)?),
+                    return_type: Some(from_substrait_type(&function.output_type)),
+                }))
+            }
+            proto::Expression::Cast(cast) => Ok(BoundExpr::TypeCast(BoundTypeCast {
+                expr: Box::new(self.consume_expr(&cast.input, input_types)?),
+                cast_type: from_substrait_type(&cast.cast_type),
+            })),
+        }
+    }
+
+    fn function_name(&self, anchor: u32) -> Result<&str, SubstraitError> {
+        self.functions
+            .get(&anchor)
+            .map(|name| name.as_str())
+            .ok_or(SubstraitError::UndeclaredFunction(anchor))
+    }
+}
+
+fn input_refs(types: &[DataType]) -> Vec<BoundExpr> {
+    types
+        .iter()
+        .enumerate()
+        .map(|(index, data_type)| {
+            BoundExpr::InputRef(BoundInputRef {
+                index,
+                return_type: data_type.clone(),
+            })
+        })
+        .collect()
+}
+
+fn select(exprs: &[BoundExpr], output_mapping: &[usize]) -> Result<Vec<BoundExpr>, SubstraitError> {
+    output_mapping
+        .iter()
+        .map(|i| {
+            exprs.get(*i).cloned().ok_or_else(|| {
+                SubstraitError::InvalidPlan(format!(
+                    "emitted field {i} is out of the {} fields",
+                    exprs.len()
+                ))
+            })
+        })
+        .collect()
+}
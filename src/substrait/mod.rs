@@ -0,0 +1,253 @@
+//! Translation between logical plans and [Substrait](https://substrait.io) plans, so plans
+//! can be shipped between services, compared in tests and generated by other tools to be
+//! run here. Plans are read and written in the JSON encoding of the Substrait protobuf
+//! messages.
+//!
+//! The relations supported are reads of tables and of virtual tables of values, filters,
+//! projections and aggregations with a single grouping set.
+
+mod consumer;
+mod producer;
+pub mod proto;
+
+use arrow::datatypes::DataType;
+pub use consumer::SubstraitConsumer;
+pub use producer::SubstraitProducer;
+use sqlparser::ast::BinaryOperator;
+
+use crate::binder::expression::agg_func::AggFunc;
+
+const FUNCTIONS_ARITHMETIC: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_arithmetic.yaml";
+const FUNCTIONS_COMPARISON: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_comparison.yaml";
+const FUNCTIONS_BOOLEAN: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_boolean.yaml";
+const FUNCTIONS_AGGREGATE_GENERIC: &str = "https://github.com/substrait-io/substrait/blob/main/extensions/functions_aggregate_generic.yaml";
+const FUNCTIONS_STRING: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_string.yaml";
+
+/// the binary operators with the functions computing them, and the extensions declaring
+/// those functions
+const BINARY_OP_FUNCTIONS: &[(BinaryOperator, &str, &str)] = &[
+    (BinaryOperator::Plus, "add", FUNCTIONS_ARITHMETIC),
+    (BinaryOperator::Minus, "subtract", FUNCTIONS_ARITHMETIC),
+    (BinaryOperator::Multiply, "multiply", FUNCTIONS_ARITHMETIC),
+    (BinaryOperator::Divide, "divide", FUNCTIONS_ARITHMETIC),
+    (BinaryOperator::Modulo, "modulus", FUNCTIONS_ARITHMETIC),
+    (BinaryOperator::Eq, "equal", FUNCTIONS_COMPARISON),
+    (BinaryOperator::NotEq, "not_equal", FUNCTIONS_COMPARISON),
+    (BinaryOperator::Lt, "lt", FUNCTIONS_COMPARISON),
+    (BinaryOperator::LtEq, "lte", FUNCTIONS_COMPARISON),
+    (BinaryOperator::Gt, "gt", FUNCTIONS_COMPARISON),
+    (BinaryOperator::GtEq, "gte", FUNCTIONS_COMPARISON),
+    (BinaryOperator::And, "and", FUNCTIONS_BOOLEAN),
+    (BinaryOperator::Or, "or", FUNCTIONS_BOOLEAN),
+];
+
+/// the functions testing for nulls, with whether they are negated
+const IS_NULL_FUNCTIONS: &[(bool, &str, &str)] = &[
+    (false, "is_null", FUNCTIONS_COMPARISON),
+    (true, "is_not_null", FUNCTIONS_COMPARISON),
+];
+
+/// the aggregate functions of the standard extensions
+const AGG_FUNCTIONS: &[(AggFunc, &str, &str)] = &[
+    (AggFunc::Count, "count", FUNCTIONS_AGGREGATE_GENERIC),
+    (AggFunc::Sum, "sum", FUNCTIONS_ARITHMETIC),
+    (AggFunc::Min, "min", FUNCTIONS_ARITHMETIC),
+    (AggFunc::Max, "max", FUNCTIONS_ARITHMETIC),
+    (AggFunc::StringAgg, "string_agg", FUNCTIONS_STRING),
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum SubstraitError {
+    #[error("unsupported in substrait plans: {0}")]
+    Unsupported(String),
+    #[error("invalid substrait plan: {0}")]
+    InvalidPlan(String),
+    #[error("table {0} not found")]
+    TableNotFound(String),
+    #[error("column {0} not found in table {1}")]
+    ColumnNotFound(String, String),
+    #[error("function reference {0} is not declared")]
+    UndeclaredFunction(u32),
+    #[error("unknown function {0}")]
+    UnknownFunction(String),
+}
+
+fn to_substrait_type(data_type: &DataType) -> Result<proto::Type, SubstraitError> {
+    let kind = proto::TypeKind::default();
+    Ok(match data_type {
+        DataType::Boolean => proto::Type::Bool(kind),
+        DataType::Int32 => proto::Type::I32(kind),
+        DataType::Int64 => proto::Type::I64(kind),
+        DataType::Float64 => proto::Type::Fp64(kind),
+        DataType::Utf8 => proto::Type::String(kind),
+        _ => return Err(SubstraitError::Unsupported(format!("type {data_type}"))),
+    })
+}
+
+fn from_substrait_type(data_type: &proto::Type) -> DataType {
+    match data_type {
+        proto::Type::Bool(_) => DataType::Boolean,
+        proto::Type::I32(_) => DataType::Int32,
+        proto::Type::I64(_) => DataType::Int64,
+        proto::Type::Fp64(_) => DataType::Float64,
+        proto::Type::String(_) => DataType::Utf8,
+    }
+}
+
+#[cfg(test)]
+mod substrait_test {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use super::*;
+    use crate::{
+        binder::{expression::BoundExpr, Binder},
+        catalog::{ColumnCatalog, ColumnDesc, RootCatalog, RootCatalogRef, TableCatalog},
+        optimizer::{
+            input_ref_rewriter::InputRefRewriter, plan_rewriter::PlanRewriter, PlanNodeType,
+            PlanRef,
+        },
+        parser::parse,
+        planner::Planner,
+    };
+
+    fn catalog() -> RootCatalogRef {
+        let columns = [
+            ("a", DataType::Int32),
+            ("b", DataType::Int64),
+            ("c", DataType::Utf8),
+        ]
+        .into_iter()
+        .map(|(name, data_type)| ColumnCatalog {
+            id: name.to_string(),
+            desc: ColumnDesc {
+                name: name.to_string(),
+                data_type,
+                nullable: true,
+                default: None,
+            },
+        })
+        .collect::<Vec<_>>();
+        let table = TableCatalog {
+            id: "t".to_string(),
+            name: "t".to_string(),
+            column_ids: columns.iter().map(|c| c.id.clone()).collect(),
+            columns: columns
+                .into_iter()
+                .map(|c| (c.id.clone(), c))
+                .collect::<BTreeMap<_, _>>(),
+            statistics: None,
+        };
+        let mut catalog = RootCatalog::new();
+        catalog.tables.insert(table.id.clone(), table);
+        Arc::new(catalog)
+    }
+
+    fn plan(catalog: &RootCatalogRef, sql: &str) -> PlanRef {
+        let stmts = parse(sql).unwrap();
+        let bound = Binder::new(catalog.clone()).bind(&stmts[0]).unwrap();
+        let plan = Planner {}.plan(bound).unwrap();
+        InputRefRewriter::default().rewrite(plan)
+    }
+
+    #[test]
+    fn test_substrait_round_trip() {
+        let catalog = catalog();
+        for sql in [
+            "select a, b * 2 from t where a > 1 and c = 'x'",
+            "select c, sum(b), count(a) filter (where a > 1) from t group by c",
+            "select string_agg(c, ',' order by a desc) from t",
+            "select a + b from t",
+            "select 1 + 2, 'x'",
+        ] {
+            let plan = plan(&catalog, sql);
+            let substrait = SubstraitProducer::default().produce(plan.clone()).unwrap();
+            let json = serde_json::to_string(&substrait).unwrap();
+            let decoded: proto::Plan = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded, substrait, "{json}");
+
+            let consumed = SubstraitConsumer::new(catalog.clone())
+                .consume(&decoded)
+                .unwrap();
+            assert_eq!(format!("{consumed:?}"), format!("{plan:?}"), "{sql}");
+        }
+
+        let plan = plan(&catalog, "select a from t group by grouping sets ((a), ())");
+        assert!(matches!(
+            SubstraitProducer::default().produce(plan),
+            Err(SubstraitError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_substrait_consume_plan_of_other_producer() {
+        // fields of both input and expressions emitted, function names with signatures and
+        // 64 bit integers as strings, like other producers write them
+        let json = r#"{
+            "extensionUris": [{"extensionUriAnchor": 7, "uri": "/functions_arithmetic.yaml"}],
+            "extensions": [{"extensionFunction": {
+                "extensionUriReference": 7, "functionAnchor": 3, "name": "add:i64_i64"
+            }}],
+            "relations": [{"root": {
+                "input": {"project": {
+                    "common": {"emit": {"outputMapping": [2, 3]}},
+                    "input": {"read": {
+                        "baseSchema": {
+                            "names": ["a", "b", "c"],
+                            "struct": {"types": [
+                                {"i32": {"nullability": "NULLABILITY_NULLABLE"}},
+                                {"i64": {"nullability": "NULLABILITY_NULLABLE"}},
+                                {"string": {"nullability": "NULLABILITY_NULLABLE"}}
+                            ]}
+                        },
+                        "namedTable": {"names": ["t"]}
+                    }},
+                    "expressions": [{"scalarFunction": {
+                        "functionReference": 3,
+                        "arguments": [
+                            {"value": {"selection": {
+                                "directReference": {"structField": {"field": 1}},
+                                "rootReference": {}
+                            }}},
+                            {"value": {"literal": {"i64": "10", "nullable": false}}}
+                        ],
+                        "outputType": {"i64": {}}
+                    }}]
+                }},
+                "names": ["c", "b_plus_10"]
+            }}]
+        }"#;
+        let substrait: proto::Plan = serde_json::from_str(json).unwrap();
+        let plan = SubstraitConsumer::new(catalog())
+            .consume(&substrait)
+            .unwrap();
+        let project = plan.as_logical_project().unwrap();
+        assert_eq!(project.exprs().len(), 2);
+        assert_eq!(project.exprs()[0].return_type(), Some(DataType::Utf8));
+        let BoundExpr::BinaryOp(add) = &project.exprs()[1] else {
+            panic!("expected add, got {:?}", project.exprs()[1]);
+        };
+        assert_eq!(add.op, BinaryOperator::Plus);
+        assert_eq!(
+            *add.right,
+            BoundExpr::Constant(crate::types::ScalarValue::Int64(Some(10)))
+        );
+        assert_eq!(project.input().node_type(), PlanNodeType::LogicalTableScan);
+
+        let unknown = json.replace("\"name\": \"add:i64_i64\"", "\"name\": \"power\"");
+        let substrait: proto::Plan = serde_json::from_str(&unknown).unwrap();
+        assert!(matches!(
+            SubstraitConsumer::new(catalog()).consume(&substrait),
+            Err(SubstraitError::UnknownFunction(_))
+        ));
+        let missing = json.replace("[\"t\"]", "[\"u\"]");
+        let substrait: proto::Plan = serde_json::from_str(&missing).unwrap();
+        assert!(matches!(
+            SubstraitConsumer::new(catalog()).consume(&substrait),
+            Err(SubstraitError::TableNotFound(_))
+        ));
+    }
+}
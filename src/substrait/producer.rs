@@ -0,0 +1,328 @@
+use arrow::datatypes::DataType;
+
+use crate::{
+    binder::expression::{agg_func::BoundAggFunc, BoundExpr},
+    catalog::ColumnCatalog,
+    optimizer::{PlanNodeType, PlanRef},
+    substrait::{
+        proto, to_substrait_type, SubstraitError, AGG_FUNCTIONS, BINARY_OP_FUNCTIONS,
+        IS_NULL_FUNCTIONS,
+    },
+    types::ScalarValue,
+};
+
+/// Translates a logical plan into a Substrait plan. The exprs of the plan are expected to
+/// be resolved into the fields of their inputs by the `InputRefRewriter`, as Substrait
+/// refers to fields by index.
+#[derive(Default)]
+pub struct SubstraitProducer {
+    extension_uris: Vec<proto::SimpleExtensionUri>,
+    extensions: Vec<proto::SimpleExtensionDeclaration>,
+}
+
+impl SubstraitProducer {
+    pub fn produce(mut self, plan: PlanRef) -> Result<proto::Plan, SubstraitError> {
+        let (input, names) = self.produce_rel(&plan)?;
+        Ok(proto::Plan {
+            version: Some(proto::Version {
+                producer: "query-engine".to_string(),
+            }),
+            extension_uris: self.extension_uris,
+            extensions: self.extensions,
+            relations: vec![proto::PlanRel::Root(proto::RelRoot { input, names })],
+        })
+    }
+
+    /// the relation of the plan, with the names of its output fields
+    fn produce_rel(&mut self, plan: &PlanRef) -> Result<(proto::Rel, Vec<String>), SubstraitError> {
+        match plan.node_type() {
+            PlanNodeType::LogicalTableScan => {
+                let scan = plan.as_logical_table_scan().unwrap();
+                let filter = scan.filter().map(|f| self.produce_expr(&f)).transpose()?;
+                let rel = proto::ReadRel {
+                    common: None,
+                    base_schema: named_struct(&scan.columns())?,
+                    filter,
+                    read_type: proto::ReadType::NamedTable(proto::NamedTable {
+                        names: vec![scan.table_id()],
+                    }),
+                };
+                Ok((proto::Rel::Read(Box::new(rel)), names(&scan.columns())))
+            }
+            PlanNodeType::LogicalValues => {
+                let values = plan.as_logical_values().unwrap();
+                let expressions = values
+                    .rows()
+                    .iter()
+                    .map(|row| {
+                        let fields = row
+                            .iter()
+                            .map(|e| self.produce_expr(e))
+                            .collect::<Result<_, _>>()?;
+                        Ok(proto::NestedStruct { fields })
+                    })
+                    .collect::<Result<_, SubstraitError>>()?;
+                let rel = proto::ReadRel {
+                    common: None,
+                    base_schema: named_struct(&values.columns())?,
+                    filter: None,
+                    read_type: proto::ReadType::VirtualTable(proto::VirtualTable { expressions }),
+                };
+                Ok((proto::Rel::Read(Box::new(rel)), names(&values.columns())))
+            }
+            PlanNodeType::LogicalFilter => {
+                let filter = plan.as_logical_filter().unwrap();
+                let (input, names) = self.produce_rel(&filter.input())?;
+                let rel = proto::FilterRel {
+                    common: None,
+                    input,
+                    condition: self.produce_expr(&filter.expr())?,
+                };
+                Ok((proto::Rel::Filter(Box::new(rel)), names))
+            }
+            PlanNodeType::LogicalProject => {
+                let project = plan.as_logical_project().unwrap();
+                let (input, input_names) = self.produce_rel(&project.input())?;
+                let exprs = project.exprs();
+                let expressions = exprs
+                    .iter()
+                    .map(|e| self.produce_expr(e))
+                    .collect::<Result<_, _>>()?;
+                // only the exprs are output, not the input fields before them
+                let n = input_names.len();
+                let rel = proto::ProjectRel {
+                    common: Some(emit((n..n + exprs.len()).collect())),
+                    input,
+                    expressions,
+                };
+                let names = exprs.iter().map(|e| name(e, &input_names)).collect();
+                Ok((proto::Rel::Project(Box::new(rel)), names))
+            }
+            PlanNodeType::LogicalAgg => {
+                let agg = plan.as_logical_agg().unwrap();
+                if !agg.grouping_sets().is_empty() {
+                    return Err(SubstraitError::Unsupported("grouping sets".to_string()));
+                }
+                let (input, input_names) = self.produce_rel(&agg.input())?;
+                let group_by = agg.group_by();
+                let mut groupings = vec![];
+                if !group_by.is_empty() {
+                    let grouping_expressions = group_by
+                        .iter()
+                        .map(|e| self.produce_expr(e))
+                        .collect::<Result<_, _>>()?;
+                    groupings.push(proto::Grouping {
+                        grouping_expressions,
+                    });
+                }
+                let agg_funcs = agg.agg_funcs();
+                let measures = agg_funcs
+                    .iter()
+                    .map(|e| match e {
+                        BoundExpr::AggFunc(agg_func) => self.produce_measure(agg_func),
+                        _ => Err(SubstraitError::InvalidPlan(format!(
+                            "{e:?} is not an aggregate function"
+                        ))),
+                    })
+                    .collect::<Result<_, _>>()?;
+                let rel = proto::AggregateRel {
+                    common: None,
+                    input,
+                    groupings,
+                    measures,
+                };
+                let names = group_by
+                    .iter()
+                    .chain(&agg_funcs)
+                    .map(|e| name(e, &input_names))
+                    .collect();
+                Ok((proto::Rel::Aggregate(Box::new(rel)), names))
+            }
+            node_type => Err(SubstraitError::Unsupported(format!("{node_type:?}"))),
+        }
+    }
+
+    fn produce_measure(&mut self, agg: &BoundAggFunc) -> Result<proto::Measure, SubstraitError> {
+        let Some((_, name, uri)) = AGG_FUNCTIONS.iter().find(|(f, _, _)| *f == agg.func) else {
+            return Err(SubstraitError::Unsupported(format!("{:?}", agg.func)));
+        };
+        let sorts = agg
+            .order_by
+            .iter()
+            .map(|order_by| {
+                let direction = match (order_by.asc, order_by.nulls_first) {
+                    (true, true) => proto::SortDirection::AscNullsFirst,
+                    (true, false) => proto::SortDirection::AscNullsLast,
+                    (false, true) => proto::SortDirection::DescNullsFirst,
+                    (false, false) => proto::SortDirection::DescNullsLast,
+                };
+                Ok(proto::SortField {
+                    expr: self.produce_expr(&order_by.expr)?,
+                    direction,
+                })
+            })
+            .collect::<Result<_, SubstraitError>>()?;
+        Ok(proto::Measure {
+            measure: proto::AggregateFunction {
+                function_reference: self.declare_function(name, uri),
+                arguments: self.produce_arguments(&agg.exprs)?,
+                output_type: to_substrait_type(&agg.return_type)?,
+                sorts,
+                phase: proto::AggregationPhase::InitialToResult,
+                invocation: proto::AggregationInvocation::All,
+            },
+            filter: agg
+                .filter
+                .as_ref()
+                .map(|f| self.produce_expr(f))
+                .transpose()?,
+        })
+    }
+
+    fn produce_expr(&mut self, expr: &BoundExpr) -> Result<proto::Expression, SubstraitError> {
+        match expr {
+            BoundExpr::Constant(value) => Ok(proto::Expression::Literal(literal(value)?)),
+            BoundExpr::InputRef(input_ref) => {
+                Ok(proto::Expression::Selection(proto::FieldReference {
+                    direct_reference: proto::ReferenceSegment {
+                        struct_field: proto::StructField {
+                            field: input_ref.index,
+                        },
+                    },
+                    root_reference: proto::Empty {},
+                }))
+            }
+            BoundExpr::BinaryOp(binary_op) => {
+                let Some((_, name, uri)) = BINARY_OP_FUNCTIONS
+                    .iter()
+                    .find(|(op, _, _)| *op == binary_op.op)
+                else {
+                    return Err(SubstraitError::Unsupported(format!("{}", binary_op.op)));
+                };
+                let output_type = binary_op.return_type.as_ref().ok_or_else(|| {
+                    SubstraitError::InvalidPlan(format!("{expr:?} has no return type"))
+                })?;
+                Ok(proto::Expression::ScalarFunction(proto::ScalarFunction {
+                    function_reference: self.declare_function(name, uri),
+                    arguments: self
+                        .produce_arguments(&[*binary_op.left.clone(), *binary_op.right.clone()])?,
+                    output_type: to_substrait_type(output_type)?,
+                }))
+            }
+            BoundExpr::IsNull(is_null) => {
+                let (_, name, uri) = IS_NULL_FUNCTIONS
+                    .iter()
+                    .find(|(negated, _, _)| *negated == is_null.negated)
+                    .unwrap();
+                Ok(proto::Expression::ScalarFunction(proto::ScalarFunction {
+                    function_reference: self.declare_function(name, uri),
+                    arguments: self.produce_arguments(&[*is_null.expr.clone()])?,
+                    output_type: to_substrait_type(&DataType::Boolean)?,
+                }))
+            }
+            BoundExpr::TypeCast(type_cast) => Ok(proto::Expression::Cast(Box::new(proto::Cast {
+                cast_type: to_substrait_type(&type_cast.cast_type)?,
+                input: self.produce_expr(&type_cast.expr)?,
+                failure_behavior: proto::FailureBehavior::ThrowException,
+            }))),
+            BoundExpr::ColumnRef(_) | BoundExpr::AggFunc(_) => Err(SubstraitError::InvalidPlan(
+                format!("{expr:?} is not resolved into an input field"),
+            )),
+        }
+    }
+
+    fn produce_arguments(
+        &mut self,
+        exprs: &[BoundExpr],
+    ) -> Result<Vec<proto::FunctionArgument>, SubstraitError> {
+        exprs
+            .iter()
+            .map(|e| {
+                Ok(proto::FunctionArgument {
+                    value: self.produce_expr(e)?,
+                })
+            })
+            .collect()
+    }
+
+    /// the anchor of the function, declaring it and its extension on first use
+    fn declare_function(&mut self, name: &str, uri: &str) -> u32 {
+        let declared = self
+            .extensions
+            .iter()
+            .filter_map(|e| e.extension_function.as_ref());
+        if let Some(function) = declared.clone().find(|f| f.name == name) {
+            return function.function_anchor;
+        }
+        let extension_uri_reference = match self.extension_uris.iter().find(|u| u.uri == uri) {
+            Some(extension_uri) => extension_uri.extension_uri_anchor,
+            None => {
+                let anchor = self.extension_uris.len() as u32 + 1;
+                self.extension_uris.push(proto::SimpleExtensionUri {
+                    extension_uri_anchor: anchor,
+                    uri: uri.to_string(),
+                });
+                anchor
+            }
+        };
+        let function_anchor = declared.count() as u32 + 1;
+        self.extensions.push(proto::SimpleExtensionDeclaration {
+            extension_function: Some(proto::ExtensionFunction {
+                extension_uri_reference,
+                function_anchor,
+                name: name.to_string(),
+            }),
+        });
+        function_anchor
+    }
+}
+
+fn literal(value: &ScalarValue) -> Result<proto::Literal, SubstraitError> {
+    let literal_type = match value {
+        ScalarValue::Boolean(Some(v)) => proto::LiteralType::Boolean(*v),
+        ScalarValue::Int32(Some(v)) => proto::LiteralType::I32(*v),
+        ScalarValue::Int64(Some(v)) => proto::LiteralType::I64(*v),
+        ScalarValue::Float64(Some(v)) => proto::LiteralType::Fp64(*v),
+        ScalarValue::String(Some(v)) => proto::LiteralType::String(v.clone()),
+        // a null of unknown type has no literal
+        ScalarValue::Null => return Err(SubstraitError::Unsupported("untyped NULL".to_string())),
+        _ => proto::LiteralType::Null(to_substrait_type(&value.data_type())?),
+    };
+    Ok(proto::Literal {
+        nullable: value.is_null(),
+        literal_type,
+    })
+}
+
+fn named_struct(columns: &[ColumnCatalog]) -> Result<proto::NamedStruct, SubstraitError> {
+    Ok(proto::NamedStruct {
+        names: names(columns),
+        struct_type: proto::StructType {
+            types: columns
+                .iter()
+                .map(|c| to_substrait_type(&c.desc.data_type))
+                .collect::<Result<_, _>>()?,
+            nullability: proto::Nullability::Required,
+        },
+    })
+}
+
+fn names(columns: &[ColumnCatalog]) -> Vec<String> {
+    columns.iter().map(|c| c.desc.name.clone()).collect()
+}
+
+/// the name of the field output for the expr, which is the name of the input field it
+/// refers to and `?column?` for exprs other than fields, as the executors name them
+fn name(expr: &BoundExpr, input_names: &[String]) -> String {
+    match expr {
+        BoundExpr::InputRef(input_ref) => input_names[input_ref.index].clone(),
+        _ => "?column?".to_string(),
+    }
+}
+
+fn emit(output_mapping: Vec<usize>) -> proto::RelCommon {
+    proto::RelCommon {
+        direct: None,
+        emit: Some(proto::Emit { output_mapping }),
+    }
+}
@@ -0,0 +1,383 @@
+//! The messages of Substrait plans which the engine produces and consumes, in the JSON
+//! encoding of their protobuf messages. Fields are named in lowerCamelCase, a oneof is
+//! an object keyed by the name of the field set, enums are the names of their values
+//! and 64 bit integers are strings. Fields of the messages which the engine does not use
+//! are left out, and ignored when a plan is read.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Plan {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extension_uris: Vec<SimpleExtensionUri>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<SimpleExtensionDeclaration>,
+    #[serde(default)]
+    pub relations: Vec<PlanRel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    #[serde(default)]
+    pub producer: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleExtensionUri {
+    pub extension_uri_anchor: u32,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleExtensionDeclaration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_function: Option<ExtensionFunction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionFunction {
+    #[serde(default)]
+    pub extension_uri_reference: u32,
+    pub function_anchor: u32,
+    /// the name of the function, optionally followed by its signature like `add:i32_i32`
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlanRel {
+    Root(RelRoot),
+    Rel(Rel),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelRoot {
+    pub input: Rel,
+    /// the names of the output columns
+    #[serde(default)]
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Rel {
+    Read(Box<ReadRel>),
+    Filter(Box<FilterRel>),
+    Project(Box<ProjectRel>),
+    Aggregate(Box<AggregateRel>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelCommon {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direct: Option<Empty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emit: Option<Emit>,
+}
+
+/// the fields a relation outputs, by their index in what it outputs without an emit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Emit {
+    pub output_mapping: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct Empty {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadRel {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub common: Option<RelCommon>,
+    pub base_schema: NamedStruct,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Expression>,
+    #[serde(flatten)]
+    pub read_type: ReadType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadType {
+    NamedTable(NamedTable),
+    VirtualTable(VirtualTable),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedTable {
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VirtualTable {
+    /// the rows of the table
+    #[serde(default)]
+    pub expressions: Vec<NestedStruct>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NestedStruct {
+    #[serde(default)]
+    pub fields: Vec<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRel {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub common: Option<RelCommon>,
+    pub input: Rel,
+    pub condition: Expression,
+}
+
+/// A projection outputs the fields of its input followed by its expressions, unless its
+/// common emits a subset of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectRel {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub common: Option<RelCommon>,
+    pub input: Rel,
+    #[serde(default)]
+    pub expressions: Vec<Expression>,
+}
+
+/// An aggregation outputs the grouping expressions followed by the measures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateRel {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub common: Option<RelCommon>,
+    pub input: Rel,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groupings: Vec<Grouping>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub measures: Vec<Measure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Grouping {
+    #[serde(default)]
+    pub grouping_expressions: Vec<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measure {
+    pub measure: AggregateFunction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateFunction {
+    pub function_reference: u32,
+    #[serde(default)]
+    pub arguments: Vec<FunctionArgument>,
+    pub output_type: Type,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sorts: Vec<SortField>,
+    #[serde(default)]
+    pub phase: AggregationPhase,
+    #[serde(default)]
+    pub invocation: AggregationInvocation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum AggregationPhase {
+    #[serde(rename = "AGGREGATION_PHASE_UNSPECIFIED")]
+    Unspecified,
+    #[default]
+    #[serde(rename = "AGGREGATION_PHASE_INITIAL_TO_RESULT")]
+    InitialToResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum AggregationInvocation {
+    #[serde(rename = "AGGREGATION_INVOCATION_UNSPECIFIED")]
+    Unspecified,
+    #[default]
+    #[serde(rename = "AGGREGATION_INVOCATION_ALL")]
+    All,
+    #[serde(rename = "AGGREGATION_INVOCATION_DISTINCT")]
+    Distinct,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SortField {
+    pub expr: Expression,
+    pub direction: SortDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SortDirection {
+    #[serde(rename = "SORT_DIRECTION_ASC_NULLS_FIRST")]
+    AscNullsFirst,
+    #[serde(rename = "SORT_DIRECTION_ASC_NULLS_LAST")]
+    AscNullsLast,
+    #[serde(rename = "SORT_DIRECTION_DESC_NULLS_FIRST")]
+    DescNullsFirst,
+    #[serde(rename = "SORT_DIRECTION_DESC_NULLS_LAST")]
+    DescNullsLast,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Expression {
+    Literal(Literal),
+    Selection(FieldReference),
+    ScalarFunction(ScalarFunction),
+    Cast(Box<Cast>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Literal {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub nullable: bool,
+    #[serde(flatten)]
+    pub literal_type: LiteralType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LiteralType {
+    Boolean(bool),
+    I32(i32),
+    I64(#[serde(with = "int64")] i64),
+    Fp64(f64),
+    String(String),
+    /// a null of the type
+    Null(Type),
+}
+
+/// A reference to a field of the input of the relation, by its index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldReference {
+    pub direct_reference: ReferenceSegment,
+    #[serde(default)]
+    pub root_reference: Empty,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceSegment {
+    pub struct_field: StructField,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructField {
+    #[serde(default)]
+    pub field: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScalarFunction {
+    pub function_reference: u32,
+    #[serde(default)]
+    pub arguments: Vec<FunctionArgument>,
+    pub output_type: Type,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionArgument {
+    pub value: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cast {
+    #[serde(rename = "type")]
+    pub cast_type: Type,
+    pub input: Expression,
+    #[serde(default)]
+    pub failure_behavior: FailureBehavior,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum FailureBehavior {
+    #[serde(rename = "FAILURE_BEHAVIOR_UNSPECIFIED")]
+    Unspecified,
+    #[serde(rename = "FAILURE_BEHAVIOR_RETURN_NULL")]
+    ReturnNull,
+    #[default]
+    #[serde(rename = "FAILURE_BEHAVIOR_THROW_EXCEPTION")]
+    ThrowException,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedStruct {
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(rename = "struct", default)]
+    pub struct_type: StructType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct StructType {
+    #[serde(default)]
+    pub types: Vec<Type>,
+    #[serde(default)]
+    pub nullability: Nullability,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Type {
+    Bool(TypeKind),
+    I32(TypeKind),
+    I64(TypeKind),
+    Fp64(TypeKind),
+    String(TypeKind),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct TypeKind {
+    #[serde(default)]
+    pub nullability: Nullability,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum Nullability {
+    #[serde(rename = "NULLABILITY_UNSPECIFIED")]
+    Unspecified,
+    #[default]
+    #[serde(rename = "NULLABILITY_NULLABLE")]
+    Nullable,
+    #[serde(rename = "NULLABILITY_REQUIRED")]
+    Required,
+}
+
+/// 64 bit integers are strings in JSON, though plans with numbers are read as well
+mod int64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Int64 {
+            Number(i64),
+            String(String),
+        }
+        match Int64::deserialize(deserializer)? {
+            Int64::Number(value) => Ok(value),
+            Int64::String(value) => value.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
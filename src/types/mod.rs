@@ -1,13 +1,16 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use arrow::{
     array::{
-        new_null_array, ArrayRef, BooleanArray, Float64Array, Int32Array, Int64Array, StringArray,
+        new_null_array, Array, ArrayRef, AsArray, BooleanArray, Float64Array, Int32Array,
+        Int64Array, StringArray,
     },
-    datatypes::DataType,
+    compute::cast,
+    datatypes::{DataType, Float64Type, Int32Type, Int64Type},
+    error::ArrowError,
 };
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum ScalarValue {
     Null,
     Boolean(Option<bool>),
@@ -28,6 +31,62 @@ impl ScalarValue {
             ScalarValue::String(_) => DataType::Utf8,
         }
     }
+
+    /// Create a null value of the given data type
+    pub fn new_null(data_type: &DataType) -> Self {
+        match data_type {
+            DataType::Boolean => ScalarValue::Boolean(None),
+            DataType::Float64 => ScalarValue::Float64(None),
+            DataType::Int32 => ScalarValue::Int32(None),
+            DataType::Int64 => ScalarValue::Int64(None),
+            DataType::Utf8 => ScalarValue::String(None),
+            _ => ScalarValue::Null,
+        }
+    }
+
+    /// The value at the index of the array. Values of types without a scalar value, like
+    /// dates and timestamps, are turned into strings.
+    pub fn try_from_array(array: &ArrayRef, index: usize) -> Result<Self, ArrowError> {
+        if array.is_null(index) {
+            return Ok(Self::new_null(array.data_type()));
+        }
+        Ok(match array.data_type() {
+            DataType::Boolean => array.as_boolean().value(index).into(),
+            DataType::Float64 => array.as_primitive::<Float64Type>().value(index).into(),
+            DataType::Int32 => array.as_primitive::<Int32Type>().value(index).into(),
+            DataType::Int64 => array.as_primitive::<Int64Type>().value(index).into(),
+            DataType::Utf8 => array.as_string::<i32>().value(index).to_string().into(),
+            _ => {
+                let value = cast(&array.slice(index, 1), &DataType::Utf8)?;
+                value.as_string::<i32>().value(0).to_string().into()
+            }
+        })
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(
+            self,
+            ScalarValue::Null
+                | ScalarValue::Boolean(None)
+                | ScalarValue::Float64(None)
+                | ScalarValue::Int32(None)
+                | ScalarValue::Int64(None)
+                | ScalarValue::String(None)
+        )
+    }
+}
+
+impl fmt::Display for ScalarValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScalarValue::Boolean(Some(v)) => write!(f, "{v}"),
+            ScalarValue::Float64(Some(v)) => write!(f, "{v}"),
+            ScalarValue::Int32(Some(v)) => write!(f, "{v}"),
+            ScalarValue::Int64(Some(v)) => write!(f, "{v}"),
+            ScalarValue::String(Some(v)) => write!(f, "{v}"),
+            _ => write!(f, "NULL"),
+        }
+    }
 }
 
 macro_rules! impl_scalar {
@@ -85,3 +144,29 @@ pub fn build_scalar_value_array(scalar_value: &ScalarValue, capacity: usize) ->
         ScalarValue::String(s) => Arc::new(StringArray::from(vec![s.as_deref(); capacity])),
     }
 }
+
+/// Build an array of `data_type` from scalar values, values of other types are treated as null.
+pub fn build_array_from_scalar_values(data_type: &DataType, values: &[ScalarValue]) -> ArrayRef {
+    macro_rules! build_array {
+        ($ARRAY:ty, $SCALAR:ident) => {
+            Arc::new(
+                values
+                    .iter()
+                    .map(|v| match v {
+                        ScalarValue::$SCALAR(v) => v.clone(),
+                        _ => None,
+                    })
+                    .collect::<$ARRAY>(),
+            )
+        };
+    }
+
+    match data_type {
+        DataType::Boolean => build_array!(BooleanArray, Boolean),
+        DataType::Float64 => build_array!(Float64Array, Float64),
+        DataType::Int32 => build_array!(Int32Array, Int32),
+        DataType::Int64 => build_array!(Int64Array, Int64),
+        DataType::Utf8 => build_array!(StringArray, String),
+        _ => new_null_array(data_type, values.len()),
+    }
+}
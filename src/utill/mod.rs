@@ -9,7 +9,7 @@ pub fn pretty_batches(batches: &Vec<RecordBatch>) {
 
 pub fn record_batch_to_string(batch: &RecordBatch) -> Result<String, ArrowError> {
     let mut output = String::new();
-    for row in 0..batch.num_columns() {
+    for row in 0..batch.num_rows() {
         for col in 0..batch.num_columns() {
             if col != 0 {
                 output.push(' ');
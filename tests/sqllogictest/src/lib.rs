@@ -2,9 +2,9 @@
 
 use std::sync::Arc;
 
+use libsqllogictest::{AsyncDB, Runner};
 use query_engine::db::{Database, DatabaseError};
 use query_engine::utill::record_batch_to_string;
-use sqllogictest::{AsyncDB, Runner};
 
 fn init_tables(db: Arc<Database>) {
     const CSV_FILES: &str = "../csv/**/*.csv";
@@ -21,7 +21,7 @@ fn init_tables(db: Arc<Database>) {
 }
 
 pub fn test_run(sqlfile: &str) {
-    let db = Arc::new(Database::new_on_csv());
+    let db = Arc::new(Database::new_in_memory());
     init_tables(db.clone());
     println!("init database with csv tables done for {}", sqlfile);
 
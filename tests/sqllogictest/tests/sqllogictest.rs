@@ -1,5 +1,6 @@
 use glob::glob;
-use libtest_mimic::{Arguments, Test, run_tests};
+use libtest_mimic::{Arguments, Outcome, Test, run_tests};
+use sqllogictest::test_run;
 
 fn main() {
     const SLT_PATTERN: &str = "../slt/**/*.slt";
@@ -28,8 +29,10 @@ fn main() {
 
     run_tests(&args, tests, |test| {
         let file = &test.data;
-        // test_run
-        libtest_mimic::Outcome::Passed
+        match std::panic::catch_unwind(|| test_run(file)) {
+            Ok(()) => Outcome::Passed,
+            Err(_) => Outcome::Failed { msg: None },
+        }
     })
     .exit();
 }